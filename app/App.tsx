@@ -1,16 +1,75 @@
-import { useState, useCallback, useEffect, useMemo } from "react";
+import { useState, useCallback, useEffect, useMemo, useReducer, useRef } from "react";
 import { Terminal } from "./components/Terminal";
+import { TerminalTabs } from "./components/TerminalTabs";
 import { Preview } from "./components/Preview";
 import { SplitView, Pane } from "./components/layout";
 import { useProjectDialog } from "./hooks/useProjectDialog";
 import { useConfig } from "./hooks/useConfig";
+import { useProjectConfig } from "./hooks/useProjectConfig";
+import { useSourceDirCheck } from "./hooks/useSourceDirCheck";
 import { useSphinx } from "./hooks/useSphinx";
 import { useDevConfig } from "./hooks/useDevConfig";
+import { usePreviewSource } from "./hooks/usePreviewSource";
+import { useControlCommands } from "./hooks/useControlCommands";
+import { useBuildDiff } from "./hooks/useBuildDiff";
+import { useSphinxLog } from "./hooks/useSphinxLog";
+import { useSphinxDiagnostics } from "./hooks/useSphinxDiagnostics";
+import { usePreviewOutline } from "./hooks/usePreviewOutline";
+import { useSplitLayout } from "./hooks/useSplitLayout";
+import { useIdleTimeout } from "./hooks/useIdleTimeout";
+import { useOnboarding } from "./hooks/useOnboarding";
+import { useTerminalRecording } from "./hooks/useTerminalRecording";
+import { useRecentProjects } from "./hooks/useRecentProjects";
+import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
 import { mergeConfig } from "./types/devConfig";
+import type { Heading } from "./types/outline";
+import {
+  shouldAutoOpenBrowser,
+  shouldAutoStartSphinx,
+  relativePageLink,
+  rebuildPauseReducer,
+  INITIAL_REBUILD_PAUSE,
+  headingUrl,
+  pinnedReloadUrl,
+} from "./utils/preview";
+import type { PinnedHeading } from "./utils/preview";
+import { logger, setLogLevel } from "./utils/logger";
+import { setClipboard } from "./utils/clipboard";
+import {
+  buildEditorCommand,
+  dispatchExitAction,
+  duplicateCwd,
+  isProjectSwitcherShortcut,
+  terminalReloadAction,
+} from "./utils/terminal";
+import { formatHardReloadResult, runHardReload } from "./utils/hardReload";
+import type { HardReloadResult } from "./utils/hardReload";
+import { LogViewer } from "./components/LogViewer";
+import { BuildDiffPanel } from "./components/BuildDiffPanel";
+import { SphinxLogPanel } from "./components/SphinxLogPanel";
+import { DiagnosticsPanel } from "./components/DiagnosticsPanel";
+import { diagnosticSource } from "./utils/diagnostics";
+import type { ProjectConfig } from "./types/config";
+import type { SphinxDiagnostic } from "./types/sphinx";
+import { OutlineSidebar } from "./components/OutlineSidebar";
+import { Onboarding } from "./components/Onboarding";
+import { ProjectSwitcher } from "./components/ProjectSwitcher";
+import { TerminalExitOverlay } from "./components/TerminalExitOverlay";
+import { ContextMenu } from "./components/ContextMenu";
+import { StatusBar } from "./components/StatusBar";
+import { PreviewStatus } from "./components/PreviewStatus";
+import { PreviewActions } from "./components/PreviewActions";
+import { contextMenuEffect, contextMenuItems } from "./utils/contextMenu";
+import type { ContextMenuAction, ContextMenuState } from "./utils/contextMenu";
 import "./App.css";
 
+const IS_MAC = navigator.userAgent.includes("Mac");
+
 function App() {
-  const [exited, setExited] = useState(false);
+  // シェルの終了コード（終了して待機している間のみ）
+  const [exitCode, setExitCode] = useState<number | null>(null);
+  const exited = exitCode !== null;
 
   // ローカル開発用設定
   const { devConfig, loaded: devConfigLoaded } = useDevConfig();
@@ -18,6 +77,34 @@ function App() {
   // プロジェクト選択
   const { projectPath, setProjectPath, showDialog } = useProjectDialog();
 
+  // 最近のプロジェクトから絞り込んで切り替える（Mod + Shift + P）
+  const recentProjects = useRecentProjects(projectPath);
+  const [showSwitcher, setShowSwitcher] = useState(false);
+  const openProject = useCallback(
+    (path: string) => {
+      setShowSwitcher(false);
+      setProjectPath(path);
+    },
+    [setProjectPath]
+  );
+  useEffect(() => {
+    // ターミナルにキーが渡る前に捕捉する
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (!isProjectSwitcherShortcut(event, IS_MAC)) return;
+      event.preventDefault();
+      event.stopPropagation();
+      setShowSwitcher((show) => !show);
+    };
+    window.addEventListener("keydown", handleKeyDown, true);
+    return () => window.removeEventListener("keydown", handleKeyDown, true);
+  }, []);
+
+  // 初回起動の案内（設定ファイルが無く、dev configでプロジェクトを開かない場合）
+  const onboarding = useOnboarding({
+    devConfigLoaded,
+    hasDevProject: !!devConfig?.project_path,
+  });
+
   // dev configからプロジェクトパスを設定
   useEffect(() => {
     if (devConfigLoaded && devConfig?.project_path && !projectPath) {
@@ -27,134 +114,718 @@ function App() {
 
   // projectPathが変わったら新しいsessionIdを生成（ターミナル再起動）
   const [sessionId, setSessionId] = useState(() => crypto.randomUUID());
+  // ターミナルのPTYセッションID（シェルの再起動時のみ単独で更新）
+  const [terminalSessionId, setTerminalSessionId] = useState(() => crypto.randomUUID());
+  // 複製して開いた追加のターミナル（nullはメインのターミナルを表示）
+  const [extraTerminals, setExtraTerminals] = useState<{ id: string; cwd: string }[]>([]);
+  const [activeTerminalId, setActiveTerminalId] = useState<string | null>(null);
+  // OSC 7で通知された各ターミナルの作業ディレクトリ
+  const terminalCwdsRef = useRef<Record<string, string>>({});
+  // OSC 0/2で設定された各ターミナルのタイトル（表示中のものをヘッダーに出す）
+  const [terminalTitles, setTerminalTitles] = useState<Record<string, string>>({});
+  const handleTitleChange = useCallback((id: string, title: string) => {
+    setTerminalTitles((titles) => ({ ...titles, [id]: title }));
+  }, []);
+  // 開き直した時にメインのターミナルを残すか（terminal.preserve_scrollback_on_reload）
+  const preserveScrollbackRef = useRef<boolean | undefined>(false);
+  // 次にメインのターミナルを起動し直す時に、前のスクロールバックを表示するか
+  const [reprintScrollback, setReprintScrollback] = useState(false);
   useEffect(() => {
     if (projectPath) {
+      // 終了したシェルのセッションは使い回せない
+      const mainCwd = exitCode === null ? terminalCwdsRef.current[terminalSessionId] : undefined;
+      const action = terminalReloadAction(preserveScrollbackRef.current, mainCwd, projectPath);
       setSessionId(crypto.randomUUID());
-      setExited(false);
+      if (action !== "reuse") setTerminalSessionId(crypto.randomUUID());
+      setReprintScrollback(action === "reprint");
+      setExitCode(null);
+      setExtraTerminals([]);
+      setActiveTerminalId(null);
+      terminalCwdsRef.current =
+        action === "reuse" && mainCwd ? { [terminalSessionId]: mainCwd } : {};
+      setTerminalTitles((titles) =>
+        action === "reuse" && titles[terminalSessionId]
+          ? { [terminalSessionId]: titles[terminalSessionId] }
+          : {}
+      );
     }
+    // プロジェクトが変わった時だけ実行する（その時点のメインのターミナルを参照）
+    // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [projectPath]);
-  const { config, loading: configLoading } = useConfig();
+  const { config, loading: configLoading, refresh: refreshConfig } = useConfig(projectPath);
+  // プロジェクトの.khafre.toml
+  const {
+    projectConfig,
+    loaded: projectConfigLoaded,
+    reload: reloadProjectConfig,
+  } = useProjectConfig(projectPath);
 
-  // devConfigによる設定の上書きをマージ
+  // .khafre.toml、devConfigの順に設定の上書きをマージ
   const effectiveConfig = useMemo(() => {
     if (!config) return null;
-    return mergeConfig(config, devConfig?.config);
-  }, [config, devConfig?.config]);
+    return mergeConfig(mergeConfig(config, projectConfig ?? undefined), devConfig?.config);
+  }, [config, projectConfig, devConfig?.config]);
+  preserveScrollbackRef.current = effectiveConfig?.terminal.preserve_scrollback_on_reload;
+
+  // ログレベルを設定に追従
+  const logLevel = effectiveConfig?.log.level;
+  useEffect(() => {
+    if (logLevel) setLogLevel(logLevel);
+  }, [logLevel]);
+  const [showLogs, setShowLogs] = useState(false);
 
   // sphinx-autobuild
   const {
     previewUrl,
     isRunning: sphinxRunning,
     error: sphinxError,
+    buildCount,
+    health: sphinxHealth,
+    portFallback,
+    interpreterFallback,
     start: startSphinx,
     stop: stopSphinx,
+    restart: restartSphinx,
+    restartWith: restartSphinxWith,
     openInBrowser,
+    revealBuildDir,
+    copyIssueReport,
   } = useSphinx({ sessionId, projectPath, config: effectiveConfig });
 
-  const handleExit = useCallback((_code: number) => {
-    setExited(true);
+  // 自動再ビルドの反映を一時停止（停止中のビルドは数えるだけで、再開時に最新を読み込む）
+  const [rebuildPause, dispatchRebuildPause] = useReducer(
+    rebuildPauseReducer,
+    INITIAL_REBUILD_PAUSE
+  );
+  useEffect(() => {
+    if (buildCount > 0) dispatchRebuildPause({ type: "built" });
+  }, [buildCount]);
+
+  // sphinx-autobuildの出力（パネルを開いている間だけ購読する）
+  const [showSphinxLog, setShowSphinxLog] = useState(false);
+  const sphinxLog = useSphinxLog(sessionId, showSphinxLog);
+
+  // source_dirにconf.pyが無いプロジェクト（docs以外の構成）ではconf.pyの場所を提案する
+  const sourceDirCheck = useSourceDirCheck({
+    projectPath,
+    sourceDir: projectConfigLoaded ? effectiveConfig?.sphinx.source_dir : undefined,
+    enabled: effectiveConfig?.preview.enabled ?? true,
+    onSaved: reloadProjectConfig,
+  });
+
+  // 直近のビルドの警告・エラー
+  const diagnostics = useSphinxDiagnostics(sessionId);
+  const [showDiagnostics, setShowDiagnostics] = useState(false);
+
+  // 一時ディレクトリへの再ビルドと差分表示
+  const buildDiff = useBuildDiff({ projectPath, config: effectiveConfig });
+  const [showBuildDiff, setShowBuildDiff] = useState(false);
+  const runBuildDiff = buildDiff.run;
+  const handleBuildDiff = useCallback(() => {
+    setShowBuildDiff(true);
+    runBuildDiff();
+  }, [runBuildDiff]);
+
+  // externalモードまたはopen_browser_on_ready有効時は、サーバー起動後に一度だけブラウザを開く
+  const previewEnabled = effectiveConfig?.preview.enabled ?? true;
+  const previewMode = effectiveConfig?.preview.mode ?? "embedded";
+  const openOnReady = effectiveConfig?.preview.open_browser_on_ready ?? false;
+  const browserOpenedRef = useRef(false);
+  useEffect(() => {
+    browserOpenedRef.current = false;
+  }, [sessionId]);
+  useEffect(() => {
+    const alreadyOpened = browserOpenedRef.current;
+    if (shouldAutoOpenBrowser({ mode: previewMode, openOnReady, previewUrl, alreadyOpened })) {
+      browserOpenedRef.current = true;
+      openInBrowser();
+    }
+  }, [previewMode, openOnReady, previewUrl, openInBrowser]);
+
+  // プレビュー中のページに対応するソースをエディタで開く
+  const [pageUrl, setPageUrl] = useState<string | null>(null);
+  // 制御ソケットから指定されたページ
+  const [navigateUrl, setNavigateUrl] = useState<string | null>(null);
+  const [reloadToken, setReloadToken] = useState(0);
+  // アウトラインで選んだ見出し（再ビルドで読み直しても同じ位置を表示する）
+  const [pinnedHeading, setPinnedHeading] = useState<PinnedHeading | null>(null);
+  useEffect(() => {
+    setPageUrl(null);
+    setNavigateUrl(null);
+    setPinnedHeading(null);
+  }, [previewUrl]);
+  // 表示中のページを読み込み直す（iframeを作り直すのでキャッシュされた古いページを残さない）
+  const reloadPreview = useCallback(() => {
+    if (pageUrl) setNavigateUrl(pageUrl);
+    setReloadToken((token) => token + 1);
+  }, [pageUrl]);
+  const navigatePreview = useCallback((url: string) => {
+    setNavigateUrl(url);
+    setReloadToken((token) => token + 1);
+  }, []);
+
+  // プレビューを操作しないまま一定時間経ったらsphinx-autobuildを止める（sphinx.idle_timeout_secs）
+  const [idleStopped, setIdleStopped] = useState(false);
+  const markPreviewActivity = useIdleTimeout({
+    timeoutSecs: effectiveConfig?.sphinx.idle_timeout_secs ?? 0,
+    active: sphinxRunning,
+    onTimeout: () => {
+      logger.info("Stopping sphinx-autobuild after the idle timeout");
+      stopSphinx();
+      setIdleStopped(true);
+    },
+  });
+  useEffect(() => {
+    if (sphinxRunning) setIdleStopped(false);
+  }, [sphinxRunning]);
+  useEffect(() => {
+    setIdleStopped(false);
+  }, [sessionId]);
+  // ページ移動と再ビルド（ソースの編集）もプレビューの利用とみなす
+  useEffect(() => {
+    markPreviewActivity();
+  }, [pageUrl, buildCount, markPreviewActivity]);
+  const sourcePath = usePreviewSource({
+    pageUrl,
+    baseUrl: previewUrl,
+    projectPath,
+    sourceDir: effectiveConfig?.sphinx.source_dir ?? null,
+  });
+  const editorCommand = effectiveConfig?.editor.command;
+  const editorLineFormat = effectiveConfig?.editor.line_format;
+  // エディタを開けなかった理由（少し経つと消す）
+  const [editorNotice, setEditorNotice] = useState<string | null>(null);
+  useEffect(() => {
+    if (!editorNotice) return;
+    const timer = window.setTimeout(() => setEditorNotice(null), 5000);
+    return () => window.clearTimeout(timer);
+  }, [editorNotice]);
+
+  // メインのターミナルにエディタのコマンドを入力する
+  // vimやビルドなどが動いている間はそのプログラムへの入力になってしまうので送らない
+  const openInEditor = useCallback(
+    async (path: string, line?: number) => {
+      if (!editorCommand) return;
+      try {
+        const busy = await invoke<boolean>("terminal_has_foreground_job", {
+          sessionId: terminalSessionId,
+        });
+        if (busy) {
+          setEditorNotice("Terminal is busy — quit the running program to open the editor");
+          return;
+        }
+        const data = buildEditorCommand(editorCommand, path, line, editorLineFormat);
+        await invoke("pty_write", { sessionId: terminalSessionId, data });
+      } catch (e) {
+        logger.error("Failed to open the editor:", e);
+      }
+    },
+    [terminalSessionId, editorCommand, editorLineFormat]
+  );
+
+  const handleEditSource = useCallback(() => {
+    if (sourcePath) openInEditor(sourcePath);
+  }, [sourcePath, openInEditor]);
+
+  // 表示中のターミナルにフォーカスして直前のコマンドを再実行（ターミナルごとの回数）
+  const [rerunTokens, setRerunTokens] = useState<Record<string, number>>({});
+  const handleRerun = useCallback(() => {
+    const id = activeTerminalId ?? terminalSessionId;
+    setRerunTokens((tokens) => ({ ...tokens, [id]: (tokens[id] ?? 0) + 1 }));
+  }, [activeTerminalId, terminalSessionId]);
+
+  // 警告の場所をターミナルのエディタで開く
+  const handleOpenDiagnostic = useCallback(
+    (diagnostic: SphinxDiagnostic) => {
+      const { path, line } = diagnosticSource(diagnostic);
+      openInEditor(path, line);
+    },
+    [openInEditor]
+  );
+
+  // プロジェクトのパスとプレビューのURLの右クリックメニュー
+  const [contextMenu, setContextMenu] = useState<ContextMenuState | null>(null);
+  const closeContextMenu = useCallback(() => setContextMenu(null), []);
+  const handleContextMenuAction = useCallback(
+    (action: ContextMenuAction) => {
+      if (!contextMenu) return;
+      const effect = contextMenuEffect(action, contextMenu.value);
+      switch (effect.type) {
+        case "copy":
+          setClipboard(effect.text);
+          break;
+        case "reveal":
+          invoke("reveal_project_dir", { projectPath: effect.path }).catch((e) =>
+            logger.error("Failed to open the project folder:", e)
+          );
+          break;
+        case "open_url":
+          invoke("open_in_browser", { url: effect.url }).catch((e) =>
+            logger.error("Failed to open the preview in the browser:", e)
+          );
+          break;
+      }
+    },
+    [contextMenu]
+  );
+
+  // 表示中ページのリンクを共有用にコピー
+  const handleCopyLink = useCallback(
+    (url: string, relative: boolean) => {
+      const link = relative && previewUrl ? relativePageLink(url, previewUrl) : url;
+      if (link !== null) setClipboard(link);
+    },
+    [previewUrl]
+  );
+
+  // 表示中ページの見出し一覧（preview.outline）
+  const showOutline =
+    (effectiveConfig?.preview.outline ?? false) && previewMode === "embedded" && !!previewUrl;
+  const headings = usePreviewOutline({
+    sourcePath,
+    enabled: showOutline,
+    refreshToken: buildCount,
+  });
+  const handleSelectHeading = useCallback(
+    (heading: Heading) => {
+      if (!pageUrl) return;
+      setPinnedHeading({ page: pageUrl.split("#")[0], anchor: heading.anchor });
+      setNavigateUrl(headingUrl(pageUrl, heading.anchor));
+    },
+    [pageUrl]
+  );
+  // livereloadによる再読み込みはスクロール位置を戻すだけなので、
+  // 再ビルド後の見出し一覧が届いたら選んだ見出しの位置で開き直す
+  const pinnedBuildRef = useRef(buildCount);
+  useEffect(() => {
+    if (pinnedBuildRef.current === buildCount) return;
+    pinnedBuildRef.current = buildCount;
+    if (!pinnedHeading || !pageUrl || rebuildPause.paused) return;
+    const url = pinnedReloadUrl(pinnedHeading, pageUrl, headings);
+    if (url === null) {
+      // 別のページに移った
+      setPinnedHeading(null);
+      return;
+    }
+    if (url === pinnedHeading.page) {
+      logger.info("Pinned heading no longer exists:", pinnedHeading.anchor);
+      setPinnedHeading(null);
+    }
+    setNavigateUrl(url);
+    setReloadToken((token) => token + 1);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [headings]);
+
+  // プレビューとターミナルの分割（比率と並べ方は保存される。比率はui.split_ratioにも保存）
+  const splitLayout = useSplitLayout(effectiveConfig?.ui.split_ratio);
+
+  // 制御ソケット経由のコマンド
+  useControlCommands(previewUrl, {
+    openProject,
+    startPreview: startSphinx,
+    stopPreview: stopSphinx,
+    reloadPreview,
+    navigatePreview,
+    setSplitRatio: splitLayout.setRatio,
+    setSplitOrientation: splitLayout.setOrientation,
+  });
+
+  // シェル終了時の動作（terminal.on_exit）
+  const onExitAction = effectiveConfig?.terminal.on_exit;
+  const handleExit = useCallback(
+    (code: number) => {
+      dispatchExitAction(onExitAction, {
+        idle: () => setExitCode(code),
+        respawn: () => {
+          setReprintScrollback(false);
+          setTerminalSessionId(crypto.randomUUID());
+        },
+        close: () => {
+          invoke("exit_app").catch((e) => logger.error("Failed to exit app:", e));
+        },
+      });
+    },
+    [onExitAction]
+  );
+  // 終了したシェルを新しいセッションで起動し直す
+  const handleRestart = useCallback(() => {
+    setExitCode(null);
+    setReprintScrollback(false);
+    setTerminalSessionId(crypto.randomUUID());
+  }, []);
+
+  // 設定の読み直しとターミナル・sphinx-autobuildの再起動をまとめて行う（おかしな状態からの復旧用）
+  const [hardReloading, setHardReloading] = useState(false);
+  const [hardReloadResult, setHardReloadResult] = useState<HardReloadResult | null>(null);
+  const handleHardReload = useCallback(async () => {
+    setHardReloading(true);
+    setHardReloadResult(null);
+    // 読み直した設定（この時点ではまだ描画に反映されていないので、sphinx-autobuildにはこれを渡す）
+    let reloadedConfig: ProjectConfig | null = null;
+    const result = await runHardReload([
+      // theme_fileなども読み直すので、テーマも反映し直される
+      {
+        label: "config",
+        run: async () => {
+          const nextConfig = await refreshConfig();
+          const nextProjectConfig = await reloadProjectConfig();
+          reloadedConfig = mergeConfig(
+            mergeConfig(nextConfig, nextProjectConfig ?? undefined),
+            devConfig?.config
+          );
+        },
+      },
+      {
+        label: "terminal",
+        run: async () => {
+          // 実行中のコマンド（エディタなど）は中断しない
+          const busy =
+            !exited &&
+            (await invoke<boolean>("terminal_has_foreground_job", {
+              sessionId: terminalSessionId,
+            }));
+          if (busy) return "skipped";
+          handleRestart();
+        },
+      },
+      {
+        label: "preview",
+        run: async () => {
+          if (!reloadedConfig?.preview.enabled && !sphinxRunning) return "skipped";
+          // source_dirやポート、環境変数なども読み直した設定で起動し直す
+          const error = reloadedConfig ? await restartSphinxWith(reloadedConfig) : null;
+          if (error) throw new Error(error);
+        },
+      },
+    ]);
+    if (result.type === "failed") logger.error(formatHardReloadResult(result));
+    else logger.info(formatHardReloadResult(result));
+    setHardReloadResult(result);
+    setHardReloading(false);
+  }, [
+    refreshConfig,
+    reloadProjectConfig,
+    devConfig?.config,
+    exited,
+    terminalSessionId,
+    handleRestart,
+    sphinxRunning,
+    restartSphinxWith,
+  ]);
+  useEffect(() => {
+    if (!hardReloadResult) return;
+    const timer = window.setTimeout(() => setHardReloadResult(null), 5000);
+    return () => window.clearTimeout(timer);
+  }, [hardReloadResult]);
+
+  // 表示中のターミナルと同じ作業ディレクトリで新しいターミナルを開く
+  const handleDuplicateTerminal = useCallback(() => {
+    if (!projectPath) return;
+    const cwd = duplicateCwd(
+      terminalCwdsRef.current[activeTerminalId ?? terminalSessionId],
+      projectPath
+    );
+    const id = crypto.randomUUID();
+    setExtraTerminals((terminals) => [...terminals, { id, cwd }]);
+    setActiveTerminalId(id);
+  }, [projectPath, activeTerminalId, terminalSessionId]);
+  const handleCloseTerminal = useCallback((id: string) => {
+    setExtraTerminals((terminals) => terminals.filter((terminal) => terminal.id !== id));
+    setActiveTerminalId((active) => (active === id ? null : active));
+    delete terminalCwdsRef.current[id];
   }, []);
 
+  // 設定中のカラースキーム（theme_fileから読み込んだものを含む）をAlacritty TOMLとして保存
+  const colorScheme = effectiveConfig?.terminal.color_scheme;
+  const handleExportTheme = useCallback(async () => {
+    if (!colorScheme) return;
+    try {
+      const path = await save({
+        title: "Export Terminal Theme",
+        defaultPath: "khafre.toml",
+        filters: [{ name: "Alacritty theme", extensions: ["toml"] }],
+      });
+      if (path) await invoke("export_alacritty_theme", { scheme: colorScheme, path });
+    } catch (e) {
+      logger.error("Failed to export theme:", e);
+    }
+  }, [colorScheme]);
+
+  const activeTerminalTitle = terminalTitles[activeTerminalId ?? terminalSessionId];
+
+  // ターミナル出力の録画（閉じたターミナルの録画は終わったものとして扱う）
+  const recording = useTerminalRecording();
+  const recordingId =
+    recording.recordingId &&
+    [terminalSessionId, ...extraTerminals.map(({ id }) => id)].includes(recording.recordingId)
+      ? recording.recordingId
+      : null;
+
   // 起動時にプロジェクト選択ダイアログを表示（dev configが無い場合のみ）
+  // 初回起動の案内を表示する場合は、案内のボタンから開く
+  const onboardingPending = onboarding.checking || onboarding.show;
   useEffect(() => {
-    if (devConfigLoaded && !projectPath && !devConfig?.project_path) {
+    if (devConfigLoaded && !onboardingPending && !projectPath && !devConfig?.project_path) {
       showDialog();
     }
     // showDialogは安定した参照なので依存配列から除外
     // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [devConfigLoaded, projectPath, devConfig]);
+  }, [devConfigLoaded, onboardingPending, projectPath, devConfig]);
+
+  // 初回起動の案内から、ターミナルのエディタで設定ファイルを開く
+  const onboardingConfigPath = onboarding.configPath;
+  const handleEditConfig = useCallback(() => {
+    if (!onboardingConfigPath) return;
+    openInEditor(onboardingConfigPath);
+    setActiveTerminalId(null);
+  }, [onboardingConfigPath, openInEditor]);
 
   // config読み込み完了時にsphinx-autobuildを自動起動
   const autoStartSphinx = devConfig?.auto_start_sphinx ?? true;
   useEffect(() => {
-    if (effectiveConfig && projectPath && !sphinxRunning && autoStartSphinx) {
+    if (
+      shouldAutoStartSphinx({
+        // .khafre.tomlを読み込むまでは起動しない（source_dirなどが変わるため）
+        config: projectConfigLoaded ? effectiveConfig : null,
+        projectPath,
+        isRunning: sphinxRunning,
+        autoStart: autoStartSphinx,
+      })
+    ) {
       startSphinx();
     }
     // 初回起動時のみ実行、sphinxRunning/startSphinxの変更では再実行しない
     // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [effectiveConfig, projectPath, autoStartSphinx]);
+  }, [effectiveConfig, projectConfigLoaded, projectPath, autoStartSphinx]);
 
-  return (
-    <main className="h-screen w-screen flex flex-col bg-gray-900">
-      <header className="h-8 bg-gray-800 flex items-center justify-between px-4 text-gray-300 text-sm shrink-0">
-        <span className="flex items-center gap-2">
-          Khafre
-          {projectPath && (
-            <span className="text-gray-500 text-xs truncate max-w-md">{projectPath}</span>
-          )}
-        </span>
-        <div className="flex items-center gap-4">
-          {configLoading && <span className="text-yellow-400 text-xs">Loading...</span>}
-          {sphinxRunning && !previewUrl && (
-            <span className="text-yellow-400 text-xs">Building...</span>
-          )}
-          {sphinxRunning && previewUrl && (
-            <span className="text-green-400 text-xs">Preview Running</span>
-          )}
-          {sphinxError && (
-            <span className="text-red-400 text-xs truncate max-w-xs">{sphinxError}</span>
-          )}
-          {sphinxRunning ? (
-            <>
-              <button
-                onClick={openInBrowser}
-                className="px-2 py-0.5 bg-blue-700 hover:bg-blue-600 rounded text-xs transition-colors"
-              >
-                Open in Browser
-              </button>
-              <button
-                onClick={stopSphinx}
-                className="px-2 py-0.5 bg-red-700 hover:bg-red-600 rounded text-xs transition-colors"
-              >
-                Stop Preview
-              </button>
-            </>
+  // ターミナル（プレビュー無効時はこれだけを表示）
+  // 非表示のターミナルもサイズを保つため、重ねてvisibilityだけ切り替える
+  const terminalLayer = (active: boolean) =>
+    `absolute inset-0 ${active ? "" : "invisible pointer-events-none"}`;
+  const terminalPane = (
+    <Pane className="flex flex-col">
+      {projectPath && effectiveConfig && (
+        <TerminalTabs
+          tabs={[
+            { id: terminalSessionId, label: "Terminal", closable: false },
+            ...extraTerminals.map(({ id, cwd }) => ({ id, label: cwd, closable: true })),
+          ]}
+          activeId={activeTerminalId ?? terminalSessionId}
+          onSelect={(id) => setActiveTerminalId(id === terminalSessionId ? null : id)}
+          onDuplicate={handleDuplicateTerminal}
+          onClose={handleCloseTerminal}
+          onRerun={handleRerun}
+          recordingId={recordingId}
+          onToggleRecording={() =>
+            recordingId ? recording.stop() : recording.start(activeTerminalId ?? terminalSessionId)
+          }
+          onExportTheme={colorScheme ? handleExportTheme : undefined}
+        />
+      )}
+      <div className="relative flex-1 min-h-0">
+        <div className={terminalLayer(activeTerminalId === null)}>
+          {projectPath && effectiveConfig ? (
+            <Terminal
+              sessionId={terminalSessionId}
+              cwd={projectPath}
+              config={effectiveConfig.terminal}
+              reprintScrollback={reprintScrollback}
+              rerunToken={rerunTokens[terminalSessionId]}
+              onExit={handleExit}
+              onCwdChange={(cwd) => (terminalCwdsRef.current[terminalSessionId] = cwd)}
+              onTitleChange={(title) => handleTitleChange(terminalSessionId, title)}
+            />
           ) : (
-            effectiveConfig && (
-              <button
-                onClick={startSphinx}
-                className="px-2 py-0.5 bg-green-700 hover:bg-green-600 rounded text-xs transition-colors"
-              >
-                Start Preview
-              </button>
-            )
+            <div className="flex items-center justify-center h-full text-gray-400">
+              Select a project to start terminal
+            </div>
           )}
-          <button
-            onClick={showDialog}
-            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
-          >
-            Open Project
-          </button>
+          {exitCode !== null && <TerminalExitOverlay code={exitCode} onRestart={handleRestart} />}
         </div>
-      </header>
+        {effectiveConfig &&
+          extraTerminals.map(({ id, cwd }) => (
+            <div key={id} className={terminalLayer(activeTerminalId === id)}>
+              <Terminal
+                sessionId={id}
+                cwd={cwd}
+                config={effectiveConfig.terminal}
+                rerunToken={rerunTokens[id]}
+                onExit={() => handleCloseTerminal(id)}
+                onCwdChange={(dir) => (terminalCwdsRef.current[id] = dir)}
+                onTitleChange={(title) => handleTitleChange(id, title)}
+              />
+            </div>
+          ))}
+      </div>
+    </Pane>
+  );
+
+  return (
+    <main className="relative h-screen w-screen flex flex-col bg-gray-900">
+      <StatusBar
+        mode={effectiveConfig?.ui.status_bar ?? "full"}
+        projectPath={projectPath}
+        terminalTitle={activeTerminalTitle}
+        onProjectContextMenu={(x, y) => {
+          if (projectPath) setContextMenu({ target: "project_path", value: projectPath, x, y });
+        }}
+        loading={configLoading}
+        editorNotice={editorNotice}
+        hardReloadResult={hardReloadResult}
+        status={
+          previewEnabled && (
+            <PreviewStatus
+              isRunning={sphinxRunning}
+              previewUrl={previewUrl}
+              health={sphinxHealth}
+              portFallback={portFallback}
+              interpreterFallback={interpreterFallback}
+              rebuildPause={rebuildPause}
+              error={sphinxError}
+              sourceDir={effectiveConfig?.sphinx.source_dir}
+              missingSourceDir={sourceDirCheck.missing}
+              onUseSourceDir={sourceDirCheck.save}
+              onChooseSourceDir={sourceDirCheck.choose}
+            />
+          )
+        }
+        previewActions={
+          previewEnabled && (
+            <PreviewActions
+              isRunning={sphinxRunning}
+              error={sphinxError}
+              paused={rebuildPause.paused}
+              ready={!!projectPath && !!effectiveConfig}
+              canStart={!!effectiveConfig}
+              diagnosticCount={diagnostics.length}
+              buildDiffRunning={buildDiff.isRunning}
+              onCopyIssueReport={copyIssueReport}
+              onOpenInBrowser={openInBrowser}
+              onTogglePause={() => dispatchRebuildPause({ type: "toggle" })}
+              onRestart={restartSphinx}
+              onStop={stopSphinx}
+              onStart={startSphinx}
+              onRevealBuildDir={revealBuildDir}
+              onToggleDiagnostics={() => setShowDiagnostics((v) => !v)}
+              onToggleBuildLog={() => setShowSphinxLog((v) => !v)}
+              onBuildDiff={handleBuildDiff}
+            />
+          )
+        }
+        hardReloading={hardReloading}
+        onHardReload={handleHardReload}
+        onToggleLogs={() => setShowLogs((v) => !v)}
+        onOpenProject={showDialog}
+        onShowRecent={recentProjects.length > 0 ? () => setShowSwitcher(true) : undefined}
+        recentShortcut={`${IS_MAC ? "Cmd" : "Ctrl"}+Shift+P`}
+      />
       <div className="flex-1 min-h-0">
-        <SplitView
-          left={
-            <Pane>
-              <Preview url={previewUrl} isBuilding={sphinxRunning && !previewUrl} />
-            </Pane>
-          }
-          right={
-            <Pane>
-              {projectPath && effectiveConfig && !exited ? (
-                <Terminal
-                  sessionId={sessionId}
-                  cwd={projectPath}
-                  shell={effectiveConfig.terminal.shell}
-                  fontFamily={effectiveConfig.terminal.font_family}
-                  fontSize={effectiveConfig.terminal.font_size}
-                  colorScheme={effectiveConfig.terminal.color_scheme}
-                  onExit={handleExit}
-                />
-              ) : (
-                <div className="flex items-center justify-center h-full text-gray-400">
-                  {exited ? "Terminal session ended" : "Select a project to start terminal"}
+        {previewEnabled ? (
+          <SplitView
+            ratio={splitLayout.ratio}
+            orientation={splitLayout.orientation}
+            onRatioChange={splitLayout.setRatio}
+            left={
+              <Pane className="flex">
+                {showOutline && (
+                  <OutlineSidebar
+                    headings={headings}
+                    pinnedAnchor={pinnedHeading?.anchor ?? null}
+                    onSelect={handleSelectHeading}
+                  />
+                )}
+                <div
+                  className="flex-1 min-w-0 h-full"
+                  onPointerEnter={markPreviewActivity}
+                  onPointerMove={markPreviewActivity}
+                  onFocus={markPreviewActivity}
+                >
+                  <Preview
+                    url={navigateUrl ?? previewUrl}
+                    isBuilding={sphinxRunning && !previewUrl}
+                    idleStopped={idleStopped && !sphinxRunning}
+                    onResume={startSphinx}
+                    mode={previewMode}
+                    onOpenInBrowser={openInBrowser}
+                    onNavigate={setPageUrl}
+                    onCopyLink={handleCopyLink}
+                    reloadToken={reloadToken}
+                    onRefresh={reloadPreview}
+                    onUrlContextMenu={(url, x, y) =>
+                      setContextMenu({ target: "preview_url", value: url, x, y })
+                    }
+                    paused={rebuildPause.paused}
+                    diagnostics={diagnostics}
+                    onOpenDiagnostic={editorCommand && !exited ? handleOpenDiagnostic : undefined}
+                    onEditSource={
+                      sourcePath && editorCommand && !exited ? handleEditSource : undefined
+                    }
+                  />
                 </div>
-              )}
-            </Pane>
+              </Pane>
+            }
+            right={terminalPane}
+          />
+        ) : (
+          terminalPane
+        )}
+      </div>
+      {showBuildDiff && (
+        <BuildDiffPanel
+          changes={buildDiff.changes}
+          isRunning={buildDiff.isRunning}
+          error={buildDiff.error}
+          onRerun={runBuildDiff}
+          onClose={() => setShowBuildDiff(false)}
+        />
+      )}
+      {showDiagnostics && (
+        <DiagnosticsPanel
+          diagnostics={diagnostics}
+          onOpen={editorCommand && !exited ? handleOpenDiagnostic : undefined}
+          onClose={() => setShowDiagnostics(false)}
+        />
+      )}
+      {showSphinxLog && (
+        <SphinxLogPanel lines={sphinxLog} onClose={() => setShowSphinxLog(false)} />
+      )}
+      {showLogs && <LogViewer onClose={() => setShowLogs(false)} />}
+      {showSwitcher && (
+        <ProjectSwitcher
+          recent={recentProjects}
+          currentPath={projectPath}
+          onSelect={openProject}
+          onBrowse={() => {
+            setShowSwitcher(false);
+            showDialog();
+          }}
+          onClose={() => setShowSwitcher(false)}
+        />
+      )}
+      {contextMenu && (
+        <ContextMenu
+          items={contextMenuItems(contextMenu.target)}
+          x={contextMenu.x}
+          y={contextMenu.y}
+          onSelect={handleContextMenuAction}
+          onClose={closeContextMenu}
+        />
+      )}
+      {onboarding.show && (
+        <Onboarding
+          projectOpened={!!projectPath}
+          previewRunning={sphinxRunning}
+          previewEnabled={previewEnabled}
+          onOpenProject={showDialog}
+          onEditConfig={
+            projectPath && editorCommand && !exited && onboardingConfigPath
+              ? handleEditConfig
+              : undefined
           }
+          onStartPreview={effectiveConfig && !sphinxRunning ? startSphinx : undefined}
+          onDismiss={onboarding.complete}
         />
-      </div>
+      )}
     </main>
   );
 }
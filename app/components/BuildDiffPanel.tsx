@@ -0,0 +1,65 @@
+import type { ChangeKind, ChangedFile } from "../types/buildDiff";
+
+const CHANGE_LABEL: Record<ChangeKind, { mark: string; className: string }> = {
+  added: { mark: "A", className: "text-green-400" },
+  removed: { mark: "D", className: "text-red-400" },
+  modified: { mark: "M", className: "text-yellow-400" },
+};
+
+interface BuildDiffPanelProps {
+  changes: ChangedFile[] | null;
+  isRunning: boolean;
+  error: string | null;
+  onRerun: () => void;
+  onClose: () => void;
+}
+
+/** 再ビルド結果と現在のビルド出力の差分一覧 */
+export function BuildDiffPanel({
+  changes,
+  isRunning,
+  error,
+  onRerun,
+  onClose,
+}: BuildDiffPanelProps) {
+  return (
+    <div className="h-48 shrink-0 flex flex-col bg-gray-950 border-t border-gray-700 text-xs">
+      <div className="h-7 flex items-center justify-between px-3 bg-gray-800 text-gray-300">
+        <span>
+          Build Diff
+          {changes && !isRunning && (
+            <span className="text-gray-500 ml-2">{changes.length} changed files</span>
+          )}
+        </span>
+        <div className="flex items-center gap-2">
+          <button
+            onClick={onRerun}
+            disabled={isRunning}
+            className="hover:text-white disabled:opacity-50"
+          >
+            Rerun
+          </button>
+          <button onClick={onClose} className="hover:text-white">
+            Close
+          </button>
+        </div>
+      </div>
+      <div className="flex-1 overflow-y-auto px-3 py-1 font-mono">
+        {isRunning && <div className="text-gray-400">Building into a temporary directory...</div>}
+        {!isRunning && error && <div className="text-red-400 whitespace-pre-wrap">{error}</div>}
+        {!isRunning && changes?.length === 0 && (
+          <div className="text-gray-400">No changes compared to the current build output</div>
+        )}
+        {!isRunning &&
+          changes?.map((file) => (
+            <div key={file.path} className="text-gray-300">
+              <span className={CHANGE_LABEL[file.change].className}>
+                {CHANGE_LABEL[file.change].mark}
+              </span>{" "}
+              {file.path}
+            </div>
+          ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,59 @@
+import { useEffect, useRef } from "react";
+import type { ContextMenuAction, ContextMenuItem } from "../utils/contextMenu";
+
+interface ContextMenuProps {
+  items: ContextMenuItem[];
+  /** 右クリックした位置（ウィンドウ座標） */
+  x: number;
+  y: number;
+  onSelect: (action: ContextMenuAction) => void;
+  onClose: () => void;
+}
+
+/** 右クリックした位置に出すメニュー（外側のクリック、Escape、ウィンドウのフォーカスが外れたら閉じる） */
+export function ContextMenu({ items, x, y, onSelect, onClose }: ContextMenuProps) {
+  const menuRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    const handlePointerDown = (event: PointerEvent) => {
+      if (!menuRef.current?.contains(event.target as Node)) onClose();
+    };
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key !== "Escape") return;
+      event.preventDefault();
+      event.stopPropagation();
+      onClose();
+    };
+    window.addEventListener("pointerdown", handlePointerDown, true);
+    window.addEventListener("keydown", handleKeyDown, true);
+    window.addEventListener("blur", onClose);
+    return () => {
+      window.removeEventListener("pointerdown", handlePointerDown, true);
+      window.removeEventListener("keydown", handleKeyDown, true);
+      window.removeEventListener("blur", onClose);
+    };
+  }, [onClose]);
+
+  return (
+    <div
+      ref={menuRef}
+      role="menu"
+      style={{ left: x, top: y }}
+      className="fixed z-40 min-w-40 rounded bg-gray-800 py-1 text-xs text-gray-200 shadow-xl border border-gray-700"
+    >
+      {items.map((item) => (
+        <button
+          key={item.action}
+          role="menuitem"
+          onClick={() => {
+            onSelect(item.action);
+            onClose();
+          }}
+          className="block w-full px-3 py-1 text-left hover:bg-gray-700"
+        >
+          {item.label}
+        </button>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,58 @@
+import type { Severity, SphinxDiagnostic } from "../types/sphinx";
+import { countBySeverity } from "../utils/diagnostics";
+
+const SEVERITY_CLASS: Record<Severity, string> = {
+  warning: "text-yellow-400",
+  error: "text-red-400",
+};
+
+interface DiagnosticsPanelProps {
+  diagnostics: SphinxDiagnostic[];
+  /** クリックした診断をエディタで開く（未指定なら一覧のみ） */
+  onOpen?: (diagnostic: SphinxDiagnostic) => void;
+  onClose: () => void;
+}
+
+/** 直近のビルドの警告・エラーの一覧 */
+export function DiagnosticsPanel({ diagnostics, onOpen, onClose }: DiagnosticsPanelProps) {
+  const counts = countBySeverity(diagnostics);
+
+  return (
+    <div className="h-48 shrink-0 flex flex-col bg-gray-950 border-t border-gray-700 text-xs">
+      <div className="h-7 flex items-center justify-between px-3 bg-gray-800 text-gray-300">
+        <span>
+          Warnings
+          <span className="text-gray-500 ml-2">
+            {counts.warning} warnings, {counts.error} errors
+          </span>
+        </span>
+        <button onClick={onClose} className="hover:text-white">
+          Close
+        </button>
+      </div>
+      <div className="flex-1 overflow-y-auto py-1 font-mono">
+        {diagnostics.length === 0 && (
+          <div className="px-3 text-gray-400">No warnings in the last build</div>
+        )}
+        {diagnostics.map((diagnostic, i) => (
+          <button
+            key={i}
+            onClick={() => onOpen?.(diagnostic)}
+            disabled={!onOpen}
+            title={onOpen ? "Open in the editor" : undefined}
+            className="block w-full px-3 text-left text-gray-300 whitespace-pre-wrap enabled:hover:bg-gray-800"
+          >
+            <span className={SEVERITY_CLASS[diagnostic.severity]}>
+              {diagnostic.severity.toUpperCase()}
+            </span>{" "}
+            <span className="text-gray-500">
+              {diagnostic.file}
+              {diagnostic.line !== null && `:${diagnostic.line}`}
+            </span>{" "}
+            {diagnostic.message}
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,61 @@
+import { useState, useMemo } from "react";
+import { useLogRecords } from "../hooks/useLogRecords";
+import { filterLogRecords, logBuffer } from "../utils/logger";
+import type { LogLevel } from "../types/config";
+
+const LEVELS: LogLevel[] = ["debug", "info", "warn", "error"];
+
+const LEVEL_CLASS: Record<LogLevel, string> = {
+  debug: "text-gray-500",
+  info: "text-gray-300",
+  warn: "text-yellow-400",
+  error: "text-red-400",
+};
+
+interface LogViewerProps {
+  onClose: () => void;
+}
+
+/** アプリ内ログビューア（レベルで絞り込み可能） */
+export function LogViewer({ onClose }: LogViewerProps) {
+  const records = useLogRecords();
+  const [minLevel, setMinLevel] = useState<LogLevel>("debug");
+  const visible = useMemo(() => filterLogRecords(records, minLevel), [records, minLevel]);
+
+  return (
+    <div className="h-48 shrink-0 flex flex-col bg-gray-950 border-t border-gray-700 text-xs">
+      <div className="h-7 flex items-center justify-between px-3 bg-gray-800 text-gray-300">
+        <span>Logs</span>
+        <div className="flex items-center gap-2">
+          <select
+            value={minLevel}
+            onChange={(e) => setMinLevel(e.target.value as LogLevel)}
+            className="bg-gray-700 rounded px-1"
+          >
+            {LEVELS.map((level) => (
+              <option key={level} value={level}>
+                {level}
+              </option>
+            ))}
+          </select>
+          <button onClick={() => logBuffer.clear()} className="hover:text-white">
+            Clear
+          </button>
+          <button onClick={onClose} className="hover:text-white">
+            Close
+          </button>
+        </div>
+      </div>
+      <div className="flex-1 overflow-y-auto px-3 py-1 font-mono">
+        {visible.map((record) => (
+          <div key={record.id} className={`whitespace-pre-wrap ${LEVEL_CLASS[record.level]}`}>
+            <span className="text-gray-600">
+              {new Date(record.timestamp).toLocaleTimeString()}
+            </span>{" "}
+            [{record.level}] {record.message}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,109 @@
+import { useState } from "react";
+
+interface OnboardingStep {
+  title: string;
+  description: string;
+  action: string;
+  done: boolean;
+  /** undefinedなら前の手順が終わるまで押せない */
+  onAction?: () => void;
+}
+
+interface OnboardingProps {
+  projectOpened: boolean;
+  previewRunning: boolean;
+  /** falseならプレビューの手順を出さない（preview.enabled = false） */
+  previewEnabled: boolean;
+  onOpenProject: () => void;
+  /** ターミナルのエディタで設定ファイルを開く（ターミナル起動前はundefined） */
+  onEditConfig?: () => void;
+  /** 設定読み込み前はundefined */
+  onStartPreview?: () => void;
+  onDismiss: () => void;
+}
+
+/** 初回起動時に表示する、プロジェクトを開いてプレビューするまでの案内 */
+export function Onboarding({
+  projectOpened,
+  previewRunning,
+  previewEnabled,
+  onOpenProject,
+  onEditConfig,
+  onStartPreview,
+  onDismiss,
+}: OnboardingProps) {
+  // 設定ファイルを開いたら、その手順は済んだものとする
+  const [configOpened, setConfigOpened] = useState(false);
+  const steps: OnboardingStep[] = [
+    {
+      title: "Open your Sphinx project",
+      description: "Pick the folder that contains your docs. The terminal starts there.",
+      action: "Open Project",
+      done: projectOpened,
+      onAction: onOpenProject,
+    },
+    {
+      title: "Configure Python",
+      description:
+        "Set python.interpreter to the Python that has sphinx-autobuild installed (for example .venv/bin/python).",
+      action: "Edit config.toml",
+      done: configOpened,
+      onAction:
+        onEditConfig &&
+        (() => {
+          onEditConfig();
+          setConfigOpened(true);
+        }),
+    },
+  ];
+  if (previewEnabled) {
+    steps.push({
+      title: "Start the preview",
+      description: "sphinx-autobuild rebuilds the docs and reloads the preview as you edit.",
+      action: "Start Preview",
+      done: previewRunning,
+      onAction: onStartPreview,
+    });
+  }
+
+  return (
+    <div className="absolute inset-0 z-20 flex items-center justify-center bg-gray-900/80">
+      <div className="w-[28rem] rounded-lg bg-gray-800 p-6 text-gray-200 shadow-xl">
+        <h2 className="text-lg font-semibold">Welcome to Khafre</h2>
+        <p className="mt-1 text-sm text-gray-400">Three steps to a live preview of your docs.</p>
+        <ol className="mt-4 space-y-4">
+          {steps.map((step, index) => (
+            <li key={step.title} className="flex gap-3">
+              <span
+                className={`h-6 w-6 shrink-0 rounded-full text-center text-sm leading-6 ${
+                  step.done ? "bg-green-700" : "bg-gray-700"
+                }`}
+              >
+                {step.done ? "✓" : index + 1}
+              </span>
+              <div className="flex-1">
+                <div className="text-sm font-medium">{step.title}</div>
+                <div className="text-xs text-gray-400">{step.description}</div>
+                <button
+                  onClick={step.onAction}
+                  disabled={!step.onAction || step.done}
+                  className="mt-2 px-2 py-0.5 bg-blue-700 hover:bg-blue-600 disabled:opacity-50 disabled:hover:bg-blue-700 rounded text-xs transition-colors"
+                >
+                  {step.action}
+                </button>
+              </div>
+            </li>
+          ))}
+        </ol>
+        <div className="mt-6 flex justify-end">
+          <button
+            onClick={onDismiss}
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+          >
+            {steps.every((step) => step.done) ? "Done" : "Skip"}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
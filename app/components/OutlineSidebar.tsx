@@ -0,0 +1,33 @@
+import type { Heading } from "../types/outline";
+
+interface OutlineSidebarProps {
+  headings: Heading[];
+  /** 再ビルド後も表示を保つ見出し */
+  pinnedAnchor: string | null;
+  onSelect: (heading: Heading) => void;
+}
+
+/** プレビュー中のページの見出し一覧 */
+export function OutlineSidebar({ headings, pinnedAnchor, onSelect }: OutlineSidebarProps) {
+  return (
+    <nav className="w-48 shrink-0 overflow-y-auto bg-gray-900 border-r border-gray-700 py-2 text-xs">
+      {headings.length === 0 ? (
+        <div className="px-3 text-gray-500">No headings</div>
+      ) : (
+        headings.map((heading, index) => (
+          <button
+            key={`${index}-${heading.anchor}`}
+            onClick={() => onSelect(heading)}
+            title={heading.title}
+            style={{ paddingLeft: `${0.75 * heading.level}rem` }}
+            className={`block w-full truncate pr-3 py-0.5 text-left hover:bg-gray-800 hover:text-white ${
+              heading.anchor === pinnedAnchor ? "bg-gray-800 text-white" : "text-gray-300"
+            }`}
+          >
+            {heading.title}
+          </button>
+        ))
+      )}
+    </nav>
+  );
+}
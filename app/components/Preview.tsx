@@ -1,10 +1,100 @@
+import { useEffect, useReducer, useRef, useState } from "react";
+import type { PreviewMode } from "../types/config";
+import type { SphinxDiagnostic } from "../types/sphinx";
+import { previewErrorOverlay } from "../utils/diagnostics";
+import {
+  INITIAL_PREVIEW_LOAD,
+  PREVIEW_DEVICES,
+  PREVIEW_LOAD_RETRIES,
+  loadPreviewDevice,
+  previewDeviceFrame,
+  previewFrameKey,
+  previewLoadReducer,
+  previewLoadStatus,
+  shouldRenderIframe,
+  storePreviewDevice,
+  type PreviewDevice,
+} from "../utils/preview";
+
 interface PreviewProps {
   url: string | null;
   isBuilding?: boolean;
+  mode?: PreviewMode;
+  onOpenInBrowser?: () => void;
+  /** iframe内でページが読み込まれた時に呼ばれる */
+  onNavigate?: (pageUrl: string) => void;
+  /** 表示中ページのソースを開く（未指定ならボタンを無効化） */
+  onEditSource?: () => void;
+  /** 表示中ページのリンクをコピー（relative: サーバールートからの相対パス） */
+  onCopyLink?: (pageUrl: string, relative: boolean) => void;
+  /** 値が変わるたびにiframeを読み込み直す */
+  reloadToken?: number;
+  /** 表示中のページを読み込み直す */
+  onRefresh?: () => void;
+  /** ツールバーのURLを右クリックした時に呼ばれる（x, yはウィンドウ座標） */
+  onUrlContextMenu?: (pageUrl: string, x: number, y: number) => void;
+  /** 自動再ビルドの反映を一時停止中（livereloadを止める） */
+  paused?: boolean;
+  /** 操作がなくsphinx-autobuildを止めた状態（sphinx.idle_timeout_secs） */
+  idleStopped?: boolean;
+  onResume?: () => void;
+  /** 直近のビルドの警告・エラー（エラーがあればページに重ねて表示） */
+  diagnostics?: SphinxDiagnostic[];
+  /** クリックしたエラーをエディタで開く */
+  onOpenDiagnostic?: (diagnostic: SphinxDiagnostic) => void;
 }
 
 /** Sphinxプレビュー用iframe */
-export function Preview({ url, isBuilding }: PreviewProps) {
+export function Preview({
+  url,
+  isBuilding,
+  mode = "embedded",
+  onOpenInBrowser,
+  onNavigate,
+  onEditSource,
+  onCopyLink,
+  reloadToken = 0,
+  onRefresh,
+  onUrlContextMenu,
+  paused = false,
+  idleStopped = false,
+  onResume,
+  diagnostics = [],
+  onOpenDiagnostic,
+}: PreviewProps) {
+  const iframeRef = useRef<HTMLIFrameElement>(null);
+  // 読み込みの失敗（サーバーの再起動中など）と、やり直すたびにiframeを作り直すためのカウンター
+  const [load, dispatchLoad] = useReducer(previewLoadReducer, INITIAL_PREVIEW_LOAD);
+  const [retryCount, setRetryCount] = useState(0);
+  const loadStatus = previewLoadStatus(load, PREVIEW_LOAD_RETRIES);
+  // 閉じたエラー表示は、次のビルドで一覧が変わるまで出さない
+  const [dismissedDiagnostics, setDismissedDiagnostics] = useState<SphinxDiagnostic[] | null>(
+    null
+  );
+  const overlayErrors = previewErrorOverlay(diagnostics, dismissedDiagnostics === diagnostics);
+  // 表示幅（モバイル・タブレットでの見え方の確認用）
+  const [device, setDevice] = useState<PreviewDevice>(loadPreviewDevice);
+  const frame = previewDeviceFrame(device);
+
+  useEffect(() => {
+    dispatchLoad({ type: "retry" });
+  }, [url, reloadToken]);
+
+  // 自動でやり直す（失敗が続くほど間隔を空ける）
+  useEffect(() => {
+    if (loadStatus !== "retrying") return;
+    const timer = window.setTimeout(
+      () => setRetryCount((count) => count + 1),
+      load.failures * 1000
+    );
+    return () => window.clearTimeout(timer);
+  }, [loadStatus, load.failures]);
+
+  const handleRetry = () => {
+    dispatchLoad({ type: "retry" });
+    setRetryCount((count) => count + 1);
+  };
+
   if (isBuilding) {
     return (
       <div className="flex items-center justify-center h-full bg-gray-800 text-gray-400">
@@ -16,6 +106,23 @@ export function Preview({ url, isBuilding }: PreviewProps) {
     );
   }
 
+  if (idleStopped) {
+    return (
+      <div className="flex items-center justify-center h-full bg-gray-800 text-gray-400">
+        <div className="text-center">
+          <p className="text-lg mb-2">Preview paused</p>
+          <p className="text-sm mb-4">sphinx-autobuild was stopped after a period of inactivity</p>
+          <button
+            onClick={onResume}
+            className="px-3 py-1 bg-green-700 hover:bg-green-600 text-gray-200 rounded text-sm transition-colors"
+          >
+            Click to Resume
+          </button>
+        </div>
+      </div>
+    );
+  }
+
   if (!url) {
     return (
       <div className="flex items-center justify-center h-full bg-gray-800 text-gray-400">
@@ -27,12 +134,179 @@ export function Preview({ url, isBuilding }: PreviewProps) {
     );
   }
 
+  // externalモードではiframeを描画せず、ステータスのみ表示
+  if (!shouldRenderIframe(mode)) {
+    return (
+      <div className="flex items-center justify-center h-full bg-gray-800 text-gray-400">
+        <div className="text-center">
+          <p className="text-lg mb-2">Preview is served in your browser</p>
+          <p className="text-sm mb-4">{url}</p>
+          <button
+            onClick={onOpenInBrowser}
+            className="px-3 py-1 bg-blue-700 hover:bg-blue-600 text-gray-200 rounded text-sm transition-colors"
+          >
+            Open in Browser
+          </button>
+        </div>
+      </div>
+    );
+  }
+
+  // 表示中のページURL（クロスオリジンで読めない場合はsrcを使う）
+  const currentPageUrl = () => {
+    try {
+      return iframeRef.current?.contentWindow?.location.href ?? url;
+    } catch {
+      // クロスオリジン
+      return url;
+    }
+  };
+
+  const handleDeviceChange = (next: PreviewDevice) => {
+    setDevice(next);
+    storePreviewDevice(next);
+  };
+
+  const handleLoad = () => {
+    const pageUrl = currentPageUrl();
+    onNavigate?.(pageUrl);
+    // 接続できなくてもエラーページでloadが発火するので、ページに届くかを確かめる
+    fetch(pageUrl, { mode: "no-cors", cache: "no-store" })
+      .then(() => dispatchLoad({ type: "loaded" }))
+      .catch(() => dispatchLoad({ type: "failed" }));
+  };
+
   return (
-    <iframe
-      src={url}
-      className="w-full h-full border-0 bg-white"
-      sandbox="allow-scripts allow-same-origin"
-      title="Sphinx Preview"
-    />
+    <div className="flex flex-col h-full">
+      <div className="h-7 bg-gray-800 flex items-center justify-end px-2 gap-2 shrink-0">
+        <span
+          onContextMenu={(e) => {
+            if (!onUrlContextMenu) return;
+            e.preventDefault();
+            onUrlContextMenu(currentPageUrl(), e.clientX, e.clientY);
+          }}
+          title={url}
+          className="mr-auto min-w-0 truncate text-gray-500 text-xs"
+        >
+          {url}
+        </span>
+        {paused && <span className="text-yellow-400 text-xs">Auto-reload paused</span>}
+        {loadStatus === "retrying" && (
+          <span className="text-yellow-400 text-xs">
+            Preview failed to load, retrying...
+          </span>
+        )}
+        <select
+          value={device}
+          onChange={(e) => handleDeviceChange(e.target.value as PreviewDevice)}
+          title="Preview width"
+          className="bg-gray-700 rounded px-1 text-xs text-gray-300"
+        >
+          {PREVIEW_DEVICES.map((preset) => (
+            <option key={preset.device} value={preset.device}>
+              {preset.label}
+            </option>
+          ))}
+        </select>
+        {onRefresh && (
+          <button
+            onClick={onRefresh}
+            title="Reload this page"
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs text-gray-300 transition-colors"
+          >
+            Refresh
+          </button>
+        )}
+        {onCopyLink && (
+          <>
+            <button
+              onClick={() => onCopyLink(currentPageUrl(), false)}
+              title="Copy the URL of this page"
+              className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs text-gray-300 transition-colors"
+            >
+              Copy Link
+            </button>
+            <button
+              onClick={() => onCopyLink(currentPageUrl(), true)}
+              title="Copy the path of this page relative to the docs root"
+              className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs text-gray-300 transition-colors"
+            >
+              Copy Path
+            </button>
+          </>
+        )}
+        <button
+          onClick={onEditSource}
+          disabled={!onEditSource}
+          title={onEditSource ? "Open this page's source in the editor" : "No source for this page"}
+          className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 disabled:hover:bg-gray-700 rounded text-xs text-gray-300 transition-colors"
+        >
+          Edit Source
+        </button>
+      </div>
+      <div
+        className={`relative flex-1 min-h-0 flex justify-center ${frame.framed ? "bg-gray-900 overflow-x-auto py-3" : ""}`}
+      >
+        <iframe
+          key={`${previewFrameKey(reloadToken, paused)}-${retryCount}`}
+          ref={iframeRef}
+          src={url}
+          onLoad={handleLoad}
+          onError={() => dispatchLoad({ type: "failed" })}
+          style={{ width: frame.width }}
+          className={`h-full shrink-0 bg-white ${frame.className}`}
+          sandbox={paused ? "allow-same-origin" : "allow-scripts allow-same-origin"}
+          title="Sphinx Preview"
+        />
+        {loadStatus !== "failed" && overlayErrors && (
+          <div className="absolute inset-0 flex flex-col bg-gray-900/95 text-gray-300 text-sm">
+            <div className="flex items-center justify-between px-4 py-2 border-b border-red-900">
+              <span className="text-red-400">
+                {overlayErrors.length} {overlayErrors.length === 1 ? "error" : "errors"} in the last
+                build
+              </span>
+              <button
+                onClick={() => setDismissedDiagnostics(diagnostics)}
+                className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+              >
+                Dismiss
+              </button>
+            </div>
+            <div className="flex-1 overflow-y-auto px-4 py-2 font-mono text-xs">
+              {overlayErrors.map((diagnostic, i) => (
+                <button
+                  key={i}
+                  onClick={() => onOpenDiagnostic?.(diagnostic)}
+                  disabled={!onOpenDiagnostic}
+                  title={onOpenDiagnostic ? "Open in the editor" : undefined}
+                  className="block w-full mb-2 text-left whitespace-pre-wrap enabled:hover:text-white"
+                >
+                  <span className="text-gray-500">
+                    {diagnostic.file}
+                    {diagnostic.line !== null && `:${diagnostic.line}`}
+                  </span>
+                  <br />
+                  {diagnostic.message}
+                </button>
+              ))}
+            </div>
+          </div>
+        )}
+        {loadStatus === "failed" && (
+          <div className="absolute inset-0 flex items-center justify-center bg-gray-800 text-gray-400">
+            <div className="text-center">
+              <p className="text-lg mb-2">Preview failed to load</p>
+              <p className="text-sm mb-4">The preview server did not respond at {url}</p>
+              <button
+                onClick={handleRetry}
+                className="px-3 py-1 bg-blue-700 hover:bg-blue-600 text-gray-200 rounded text-sm transition-colors"
+              >
+                Reload Preview
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
+    </div>
   );
 }
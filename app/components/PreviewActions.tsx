@@ -0,0 +1,132 @@
+interface PreviewActionsProps {
+  isRunning: boolean;
+  /** 直近のエラー（自動再読み込みの一時停止中は表示しない） */
+  error: string | null;
+  paused: boolean;
+  /** 設定を読み込み、プロジェクトを開いているか（起動やビルド関連のボタンを表示する） */
+  ready: boolean;
+  /** 設定を読み込んでいるか（プロジェクトを開く前でも起動できる） */
+  canStart: boolean;
+  /** 直近のビルドの警告・エラーの数 */
+  diagnosticCount: number;
+  buildDiffRunning: boolean;
+  onCopyIssueReport: () => void;
+  onOpenInBrowser: () => void;
+  onTogglePause: () => void;
+  onRestart: () => void;
+  onStop: () => void;
+  onStart: () => void;
+  onRevealBuildDir: () => void;
+  onToggleDiagnostics: () => void;
+  onToggleBuildLog: () => void;
+  onBuildDiff: () => void;
+}
+
+/** ステータスバーのプレビューの操作ボタン */
+export function PreviewActions({
+  isRunning,
+  error,
+  paused,
+  ready,
+  canStart,
+  diagnosticCount,
+  buildDiffRunning,
+  onCopyIssueReport,
+  onOpenInBrowser,
+  onTogglePause,
+  onRestart,
+  onStop,
+  onStart,
+  onRevealBuildDir,
+  onToggleDiagnostics,
+  onToggleBuildLog,
+  onBuildDiff,
+}: PreviewActionsProps) {
+  return (
+    <>
+      {error && !paused && (
+        <button
+          onClick={onCopyIssueReport}
+          title="Copy the error with the command and environment as Markdown for a bug report"
+          className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+        >
+          Copy Report
+        </button>
+      )}
+      {isRunning ? (
+        <>
+          <button
+            onClick={onOpenInBrowser}
+            className="px-2 py-0.5 bg-blue-700 hover:bg-blue-600 rounded text-xs transition-colors"
+          >
+            Open in Browser
+          </button>
+          <button
+            onClick={onTogglePause}
+            title="Stop reloading the preview on every rebuild until resumed"
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+          >
+            {paused ? "Resume Auto-reload" : "Pause Auto-reload"}
+          </button>
+          <button
+            onClick={onRestart}
+            title="Restart sphinx-autobuild with the same settings (picks up conf.py changes)"
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+          >
+            Restart Preview
+          </button>
+          <button
+            onClick={onStop}
+            className="px-2 py-0.5 bg-red-700 hover:bg-red-600 rounded text-xs transition-colors"
+          >
+            Stop Preview
+          </button>
+        </>
+      ) : (
+        canStart && (
+          <button
+            onClick={onStart}
+            className="px-2 py-0.5 bg-green-700 hover:bg-green-600 rounded text-xs transition-colors"
+          >
+            Start Preview
+          </button>
+        )
+      )}
+      {ready && (
+        <button
+          onClick={onRevealBuildDir}
+          className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+        >
+          Build Folder
+        </button>
+      )}
+      {diagnosticCount > 0 && (
+        <button
+          onClick={onToggleDiagnostics}
+          title="Show the warnings of the last build"
+          className="px-2 py-0.5 bg-yellow-800 hover:bg-yellow-700 rounded text-xs transition-colors"
+        >
+          Warnings ({diagnosticCount})
+        </button>
+      )}
+      {ready && (
+        <button
+          onClick={onToggleBuildLog}
+          title="Show the sphinx-autobuild output"
+          className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+        >
+          Build Log
+        </button>
+      )}
+      {ready && (
+        <button
+          onClick={onBuildDiff}
+          disabled={buildDiffRunning}
+          className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 rounded text-xs transition-colors"
+        >
+          Diff Build
+        </button>
+      )}
+    </>
+  );
+}
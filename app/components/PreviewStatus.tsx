@@ -0,0 +1,96 @@
+import type { InterpreterFallback, PortFallback } from "../hooks/useSphinx";
+import type { MissingSourceDir } from "../types/sphinx";
+import type { HealthStatus } from "../utils/health";
+import type { RebuildPauseState } from "../utils/preview";
+
+interface PreviewStatusProps {
+  isRunning: boolean;
+  previewUrl: string | null;
+  health: HealthStatus;
+  portFallback: PortFallback | null;
+  interpreterFallback: InterpreterFallback | null;
+  rebuildPause: RebuildPauseState;
+  error: string | null;
+  /** sphinx.source_dir（conf.pyが無い時に表示する） */
+  sourceDir?: string;
+  /** source_dirにconf.pyが無い場合の提案 */
+  missingSourceDir: MissingSourceDir | null;
+  /** 提案されたディレクトリを.khafre.tomlに保存 */
+  onUseSourceDir: (dir: string) => void;
+  /** conf.pyのあるフォルダを選んで.khafre.tomlに保存 */
+  onChooseSourceDir: () => void;
+}
+
+/** ステータスバーに表示するsphinx-autobuildの状態 */
+export function PreviewStatus({
+  isRunning,
+  previewUrl,
+  health,
+  portFallback,
+  interpreterFallback,
+  rebuildPause,
+  error,
+  sourceDir,
+  missingSourceDir,
+  onUseSourceDir,
+  onChooseSourceDir,
+}: PreviewStatusProps) {
+  const suggestion = missingSourceDir?.suggestion ?? null;
+  return (
+    <>
+      {isRunning && !previewUrl && <span className="text-yellow-400 text-xs">Building...</span>}
+      {isRunning && previewUrl && health === "healthy" && (
+        <span className="text-green-400 text-xs">Preview Running</span>
+      )}
+      {isRunning && previewUrl && health !== "healthy" && (
+        <span className="text-yellow-400 text-xs">Preview Not Responding</span>
+      )}
+      {isRunning && portFallback && (
+        <span
+          title={`sphinx.server.port ${portFallback.requested} is in use (a previous sphinx-autobuild may still be running)`}
+          className="text-yellow-400 text-xs"
+        >
+          Port {portFallback.requested} in use, using {portFallback.actual}
+        </span>
+      )}
+      {isRunning && interpreterFallback && (
+        <span
+          title={`python.interpreter ${interpreterFallback.configured} was not found, using ${interpreterFallback.actual}`}
+          className="text-yellow-400 text-xs"
+        >
+          Using detected Python
+        </span>
+      )}
+      {rebuildPause.paused && (
+        <span className="text-yellow-400 text-xs">
+          Paused
+          {rebuildPause.missedBuilds > 0 && ` (${rebuildPause.missedBuilds} builds pending)`}
+        </span>
+      )}
+      {error && !rebuildPause.paused && (
+        <span className="text-red-400 text-xs truncate max-w-xs">{error}</span>
+      )}
+      {missingSourceDir && sourceDir !== undefined && (
+        <span className="flex items-center gap-2 text-yellow-400 text-xs">
+          No conf.py in {sourceDir}
+          {suggestion !== null && (
+            <button
+              onClick={() => onUseSourceDir(suggestion)}
+              title="Save sphinx.source_dir to .khafre.toml in the project"
+              className="px-2 py-0.5 bg-yellow-800 hover:bg-yellow-700 text-gray-100 rounded transition-colors"
+            >
+              Use {suggestion}
+            </button>
+          )}
+          <button
+            onClick={onChooseSourceDir}
+            title="Choose the folder with conf.py and save it to .khafre.toml"
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 text-gray-100 rounded transition-colors"
+          >
+            Choose Folder
+          </button>
+        </span>
+      )}
+    </>
+  );
+}
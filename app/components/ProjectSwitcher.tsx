@@ -0,0 +1,109 @@
+import { useState, useMemo, useEffect, useRef } from "react";
+import { filterProjects, projectName } from "../utils/projects";
+
+interface ProjectSwitcherProps {
+  /** 最近のプロジェクト（新しい順） */
+  recent: string[];
+  /** 開いているプロジェクト（一覧では区別して表示） */
+  currentPath: string | null;
+  onSelect: (path: string) => void;
+  /** フォルダ選択ダイアログから開く */
+  onBrowse: () => void;
+  onClose: () => void;
+}
+
+/** 最近のプロジェクトを名前で絞り込んで切り替えるパレット */
+export function ProjectSwitcher({
+  recent,
+  currentPath,
+  onSelect,
+  onBrowse,
+  onClose,
+}: ProjectSwitcherProps) {
+  const [query, setQuery] = useState("");
+  const [selected, setSelected] = useState(0);
+  const matches = useMemo(() => filterProjects(query, recent), [query, recent]);
+  const listRef = useRef<HTMLUListElement>(null);
+
+  useEffect(() => {
+    setSelected(0);
+  }, [query]);
+
+  // 選択中の項目が見えるようにスクロール
+  useEffect(() => {
+    listRef.current?.children[selected]?.scrollIntoView({ block: "nearest" });
+  }, [selected]);
+
+  const handleKeyDown = (event: React.KeyboardEvent) => {
+    switch (event.key) {
+      case "ArrowDown":
+        event.preventDefault();
+        setSelected((index) => Math.min(index + 1, matches.length - 1));
+        break;
+      case "ArrowUp":
+        event.preventDefault();
+        setSelected((index) => Math.max(index - 1, 0));
+        break;
+      case "Enter":
+        event.preventDefault();
+        if (matches[selected]) onSelect(matches[selected]);
+        break;
+      case "Escape":
+        event.preventDefault();
+        onClose();
+        break;
+    }
+  };
+
+  return (
+    <div
+      className="absolute inset-0 z-20 flex justify-center bg-gray-900/60 pt-24"
+      onClick={onClose}
+    >
+      <div
+        className="w-[32rem] h-fit rounded-lg bg-gray-800 text-gray-200 shadow-xl"
+        onClick={(event) => event.stopPropagation()}
+      >
+        <input
+          autoFocus
+          value={query}
+          onChange={(event) => setQuery(event.target.value)}
+          onKeyDown={handleKeyDown}
+          placeholder="Switch project..."
+          className="w-full bg-transparent px-4 py-3 text-sm outline-none border-b border-gray-700"
+        />
+        <ul ref={listRef} className="max-h-80 overflow-y-auto py-1">
+          {matches.map((path, index) => (
+            <li
+              key={path}
+              onClick={() => onSelect(path)}
+              onMouseMove={() => setSelected(index)}
+              className={`px-4 py-1.5 cursor-pointer ${index === selected ? "bg-gray-700" : ""}`}
+            >
+              <div className="text-sm">
+                {projectName(path)}
+                {path === currentPath && (
+                  <span className="ml-2 text-xs text-gray-500">current</span>
+                )}
+              </div>
+              <div className="text-xs text-gray-400 truncate">{path}</div>
+            </li>
+          ))}
+          {matches.length === 0 && (
+            <li className="px-4 py-1.5 text-sm text-gray-500">
+              {recent.length === 0 ? "No recent projects" : "No matching projects"}
+            </li>
+          )}
+        </ul>
+        <div className="flex justify-end border-t border-gray-700 px-4 py-2">
+          <button
+            onClick={onBrowse}
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+          >
+            Browse...
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,47 @@
+import { useEffect, useRef } from "react";
+
+interface SphinxLogPanelProps {
+  lines: string[];
+  onClose: () => void;
+}
+
+/** sphinx-autobuildの出力（新しい行が来たら末尾までスクロール） */
+export function SphinxLogPanel({ lines, onClose }: SphinxLogPanelProps) {
+  const bodyRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    const body = bodyRef.current;
+    if (body) body.scrollTop = body.scrollHeight;
+  }, [lines]);
+
+  return (
+    <div className="h-48 shrink-0 flex flex-col bg-gray-950 border-t border-gray-700 text-xs">
+      <div className="h-7 flex items-center justify-between px-3 bg-gray-800 text-gray-300">
+        <span>Build Log</span>
+        <div className="flex items-center gap-2">
+          <button
+            onClick={() => navigator.clipboard.writeText(lines.join("\n"))}
+            disabled={lines.length === 0}
+            className="hover:text-white disabled:opacity-50"
+          >
+            Copy
+          </button>
+          <button onClick={onClose} className="hover:text-white">
+            Close
+          </button>
+        </div>
+      </div>
+      <div ref={bodyRef} className="flex-1 overflow-y-auto px-3 py-1 font-mono text-gray-300">
+        {lines.length === 0 ? (
+          <div className="text-gray-500">No output yet</div>
+        ) : (
+          lines.map((line, i) => (
+            <div key={i} className="whitespace-pre-wrap">
+              {line}
+            </div>
+          ))
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,151 @@
+import { useState, type ReactNode } from "react";
+import type { StatusBarMode } from "../types/config";
+import { statusBarSections } from "../utils/layout";
+import { formatHardReloadResult } from "../utils/hardReload";
+import type { HardReloadResult } from "../utils/hardReload";
+
+interface StatusBarProps {
+  /** ui.status_bar */
+  mode: StatusBarMode;
+  projectPath: string | null;
+  /** 表示中のターミナルのタイトル（OSC 0/2） */
+  terminalTitle?: string;
+  onProjectContextMenu: (x: number, y: number) => void;
+  /** 設定の読み込み中か */
+  loading: boolean;
+  /** エディタを開けなかった理由 */
+  editorNotice: string | null;
+  hardReloadResult: HardReloadResult | null;
+  /** Sphinxの状態（ビルド中・起動中・エラーなど） */
+  status?: ReactNode;
+  /** プレビューの操作ボタン */
+  previewActions?: ReactNode;
+  hardReloading: boolean;
+  onHardReload: () => void;
+  onToggleLogs: () => void;
+  onOpenProject: () => void;
+  /** 最近のプロジェクトの切り替えを開く（最近のプロジェクトが無ければundefined） */
+  onShowRecent?: () => void;
+  /** 切り替えのショートカットの表記 */
+  recentShortcut: string;
+}
+
+/**
+ * ヘッダーのステータスバー（表示する要素はui.status_barで決める）
+ * minimal / hiddenでも操作ボタン（Start/Stop・Logsなど）を使えるよう、一時的にfullで表示できる
+ */
+export function StatusBar({
+  mode,
+  projectPath,
+  terminalTitle,
+  onProjectContextMenu,
+  loading,
+  editorNotice,
+  hardReloadResult,
+  status,
+  previewActions,
+  hardReloading,
+  onHardReload,
+  onToggleLogs,
+  onOpenProject,
+  onShowRecent,
+  recentShortcut,
+}: StatusBarProps) {
+  const [expanded, setExpanded] = useState(false);
+  const sections = statusBarSections(expanded ? "full" : mode);
+
+  if (!sections.visible) {
+    return (
+      <button
+        onClick={() => setExpanded(true)}
+        title="Show the status bar (ui.status_bar = hidden)"
+        className="absolute top-1 right-1 z-20 px-1.5 bg-gray-800/70 hover:bg-gray-700 text-gray-300 rounded text-xs opacity-50 hover:opacity-100 transition-opacity"
+      >
+        ⋯
+      </button>
+    );
+  }
+
+  return (
+    <header className="h-8 bg-gray-800 flex items-center justify-between px-4 text-gray-300 text-sm shrink-0">
+      <span className="flex items-center gap-2">
+        Khafre
+        {sections.project && projectPath && (
+          <span
+            onContextMenu={(e) => {
+              e.preventDefault();
+              onProjectContextMenu(e.clientX, e.clientY);
+            }}
+            className="text-gray-500 text-xs truncate max-w-md"
+          >
+            {projectPath}
+          </span>
+        )}
+        {sections.project && terminalTitle && (
+          <span className="text-gray-400 text-xs truncate max-w-xs" title={terminalTitle}>
+            — {terminalTitle}
+          </span>
+        )}
+      </span>
+      <div className="flex items-center gap-4">
+        {loading && <span className="text-yellow-400 text-xs">Loading...</span>}
+        {editorNotice && <span className="text-yellow-400 text-xs">{editorNotice}</span>}
+        {hardReloadResult && (
+          <span
+            className={`${hardReloadResult.type === "failed" ? "text-red-400" : "text-green-400"} text-xs truncate max-w-xs`}
+          >
+            {formatHardReloadResult(hardReloadResult)}
+          </span>
+        )}
+        {sections.status && status}
+        {sections.actions && (
+          <>
+            {previewActions}
+            <button
+              onClick={onHardReload}
+              disabled={hardReloading}
+              title="Reload the config and theme, restart the terminal (unless a command is running) and restart the preview"
+              className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 disabled:opacity-50 rounded text-xs transition-colors"
+            >
+              Reload All
+            </button>
+            <button
+              onClick={onToggleLogs}
+              className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+            >
+              Logs
+            </button>
+            <button
+              onClick={onOpenProject}
+              className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+            >
+              Open Project
+            </button>
+            {onShowRecent && (
+              <button
+                onClick={onShowRecent}
+                title={`Switch to a recent project (${recentShortcut})`}
+                className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+              >
+                Recent
+              </button>
+            )}
+          </>
+        )}
+        {mode !== "full" && (
+          <button
+            onClick={() => setExpanded((value) => !value)}
+            title={
+              expanded
+                ? `Back to ui.status_bar = ${mode}`
+                : "Show the preview controls, Logs and Open Project"
+            }
+            className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+          >
+            {expanded ? "Hide" : "⋯"}
+          </button>
+        )}
+      </div>
+    </header>
+  );
+}
@@ -1,16 +1,56 @@
-import { useEffect, useRef, useCallback, useMemo } from "react";
+import { useState, useEffect, useRef, useCallback, useMemo } from "react";
 import { Terminal as XTerm, ITheme } from "@xterm/xterm";
 import { FitAddon } from "@xterm/addon-fit";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import { logger } from "../utils/logger";
 import { useSystemTheme } from "../hooks/useSystemTheme";
-import type { ColorScheme } from "../types/config";
+import {
+  isScrolledUp,
+  outputScrollTarget,
+  ResizeGate,
+  TerminalSize,
+  buildTerminalOptions,
+  buildContainerStyle,
+  hasTranslucentBackground,
+  applyCursorColors,
+  selectColorScheme,
+  fontSizeShortcut,
+  isAnsiCopyShortcut,
+  isEnterCopy,
+  isPasteShortcut,
+  promptJumpShortcut,
+  stepFontSize,
+  createResizeGate,
+  clampTerminalSize,
+  parseOsc7Cwd,
+  titleReportReply,
+  minTerminalSize,
+  formatPreservedScrollback,
+  scrollTargetAfterWrite,
+  rerunCommandData,
+  markedCommand,
+  DEFAULT_FONT_SIZE,
+} from "../utils/terminal";
+import { loadNumber, storeNumber, STORAGE_KEYS } from "../utils/storage";
+import { loadRenderer } from "../utils/renderer";
+import {
+  readSelection,
+  selectionToAnsi,
+  transformCopy,
+  type SelectionMode,
+} from "../utils/copy";
+import { getClipboard, setClipboard } from "../utils/clipboard";
+import { adjacentPromptLine, findPromptLines } from "../utils/prompt";
+import { detectUrls, isLinkClick } from "../utils/links";
+import type { ColorScheme, TerminalConfig } from "../types/config";
 import "@xterm/xterm/css/xterm.css";
 
-// デフォルトフォント設定
-const DEFAULT_FONT_FAMILY = 'Menlo, Monaco, "Courier New", monospace';
-const DEFAULT_FONT_SIZE = 14;
+// フォントサイズ変更ショートカットの修飾キー判定用
+const IS_MAC = navigator.userAgent.includes("Mac");
+
+// ビジュアルベルで光らせる時間
+const VISUAL_BELL_MS = 150;
 
 // OSテーマに応じたデフォルトカラースキーム
 const DARK_THEME: ITheme = {
@@ -26,11 +66,12 @@ const LIGHT_THEME: ITheme = {
 };
 
 // ColorScheme（snake_case）をxterm.js ITheme（camelCase）に変換
-function mapToXtermTheme(scheme: ColorScheme): ITheme {
+// 背景・前景・カーソルが無い場合（palette_fileのみの指定など）はfallbackの色を使う
+function mapToXtermTheme(scheme: ColorScheme, fallback: ITheme): ITheme {
   return {
-    background: scheme.background,
-    foreground: scheme.foreground,
-    cursor: scheme.cursor,
+    background: scheme.background ?? fallback.background,
+    foreground: scheme.foreground ?? fallback.foreground,
+    cursor: scheme.cursor ?? fallback.cursor,
     cursorAccent: scheme.cursor_accent,
     selectionBackground: scheme.selection_background,
     selectionForeground: scheme.selection_foreground,
@@ -50,35 +91,139 @@ function mapToXtermTheme(scheme: ColorScheme): ITheme {
     brightMagenta: scheme.bright_magenta,
     brightCyan: scheme.bright_cyan,
     brightWhite: scheme.bright_white,
+    extendedAnsi: scheme.extended_ansi,
   };
 }
 
 interface TerminalProps {
   sessionId: string;
   cwd?: string;
-  shell?: string;
-  fontFamily?: string;
-  fontSize?: number;
-  colorScheme?: ColorScheme;
+  config: TerminalConfig;
   onExit?: (code: number) => void;
+  /** シェルがOSC 7で作業ディレクトリを通知した時に呼ばれる */
+  onCwdChange?: (cwd: string) => void;
+  /** プログラムがOSC 0/2でタイトルを設定した時に呼ばれる */
+  onTitleChange?: (title: string) => void;
+  /** sessionIdが変わった時に、前のセッションのスクロールバックを新しいセッションの先頭に表示する */
+  reprintScrollback?: boolean;
+  /** 値が変わるたびにターミナルにフォーカスし、直前のコマンドを再実行する */
+  rerunToken?: number;
 }
 
-export function Terminal({ sessionId, cwd, shell, fontFamily, fontSize, colorScheme, onExit }: TerminalProps) {
+export function Terminal({
+  sessionId,
+  cwd,
+  config,
+  onExit,
+  onCwdChange,
+  onTitleChange,
+  reprintScrollback = false,
+  rerunToken = 0,
+}: TerminalProps) {
+  const {
+    shell,
+    cursor_color: cursorColor,
+    cursor_text_color: cursorTextColor,
+    smart_cursor_contrast: smartCursorContrast,
+    background_image: backgroundImage,
+  } = config;
   const containerRef = useRef<HTMLDivElement>(null);
   const terminalRef = useRef<XTerm | null>(null);
   const fitAddonRef = useRef<FitAddon | null>(null);
   const resizeTimeoutRef = useRef<number | null>(null);
+  // PTY起動前のリサイズを保留する
+  const resizeGateRef = useRef<ResizeGate | null>(null);
+  // スクロールバック表示中か（最新行へのジャンプボタン表示用）
+  const [scrolledUp, setScrolledUp] = useState(false);
+  // 表示領域が最小サイズを下回っているか
+  const [tooSmall, setTooSmall] = useState(false);
+  const minSizeRef = useRef(minTerminalSize(config));
+  minSizeRef.current = minTerminalSize(config);
+  // コピー時の変換設定（イベントハンドラから最新値を参照）
+  const copyConfigRef = useRef(config.copy);
+  copyConfigRef.current = config.copy;
+  const enterCopiesSelectionRef = useRef(config.enter_copies_selection ?? false);
+  enterCopiesSelectionRef.current = config.enter_copies_selection ?? false;
+  const promptPatternRef = useRef(config.prompt_pattern);
+  promptPatternRef.current = config.prompt_pattern;
+  const scrollOnOutputRef = useRef(config.scroll_on_output);
+  scrollOnOutputRef.current = config.scroll_on_output;
+  const onCwdChangeRef = useRef(onCwdChange);
+  onCwdChangeRef.current = onCwdChange;
+  const onTitleChangeRef = useRef(onTitleChange);
+  onTitleChangeRef.current = onTitleChange;
+  // 前のセッションから引き継ぐスクロールバック（終了処理で保存し、次の起動で表示する）
+  const reprintScrollbackRef = useRef(reprintScrollback);
+  reprintScrollbackRef.current = reprintScrollback;
+  const carriedScrollbackRef = useRef<string | null>(null);
+  // セマンティックマーク（OSC 133）から読み取った直前のコマンド
+  const lastCommandRef = useRef<string | null>(null);
+  // プロンプトの終わり（コマンド入力の開始位置、コマンドの実行中はnull）
+  const promptEndRef = useRef<{ x: number; y: number } | null>(null);
+  const rerunTokenRef = useRef(rerunToken);
+  const rerunHistoryFallbackRef = useRef(config.rerun_history_fallback ?? true);
+  rerunHistoryFallbackRef.current = config.rerun_history_fallback ?? true;
+  // ベルを受け取ってから一瞬だけ表示するオーバーレイ（terminal.visual_bell）
+  const [bellFlash, setBellFlash] = useState(false);
+  const bellTimerRef = useRef<number | null>(null);
+  const visualBellRef = useRef(config.visual_bell ?? true);
+  visualBellRef.current = config.visual_bell ?? true;
+  // ショートカットで変更したフォントサイズ（保存済みの値を優先）
+  const configuredFontSize = config.font_size ?? DEFAULT_FONT_SIZE;
+  const [fontSize, setFontSize] = useState(
+    () => loadNumber(STORAGE_KEYS.terminalFontSize) ?? configuredFontSize
+  );
+  const fontSizeRef = useRef(fontSize);
+  fontSizeRef.current = fontSize;
+  const configuredFontSizeRef = useRef(configuredFontSize);
+  configuredFontSizeRef.current = configuredFontSize;
 
   // OSのLight/Darkテーマを取得
   const systemTheme = useSystemTheme();
+  // theme_file_light / theme_file_darkがあればOSのテーマに合わせて切り替える
+  const colorScheme = selectColorScheme(config, systemTheme);
 
   // 実際に使用するテーマを決定
   const effectiveTheme = useMemo<ITheme>(() => {
+    // カーソル色は設定 > テーマ > デフォルトの順に優先
+    const cursorConfig = {
+      cursor_color: cursorColor,
+      cursor_text_color: cursorTextColor,
+      smart_cursor_contrast: smartCursorContrast,
+    };
+    const systemXtermTheme = systemTheme === "dark" ? DARK_THEME : LIGHT_THEME;
     if (colorScheme) {
-      return mapToXtermTheme(colorScheme);
+      return applyCursorColors(mapToXtermTheme(colorScheme, systemXtermTheme), cursorConfig);
     }
-    return systemTheme === "dark" ? DARK_THEME : LIGHT_THEME;
-  }, [colorScheme, systemTheme]);
+    return applyCursorColors(systemXtermTheme, cursorConfig);
+  }, [colorScheme, systemTheme, cursorColor, cursorTextColor, smartCursorContrast]);
+
+  // 背景を透過させる場合、テーマの背景色は外枠に描き、xterm.jsには透明を渡す
+  const translucent = hasTranslucentBackground(config);
+  const xtermTheme = useMemo<ITheme>(
+    () => (translucent ? { ...effectiveTheme, background: "rgba(0, 0, 0, 0)" } : effectiveTheme),
+    [effectiveTheme, translucent]
+  );
+
+  // 背景画像をBlob URLとして読み込む
+  const [backgroundImageUrl, setBackgroundImageUrl] = useState<string>();
+  useEffect(() => {
+    if (!backgroundImage) return;
+    let url: string | undefined;
+    let cancelled = false;
+    invoke<ArrayBuffer>("read_background_image", { path: backgroundImage })
+      .then((bytes) => {
+        if (cancelled) return;
+        url = URL.createObjectURL(new Blob([bytes]));
+        setBackgroundImageUrl(url);
+      })
+      .catch((e) => logger.warn("Failed to load the background image:", e));
+    return () => {
+      cancelled = true;
+      if (url) URL.revokeObjectURL(url);
+      setBackgroundImageUrl(undefined);
+    };
+  }, [backgroundImage]);
 
   // PTYにデータを送信
   const sendData = useCallback(
@@ -92,6 +237,20 @@ export function Terminal({ sessionId, cwd, shell, fontFamily, fontSize, colorSch
     [sessionId]
   );
 
+  // 表示領域に合わせて行列数を変更（最小サイズ未満はクランプし、はみ出た分は表示しない）
+  const fitToContainer = useCallback((): TerminalSize | null => {
+    const terminal = terminalRef.current;
+    const proposed = fitAddonRef.current?.proposeDimensions();
+    if (!terminal || !proposed || isNaN(proposed.cols) || isNaN(proposed.rows)) return null;
+
+    const { size, tooSmall } = clampTerminalSize(proposed, minSizeRef.current);
+    if (size.cols !== terminal.cols || size.rows !== terminal.rows) {
+      terminal.resize(size.cols, size.rows);
+    }
+    setTooSmall(tooSmall);
+    return size;
+  }, []);
+
   // リサイズ（間引き処理付き）
   const handleResize = useCallback(() => {
     if (resizeTimeoutRef.current) {
@@ -99,50 +258,224 @@ export function Terminal({ sessionId, cwd, shell, fontFamily, fontSize, colorSch
     }
 
     // 100msの間引き（ドラッグ中の過剰なリサイズを防ぐ）
-    resizeTimeoutRef.current = window.setTimeout(async () => {
-      if (!terminalRef.current || !fitAddonRef.current) return;
-
-      fitAddonRef.current.fit();
-      const { cols, rows } = terminalRef.current;
-
-      try {
-        await invoke("pty_resize", { sessionId, cols, rows });
-      } catch (e) {
-        logger.error("Failed to resize PTY:", e);
-      }
+    resizeTimeoutRef.current = window.setTimeout(() => {
+      const size = fitToContainer();
+      if (size) resizeGateRef.current?.request(size);
     }, 100);
-  }, [sessionId]);
+  }, [fitToContainer]);
 
   useEffect(() => {
     if (!containerRef.current) return;
 
     // xterm.js初期化
     const terminal = new XTerm({
-      cursorBlink: true,
-      fontSize: fontSize ?? DEFAULT_FONT_SIZE,
-      fontFamily: fontFamily ?? DEFAULT_FONT_FAMILY,
-      scrollback: 10000,
-      theme: effectiveTheme,
+      ...buildTerminalOptions(config),
+      fontSize: fontSizeRef.current,
+      theme: xtermTheme,
     });
 
     const fitAddon = new FitAddon();
     terminal.loadAddon(fitAddon);
 
     terminal.open(containerRef.current);
-    fitAddon.fit();
+    loadRenderer(terminal, config.renderer);
+
+    const carried = carriedScrollbackRef.current;
+    carriedScrollbackRef.current = null;
+    if (carried) terminal.write(formatPreservedScrollback(carried));
 
     terminalRef.current = terminal;
     fitAddonRef.current = fitAddon;
+    fitToContainer();
 
     // ユーザー入力をPTYに送信
     terminal.onData(sendData);
 
-    // PTYセッション開始
-    const { cols, rows } = terminal;
-    invoke("spawn_terminal", { sessionId, cwd, shell, cols, rows }).catch((e) => {
-      logger.error("Failed to spawn terminal:", e);
-      terminal.write(`\r\nError: ${e}\r\n`);
+    // 出力中のURLをCmd/Ctrl+クリックで開く（修飾キーなしのクリックは選択操作に使う）
+    terminal.registerLinkProvider({
+      provideLinks: (bufferLineNumber, callback) => {
+        const spans = detectUrls(terminal.buffer.active, bufferLineNumber - 1);
+        callback(
+          spans.map(({ url, start, end }) => ({
+            text: url,
+            // xterm.jsのセル位置は1始まり
+            range: {
+              start: { x: start.x + 1, y: start.y + 1 },
+              end: { x: end.x + 1, y: end.y + 1 },
+            },
+            activate: (event) => {
+              if (!isLinkClick(event, IS_MAC)) return;
+              invoke("open_in_browser", { url }).catch((e) =>
+                logger.error("Failed to open link:", e)
+              );
+            },
+          }))
+        );
+      },
+    });
+
+    // シェルの作業ディレクトリを追跡（OSC 7: file://host/path）
+    terminal.parser.registerOscHandler(7, (data) => {
+      const dir = parseOsc7Cwd(data);
+      if (dir) onCwdChangeRef.current?.(dir);
+      return true;
+    });
+
+    // 実行したコマンドを追跡（OSC 133: Bでコマンド入力の開始位置を覚え、Cで入力されたコマンドを読む）
+    lastCommandRef.current = null;
+    promptEndRef.current = null;
+    terminal.parser.registerOscHandler(133, (data) => {
+      const buffer = terminal.buffer.active;
+      const cursor = { x: buffer.cursorX, y: buffer.baseY + buffer.cursorY };
+      const mark = data.split(";")[0];
+      const promptEnd = promptEndRef.current;
+      if (mark === "B") {
+        promptEndRef.current = cursor;
+      } else if (mark === "C" && promptEnd) {
+        const command = markedCommand(readSelection(buffer, { start: promptEnd, end: cursor }));
+        // 空のEnterなら前のコマンドのまま
+        if (command !== "") lastCommandRef.current = command;
+        promptEndRef.current = null;
+      }
+      return true;
+    });
+
+    // ビジュアルベル（続けて鳴った場合は最後のベルから数える）
+    terminal.onBell(() => {
+      if (!visualBellRef.current) return;
+      setBellFlash(true);
+      if (bellTimerRef.current) window.clearTimeout(bellTimerRef.current);
+      bellTimerRef.current = window.setTimeout(() => setBellFlash(false), VISUAL_BELL_MS);
+    });
+
+    // タイトルの問い合わせ（CSI 21 t）に、OSC 0/2で設定されたタイトルを返す
+    // 設定されたタイトルはヘッダーにも表示する
+    let title = "";
+    terminal.onTitleChange((newTitle) => {
+      title = newTitle;
+      onTitleChangeRef.current?.(newTitle);
+    });
+    terminal.parser.registerCsiHandler({ final: "t" }, (params) => {
+      const reply = titleReportReply(params, title);
+      if (reply === null) return false;
+      sendData(reply);
+      return true;
+    });
+
+    // 折り返した行を1行に連結してコピーするか（terminal.copy.join_wrapped）
+    const joinWrapped = () => copyConfigRef.current?.join_wrapped ?? true;
+    // 選択の形（Altを押しながらドラッグを始めると矩形選択）
+    let selectionMode: SelectionMode = "normal";
+
+    // 貼り付け・フォントサイズ変更・色付きコピーのショートカット（シェルには送らない）
+    terminal.attachCustomKeyEventHandler((event) => {
+      // 貼り付け（改行の\r\n→\r変換とbracketed pasteはterminal.pasteが行う）
+      if (isPasteShortcut(event, IS_MAC)) {
+        if (event.type === "keydown") {
+          event.preventDefault();
+          getClipboard().then((text) => {
+            if (text) terminal.paste(text);
+          });
+        }
+        return false;
+      }
+      // 選択中のEnterはシェルへ送らず、コピーして選択を解除する
+      if (isEnterCopy(event, enterCopiesSelectionRef.current, terminal.hasSelection())) {
+        const range = terminal.getSelectionPosition();
+        if (event.type === "keydown" && range) {
+          event.preventDefault();
+          const text = readSelection(terminal.buffer.active, range, joinWrapped(), selectionMode);
+          setClipboard(transformCopy(text, copyConfigRef.current));
+          terminal.clearSelection();
+        }
+        return false;
+      }
+      if (isAnsiCopyShortcut(event, IS_MAC)) {
+        const range = terminal.getSelectionPosition();
+        if (event.type === "keydown" && range) {
+          event.preventDefault();
+          setClipboard(
+            selectionToAnsi(terminal.buffer.active, range, joinWrapped(), selectionMode)
+          );
+        }
+        return false;
+      }
+      // スクロールバック内の前後のプロンプトへ移動
+      const direction = promptJumpShortcut(event, IS_MAC);
+      if (direction) {
+        if (event.type === "keydown") {
+          event.preventDefault();
+          const buffer = terminal.buffer.active;
+          const lines = Array.from(
+            { length: buffer.length },
+            (_, y) => buffer.getLine(y)?.translateToString(true) ?? ""
+          );
+          const promptLines = findPromptLines(lines, promptPatternRef.current);
+          const target = adjacentPromptLine(promptLines, buffer.viewportY, direction);
+          if (target !== null) terminal.scrollToLine(target);
+        }
+        return false;
+      }
+      const action = fontSizeShortcut(event, IS_MAC);
+      if (!action) return true;
+      if (event.type === "keydown") {
+        event.preventDefault();
+        const next = stepFontSize(fontSizeRef.current, action, configuredFontSizeRef.current);
+        // リセット時は保存値を消して設定ファイルの値に戻す
+        storeNumber(STORAGE_KEYS.terminalFontSize, action === "reset" ? null : next);
+        setFontSize(next);
+      }
+      return false;
+    });
+
+    // コピー時に terminal.copy の変換を適用（xterm.js標準のコピー処理より先に捕捉）
+    const container = containerRef.current;
+    const handleCopy = (event: ClipboardEvent) => {
+      const range = terminal.getSelectionPosition();
+      if (!range || !event.clipboardData) return;
+      const text = readSelection(terminal.buffer.active, range, joinWrapped(), selectionMode);
+      event.clipboardData.setData("text/plain", transformCopy(text, copyConfigRef.current));
+      event.preventDefault();
+      event.stopPropagation();
+    };
+    container.addEventListener("copy", handleCopy, true);
+
+    const handleMouseDown = (event: MouseEvent) => {
+      selectionMode = event.altKey ? "block" : "normal";
+    };
+    container.addEventListener("mousedown", handleMouseDown, true);
+
+    // マウスでの選択が終わったらクリップボードにコピー
+    const handleMouseUp = () => {
+      const range = terminal.getSelectionPosition();
+      if (!terminal.hasSelection() || !range) return;
+      const text = readSelection(terminal.buffer.active, range, joinWrapped(), selectionMode);
+      setClipboard(transformCopy(text, copyConfigRef.current));
+    };
+    container.addEventListener("mouseup", handleMouseUp);
+
+    // スクロール位置を監視（出力でbaseYが進んだ場合も再判定）
+    const updateScrollState = () => {
+      const buffer = terminal.buffer.active;
+      setScrolledUp(isScrolledUp(buffer.viewportY, buffer.baseY));
+    };
+    terminal.onScroll(updateScrollState);
+    terminal.onWriteParsed(updateScrollState);
+
+    // PTYセッション開始（起動中のリサイズは起動完了後に最新のサイズだけ適用）
+    const resizeGate = createResizeGate((size) => {
+      invoke("pty_resize", { sessionId, ...size }).catch((e) =>
+        logger.error("Failed to resize PTY:", e)
+      );
     });
+    resizeGateRef.current = resizeGate;
+    const { cols, rows } = terminal;
+    invoke("spawn_terminal", { sessionId, cwd, shell, cols, rows })
+      .then(() => resizeGate.open({ cols, rows }))
+      .catch((e) => {
+        logger.error("Failed to spawn terminal:", e);
+        terminal.write(`\r\nError: ${e}\r\n`);
+      });
 
     // PTYからのデータを受信
     let unlistenData: UnlistenFn | null = null;
@@ -152,7 +485,19 @@ export function Terminal({ sessionId, cwd, shell, fontFamily, fontSize, colorSch
       unlistenData = await listen<[string, string]>("pty_data", (event) => {
         const [sid, data] = event.payload;
         if (sid === sessionId) {
-          terminal.write(data);
+          // terminal.scroll_on_output（書き込み前の位置から書き込み後のスクロール先を決める）
+          const buffer = terminal.buffer.active;
+          const target = outputScrollTarget(
+            scrollOnOutputRef.current,
+            buffer.viewportY,
+            buffer.baseY
+          );
+          const bufferType = buffer.type;
+          terminal.write(data, () => {
+            const next = scrollTargetAfterWrite(target, bufferType, terminal.buffer.active.type);
+            if (next === "bottom") terminal.scrollToBottom();
+            else if (next !== null) terminal.scrollToLine(next);
+          });
         }
       });
 
@@ -176,9 +521,24 @@ export function Terminal({ sessionId, cwd, shell, fontFamily, fontSize, colorSch
       if (resizeTimeoutRef.current) {
         window.clearTimeout(resizeTimeoutRef.current);
       }
+      if (bellTimerRef.current) {
+        window.clearTimeout(bellTimerRef.current);
+      }
       resizeObserver.disconnect();
+      resizeGateRef.current = null;
+      container.removeEventListener("copy", handleCopy, true);
+      container.removeEventListener("mousedown", handleMouseDown, true);
+      container.removeEventListener("mouseup", handleMouseUp);
       unlistenData?.();
       unlistenExit?.();
+      if (reprintScrollbackRef.current) {
+        // 代替スクリーン（vimなど）を表示中でも、通常のバッファのスクロールバックを引き継ぐ
+        const buffer = terminal.buffer.normal;
+        carriedScrollbackRef.current = readSelection(buffer, {
+          start: { x: 0, y: 0 },
+          end: { x: terminal.cols, y: buffer.length - 1 },
+        });
+      }
       terminal.dispose();
 
       // PTYセッション終了
@@ -188,11 +548,77 @@ export function Terminal({ sessionId, cwd, shell, fontFamily, fontSize, colorSch
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [sessionId]);
 
+  // 起動後のテーマ変更（OSのLight/Dark切り替え、テーマファイルの読み込み完了）を反映
+  useEffect(() => {
+    const terminal = terminalRef.current;
+    if (terminal) terminal.options.theme = xtermTheme;
+  }, [xtermTheme]);
+
+  // フォントサイズの変更を反映し、新しいセル寸法で行列数を合わせ直す
+  useEffect(() => {
+    const terminal = terminalRef.current;
+    if (!terminal || terminal.options.fontSize === fontSize) return;
+    terminal.options.fontSize = fontSize;
+    handleResize();
+  }, [fontSize, handleResize]);
+
+  // 直前のコマンドを再実行（値が増えた時だけ。sessionIdが変わって0に戻った場合は実行しない）
+  useEffect(() => {
+    const previous = rerunTokenRef.current;
+    rerunTokenRef.current = rerunToken;
+    if (rerunToken <= previous) return;
+    const terminal = terminalRef.current;
+    if (!terminal) return;
+    terminal.focus();
+    const buffer = terminal.buffer.active;
+    const promptEnd = promptEndRef.current;
+    const pendingInput =
+      promptEnd !== null &&
+      (buffer.cursorX !== promptEnd.x || buffer.baseY + buffer.cursorY !== promptEnd.y);
+    const data = rerunCommandData(lastCommandRef.current, {
+      historyFallback: rerunHistoryFallbackRef.current,
+      applicationCursor: terminal.modes.applicationCursorKeysMode,
+      pendingInput,
+    });
+    if (data === null) return;
+    // vimやページャー、ビルドなどが動いている間はそのプログラムへの入力になってしまうので送らない
+    invoke<boolean>("terminal_has_foreground_job", { sessionId })
+      .then((busy) => {
+        if (busy) logger.warn("Not rerunning the last command: the terminal is busy");
+        else sendData(data);
+      })
+      .catch((e) => logger.error("Failed to check the terminal:", e));
+  }, [rerunToken, sessionId, sendData]);
+
+  const scrollToBottom = useCallback(() => {
+    terminalRef.current?.scrollToBottom();
+    terminalRef.current?.focus();
+  }, []);
+
   return (
     <div
-      ref={containerRef}
-      className="w-full h-full"
-      style={{ backgroundColor: effectiveTheme.background || "#1e1e1e" }}
-    />
+      className="relative w-full h-full"
+      style={buildContainerStyle(
+        config,
+        effectiveTheme.background || "#1e1e1e",
+        backgroundImageUrl
+      )}
+    >
+      <div ref={containerRef} className="w-full h-full overflow-hidden" />
+      {bellFlash && <div className="absolute inset-0 bg-white/20 pointer-events-none" />}
+      {tooSmall && (
+        <div className="absolute top-2 left-1/2 -translate-x-1/2 px-2 py-0.5 bg-gray-700/80 text-gray-200 rounded text-xs pointer-events-none">
+          Window too small
+        </div>
+      )}
+      {scrolledUp && (
+        <button
+          onClick={scrollToBottom}
+          className="absolute bottom-3 right-5 px-2 py-0.5 bg-gray-700/80 hover:bg-gray-600 text-gray-200 rounded text-xs transition-colors"
+        >
+          ↓ Jump to latest
+        </button>
+      )}
+    </div>
   );
 }
@@ -0,0 +1,43 @@
+import { useEffect, useRef } from "react";
+
+interface TerminalExitOverlayProps {
+  /** シェルの終了コード */
+  code: number;
+  onRestart: () => void;
+}
+
+/** シェルの終了を知らせ、Enterで再起動する（terminal.on_exit = "idle"） */
+export function TerminalExitOverlay({ code, onRestart }: TerminalExitOverlayProps) {
+  const ref = useRef<HTMLDivElement>(null);
+
+  // ターミナルにフォーカスが残っていてもEnterで再起動できるよう、先に捕捉する
+  // （重ねているターミナルのペイン内だけ。他のタブやダイアログのEnterは奪わない）
+  useEffect(() => {
+    const pane = ref.current?.parentElement;
+    if (!pane) return;
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key !== "Enter") return;
+      event.preventDefault();
+      event.stopPropagation();
+      onRestart();
+    };
+    pane.addEventListener("keydown", handleKeyDown, true);
+    return () => pane.removeEventListener("keydown", handleKeyDown, true);
+  }, [onRestart]);
+
+  return (
+    <div ref={ref} className="absolute inset-0 flex items-center justify-center bg-gray-900/70">
+      <div className="flex flex-col items-center gap-2 text-gray-300">
+        <span className="text-sm">
+          Shell exited{code !== 0 && ` with code ${code}`} — press Enter to restart
+        </span>
+        <button
+          onClick={onRestart}
+          className="px-2 py-0.5 bg-gray-700 hover:bg-gray-600 rounded text-xs transition-colors"
+        >
+          Restart
+        </button>
+      </div>
+    </div>
+  );
+}
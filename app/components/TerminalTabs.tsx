@@ -0,0 +1,98 @@
+export interface TerminalTab {
+  id: string;
+  label: string;
+  /** メインのターミナルは閉じられない */
+  closable: boolean;
+}
+
+interface TerminalTabsProps {
+  tabs: TerminalTab[];
+  activeId: string;
+  onSelect: (id: string) => void;
+  /** 表示中のターミナルと同じ作業ディレクトリで新しいターミナルを開く */
+  onDuplicate: () => void;
+  onClose: (id: string) => void;
+  /** 表示中のターミナルにフォーカスして直前のコマンドを再実行 */
+  onRerun: () => void;
+  /** 録画中のターミナル（録画していなければnull） */
+  recordingId: string | null;
+  /** 表示中のターミナルの録画を開始・終了する */
+  onToggleRecording: () => void;
+  /** カラースキームをAlacritty TOMLとして保存（テーマ未設定ならundefined） */
+  onExportTheme?: () => void;
+}
+
+/** ターミナルのタブ切り替えバー */
+export function TerminalTabs({
+  tabs,
+  activeId,
+  onSelect,
+  onDuplicate,
+  onClose,
+  onRerun,
+  recordingId,
+  onToggleRecording,
+  onExportTheme,
+}: TerminalTabsProps) {
+  return (
+    <div className="h-7 shrink-0 flex items-center bg-gray-800 text-xs text-gray-400 overflow-x-auto">
+      {tabs.map((tab) => (
+        <div
+          key={tab.id}
+          className={`h-full flex items-center gap-1 px-3 border-r border-gray-700 ${
+            tab.id === activeId ? "bg-gray-900 text-gray-200" : "hover:text-gray-200"
+          }`}
+        >
+          <button onClick={() => onSelect(tab.id)} className="truncate max-w-48" title={tab.label}>
+            {tab.id === recordingId && <span className="text-red-500">● </span>}
+            {tab.label}
+          </button>
+          {tab.closable && (
+            <button
+              onClick={() => onClose(tab.id)}
+              title="Close this terminal"
+              className="hover:text-white"
+            >
+              ×
+            </button>
+          )}
+        </div>
+      ))}
+      <button
+        onClick={onDuplicate}
+        title="New terminal in the current directory"
+        className="px-3 h-full hover:text-white"
+      >
+        +
+      </button>
+      <button
+        onClick={onRerun}
+        title="Focus the terminal and run the last command again"
+        className="ml-auto px-3 h-full hover:text-white shrink-0"
+      >
+        Rerun
+      </button>
+      <button
+        onClick={onToggleRecording}
+        disabled={recordingId !== null && recordingId !== activeId}
+        title={
+          recordingId
+            ? "Stop recording and save the file"
+            : "Record this terminal's output as an asciicast file"
+        }
+        className="px-3 h-full hover:text-white disabled:opacity-50 shrink-0"
+      >
+        {recordingId ? "Stop Recording" : "Record"}
+      </button>
+      {onExportTheme && (
+        <button
+          onClick={onExportTheme}
+          title="Save the terminal colors as an Alacritty theme"
+          className="px-3 h-full hover:text-white shrink-0"
+        >
+          Export Theme
+        </button>
+      )}
+    </div>
+  );
+}
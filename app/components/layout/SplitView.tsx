@@ -1,75 +1,133 @@
 import { useState, useRef, useCallback, useEffect, ReactNode } from "react";
+import {
+  DividerDragEvent,
+  SplitOrientation,
+  nextDragging,
+  pointerRatio,
+  splitMinRatio,
+  stepRatioByKey,
+} from "../../utils/layout";
 
 interface SplitViewProps {
   left: ReactNode;
   right: ReactNode;
-  defaultRatio?: number; // 0-1, デフォルト 0.5
-  minWidth?: number; // 最小ペイン幅 (px)
+  /** 先頭（左または上）のペインの割合 0-1 */
+  ratio: number;
+  /** ドラッグ・キー操作による変更（minRatioは最小ペインサイズから求めた下限） */
+  onRatioChange: (ratio: number, minRatio: number) => void;
+  orientation?: SplitOrientation;
+  minSize?: number; // 最小ペイン幅・高さ (px)
 }
 
-/** 水平分割ビュー（ドラッグでリサイズ可能） */
-export function SplitView({ left, right, defaultRatio = 0.5, minWidth = 200 }: SplitViewProps) {
+/**
+ * 分割ビュー（ドラッグ、またはスプリッターにフォーカスして矢印キーでリサイズ可能）
+ * ドラッグ中はスプリッターがポインターをキャプチャするので、
+ * プレビューのiframeやウィンドウの外を通ってもボタンを離すまで追従する
+ */
+export function SplitView({
+  left,
+  right,
+  ratio,
+  onRatioChange,
+  orientation = "horizontal",
+  minSize = 200,
+}: SplitViewProps) {
   const containerRef = useRef<HTMLDivElement>(null);
-  const [ratio, setRatio] = useState(defaultRatio);
   const [isDragging, setIsDragging] = useState(false);
+  const vertical = orientation === "vertical";
 
-  const handleMouseDown = useCallback((e: React.MouseEvent) => {
-    e.preventDefault();
-    setIsDragging(true);
+  // 最小サイズを確保するための比率の下限（コンテナの実際の大きさから求める）
+  const minRatio = useCallback(() => {
+    const rect = containerRef.current?.getBoundingClientRect();
+    return splitMinRatio(minSize, (vertical ? rect?.height : rect?.width) ?? 0);
+  }, [minSize, vertical]);
+
+  const dispatchDrag = useCallback((event: DividerDragEvent) => {
+    setIsDragging((dragging) => nextDragging(dragging, event));
   }, []);
 
-  const handleMouseMove = useCallback(
-    (e: MouseEvent) => {
+  const handlePointerDown = useCallback(
+    (e: React.PointerEvent<HTMLDivElement>) => {
+      if (e.button !== 0) return;
+      e.preventDefault();
+      e.currentTarget.setPointerCapture(e.pointerId);
+      dispatchDrag({ type: "down" });
+    },
+    [dispatchDrag]
+  );
+
+  const handlePointerMove = useCallback(
+    (e: React.PointerEvent<HTMLDivElement>) => {
       if (!isDragging || !containerRef.current) return;
+      // ウィンドウの外でボタンを離していたらここで終える
+      if (!nextDragging(isDragging, { type: "move", buttons: e.buttons })) {
+        setIsDragging(false);
+        return;
+      }
 
       const rect = containerRef.current.getBoundingClientRect();
-      const newRatio = (e.clientX - rect.left) / rect.width;
-
-      // 最小幅を確保するための制約
-      const minRatio = minWidth / rect.width;
-      const maxRatio = 1 - minRatio;
-      setRatio(Math.max(minRatio, Math.min(maxRatio, newRatio)));
+      const newRatio = vertical
+        ? pointerRatio(e.clientY, rect.top, rect.height)
+        : pointerRatio(e.clientX, rect.left, rect.width);
+      onRatioChange(newRatio, minRatio());
     },
-    [isDragging, minWidth]
+    [isDragging, vertical, minRatio, onRatioChange]
   );
 
-  const handleMouseUp = useCallback(() => {
-    setIsDragging(false);
-  }, []);
+  // 矢印キーで1%（Shiftで5%）ずつ移動
+  const handleKeyDown = useCallback(
+    (e: React.KeyboardEvent) => {
+      const newRatio = stepRatioByKey(ratio, e, minRatio());
+      if (newRatio === null) return;
+      e.preventDefault();
+      onRatioChange(newRatio, minRatio());
+    },
+    [ratio, minRatio, onRatioChange]
+  );
 
-  // グローバルマウスイベントの登録
+  // ドラッグ中はテキスト選択を無効化
   useEffect(() => {
-    if (isDragging) {
-      document.addEventListener("mousemove", handleMouseMove);
-      document.addEventListener("mouseup", handleMouseUp);
-      // ドラッグ中はテキスト選択を無効化
-      document.body.style.userSelect = "none";
-      document.body.style.cursor = "col-resize";
-    }
+    if (!isDragging) return;
+    document.body.style.userSelect = "none";
+    document.body.style.cursor = vertical ? "row-resize" : "col-resize";
 
     return () => {
-      document.removeEventListener("mousemove", handleMouseMove);
-      document.removeEventListener("mouseup", handleMouseUp);
       document.body.style.userSelect = "";
       document.body.style.cursor = "";
     };
-  }, [isDragging, handleMouseMove, handleMouseUp]);
+  }, [isDragging, vertical]);
+
+  const paneStyle = (share: number) =>
+    vertical ? { height: `${share * 100}%` } : { width: `${share * 100}%` };
+  const paneClass = vertical ? "w-full overflow-hidden" : "h-full overflow-hidden";
 
   return (
-    <div ref={containerRef} className="flex h-full w-full">
-      {/* 左ペイン */}
-      <div style={{ width: `${ratio * 100}%` }} className="h-full overflow-hidden">
+    <div ref={containerRef} className={`flex h-full w-full ${vertical ? "flex-col" : ""}`}>
+      {/* 左（上）ペイン */}
+      <div style={paneStyle(ratio)} className={paneClass}>
         {left}
       </div>
 
       {/* スプリッター */}
       <div
-        className="w-1 bg-gray-700 cursor-col-resize hover:bg-blue-500 active:bg-blue-600 transition-colors flex-shrink-0"
-        onMouseDown={handleMouseDown}
+        role="separator"
+        aria-orientation={vertical ? "horizontal" : "vertical"}
+        aria-valuenow={Math.round(ratio * 100)}
+        aria-valuemin={0}
+        aria-valuemax={100}
+        tabIndex={0}
+        className={`${vertical ? "h-1 cursor-row-resize" : "w-1 cursor-col-resize"} bg-gray-700 hover:bg-blue-500 active:bg-blue-600 focus:bg-blue-500 focus:outline-none transition-colors flex-shrink-0`}
+        onPointerDown={handlePointerDown}
+        onPointerMove={handlePointerMove}
+        onPointerUp={() => dispatchDrag({ type: "up" })}
+        onPointerCancel={() => dispatchDrag({ type: "cancel" })}
+        onLostPointerCapture={() => dispatchDrag({ type: "cancel" })}
+        onPointerLeave={() => dispatchDrag({ type: "leave" })}
+        onKeyDown={handleKeyDown}
       />
 
-      {/* 右ペイン */}
-      <div style={{ width: `${(1 - ratio) * 100}%` }} className="h-full overflow-hidden">
+      {/* 右（下）ペイン */}
+      <div style={paneStyle(1 - ratio)} className={paneClass}>
         {right}
       </div>
     </div>
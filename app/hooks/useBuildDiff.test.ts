@@ -0,0 +1,84 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, act } from "@testing-library/react";
+import { useBuildDiff } from "./useBuildDiff";
+import type { ProjectConfig } from "../types/config";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+
+const mockConfig = {
+  sphinx: {
+    source_dir: "docs",
+    build_dir: "_build/html",
+    server: { port: 0 },
+    extra_args: [],
+    idle_timeout_secs: 0,
+    max_restarts: 3,
+    success_patterns: ["build succeeded", "waiting for changes"],
+    error_patterns: ["ERROR", "error:"],
+    health_check_interval_secs: 10,
+    health_check_failures: 3,
+    env: {},
+    inherit_env: true,
+  },
+  python: { interpreter: ".venv/bin/python" },
+  editor: { command: "nvim" },
+} as unknown as ProjectConfig;
+
+describe("useBuildDiff", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("should list changed files after run() is called", async () => {
+    const changes = [{ path: "index.html", change: "modified" }];
+    vi.mocked(invoke).mockResolvedValue(changes);
+
+    const { result } = renderHook(() =>
+      useBuildDiff({ projectPath: "/project", config: mockConfig })
+    );
+    expect(result.current.changes).toBeNull();
+
+    await act(async () => {
+      await result.current.run();
+    });
+
+    expect(invoke).toHaveBeenCalledWith("diff_build", {
+      projectPath: "/project",
+      sourceDir: "docs",
+      buildDir: "_build/html",
+      pythonPath: ".venv/bin/python",
+      env: {},
+      inheritEnv: true,
+    });
+    expect(result.current.changes).toEqual(changes);
+    expect(result.current.isRunning).toBe(false);
+    expect(result.current.error).toBeNull();
+  });
+
+  it("should report build errors", async () => {
+    vi.mocked(invoke).mockRejectedValue("sphinx-buildが失敗しました");
+
+    const { result } = renderHook(() =>
+      useBuildDiff({ projectPath: "/project", config: mockConfig })
+    );
+    await act(async () => {
+      await result.current.run();
+    });
+
+    expect(result.current.changes).toBeNull();
+    expect(result.current.error).toBe("sphinx-buildが失敗しました");
+  });
+
+  it("should do nothing without a project", async () => {
+    const { result } = renderHook(() => useBuildDiff({ projectPath: null, config: mockConfig }));
+    await act(async () => {
+      await result.current.run();
+    });
+
+    expect(invoke).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,52 @@
+import { useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { ProjectConfig } from "../types/config";
+import type { ChangedFile } from "../types/buildDiff";
+
+interface UseBuildDiffOptions {
+  projectPath: string | null;
+  config: ProjectConfig | null;
+}
+
+interface UseBuildDiffResult {
+  /** 直近の比較結果（未実行ならnull） */
+  changes: ChangedFile[] | null;
+  isRunning: boolean;
+  error: string | null;
+  run: () => Promise<void>;
+}
+
+/**
+ * 一時ディレクトリへのビルドと現在のビルド出力との比較を行うhook
+ */
+export function useBuildDiff({ projectPath, config }: UseBuildDiffOptions): UseBuildDiffResult {
+  const [changes, setChanges] = useState<ChangedFile[] | null>(null);
+  const [isRunning, setIsRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = useCallback(async () => {
+    if (!projectPath || !config) return;
+
+    setIsRunning(true);
+    setError(null);
+    try {
+      const result = await invoke<ChangedFile[]>("diff_build", {
+        projectPath,
+        sourceDir: config.sphinx.source_dir,
+        buildDir: config.sphinx.build_dir,
+        pythonPath: config.python.interpreter,
+        pythonRunner: config.python.runner,
+        env: config.sphinx.env,
+        inheritEnv: config.sphinx.inherit_env,
+      });
+      setChanges(result);
+    } catch (e) {
+      setError(String(e));
+      setChanges(null);
+    } finally {
+      setIsRunning(false);
+    }
+  }, [projectPath, config]);
+
+  return { changes, isRunning, error, run };
+}
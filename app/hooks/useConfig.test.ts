@@ -6,6 +6,16 @@ vi.mock("@tauri-apps/api/core", () => ({
   invoke: vi.fn(),
 }));
 
+// config_changedのリスナーを呼べるように保持する
+const eventListeners = new Map<string, () => void>();
+const unlisten = vi.fn();
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn(async (event: string, handler: () => void) => {
+    eventListeners.set(event, handler);
+    return unlisten;
+  }),
+}));
+
 import { invoke } from "@tauri-apps/api/core";
 
 const mockConfig = {
@@ -21,6 +31,7 @@ const mockConfig = {
 describe("useConfig", () => {
   beforeEach(() => {
     vi.clearAllMocks();
+    eventListeners.clear();
   });
 
   it("should load config on mount", async () => {
@@ -36,7 +47,26 @@ describe("useConfig", () => {
 
     expect(result.current.config).toEqual(mockConfig);
     expect(result.current.error).toBeNull();
-    expect(invoke).toHaveBeenCalledWith("load_config");
+    expect(invoke).toHaveBeenCalledWith("load_config", { projectPath: null });
+  });
+
+  it("should reload config with the project path when it changes", async () => {
+    vi.mocked(invoke).mockResolvedValue(mockConfig);
+
+    const { result, rerender } = renderHook(({ projectPath }) => useConfig(projectPath), {
+      initialProps: { projectPath: null as string | null },
+    });
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    rerender({ projectPath: "/path/to/project" });
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenLastCalledWith("load_config", { projectPath: "/path/to/project" });
+    });
+    expect(invoke).toHaveBeenCalledTimes(2);
   });
 
   it("should handle error when loading config fails", async () => {
@@ -71,4 +101,81 @@ describe("useConfig", () => {
       expect(invoke).toHaveBeenCalledTimes(2);
     });
   });
+
+  it("should reload config when the config file changes", async () => {
+    vi.mocked(invoke).mockResolvedValue(mockConfig);
+
+    const { result } = renderHook(() => useConfig("/path/to/project"));
+
+    await waitFor(() => {
+      expect(eventListeners.has("config_changed")).toBe(true);
+    });
+
+    const changedConfig = { ...mockConfig, editor: { command: "vim" } };
+    vi.mocked(invoke).mockResolvedValue(changedConfig);
+    act(() => {
+      eventListeners.get("config_changed")?.();
+    });
+
+    await waitFor(() => {
+      expect(result.current.config).toEqual(changedConfig);
+    });
+    expect(invoke).toHaveBeenLastCalledWith("load_config", { projectPath: "/path/to/project" });
+  });
+
+  it("should keep the previous config when the changed file cannot be read", async () => {
+    vi.mocked(invoke).mockResolvedValue(mockConfig);
+
+    const { result } = renderHook(() => useConfig());
+
+    await waitFor(() => {
+      expect(result.current.config).toEqual(mockConfig);
+    });
+    await waitFor(() => {
+      expect(eventListeners.has("config_changed")).toBe(true);
+    });
+
+    vi.mocked(invoke).mockRejectedValue(new Error("invalid TOML"));
+    act(() => {
+      eventListeners.get("config_changed")?.();
+    });
+
+    await waitFor(() => {
+      expect(result.current.error).toBe("Error: invalid TOML");
+    });
+    expect(result.current.config).toEqual(mockConfig);
+  });
+
+  it("should reject refresh() and keep the previous config when reading fails", async () => {
+    vi.mocked(invoke).mockResolvedValue(mockConfig);
+
+    const { result } = renderHook(() => useConfig());
+
+    await waitFor(() => {
+      expect(result.current.config).toEqual(mockConfig);
+    });
+
+    vi.mocked(invoke).mockRejectedValue(new Error("invalid TOML"));
+    await act(async () => {
+      await expect(result.current.refresh()).rejects.toThrow("invalid TOML");
+    });
+
+    expect(result.current.error).toBe("Error: invalid TOML");
+    expect(result.current.config).toEqual(mockConfig);
+  });
+
+  it("should stop watching the config file on unmount", async () => {
+    vi.mocked(invoke).mockResolvedValue(mockConfig);
+
+    const { unmount } = renderHook(() => useConfig());
+
+    await waitFor(() => {
+      expect(eventListeners.has("config_changed")).toBe(true);
+    });
+    unmount();
+
+    await waitFor(() => {
+      expect(unlisten).toHaveBeenCalled();
+    });
+  });
 });
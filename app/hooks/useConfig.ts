@@ -1,19 +1,25 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import type { ProjectConfig } from "../types/config";
+import { logger } from "../utils/logger";
+import { useConfigWatcher } from "./useConfigWatcher";
 
 interface UseConfigResult {
   config: ProjectConfig | null;
   error: string | null;
   loading: boolean;
   reload: () => void;
+  /** 前の設定を残したまま読み直し、読み直した設定を返す（読めなければ例外を投げる） */
+  refresh: () => Promise<ProjectConfig>;
 }
 
 /**
  * グローバル設定を読み込むhook
  * $XDG_CONFIG_HOME/khafre/config.toml から設定を読み込む
+ * theme_fileの相対パスはprojectPathを基準に解決するため、プロジェクト変更時に読み直す
+ * 設定ファイルが書き換えられた時も読み直す（読めない内容なら前の設定のまま）
  */
-export function useConfig(): UseConfigResult {
+export function useConfig(projectPath: string | null = null): UseConfigResult {
   const [config, setConfig] = useState<ProjectConfig | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [loading, setLoading] = useState(true);
@@ -23,7 +29,7 @@ export function useConfig(): UseConfigResult {
     setError(null);
 
     try {
-      const loadedConfig = await invoke<ProjectConfig>("load_config");
+      const loadedConfig = await invoke<ProjectConfig>("load_config", { projectPath });
       setConfig(loadedConfig);
     } catch (e) {
       setError(String(e));
@@ -31,11 +37,29 @@ export function useConfig(): UseConfigResult {
     } finally {
       setLoading(false);
     }
-  }, []);
+  }, [projectPath]);
 
   useEffect(() => {
     loadConfig();
   }, [loadConfig]);
 
-  return { config, error, loading, reload: loadConfig };
+  const refresh = useCallback(async () => {
+    try {
+      const loadedConfig = await invoke<ProjectConfig>("load_config", { projectPath });
+      setConfig(loadedConfig);
+      setError(null);
+      logger.info("Reloaded config.toml");
+      return loadedConfig;
+    } catch (e) {
+      logger.warn("Failed to reload config.toml:", e);
+      setError(String(e));
+      throw e;
+    }
+  }, [projectPath]);
+
+  useConfigWatcher(() => {
+    refresh().catch(() => {});
+  });
+
+  return { config, error, loading, reload: loadConfig, refresh };
 }
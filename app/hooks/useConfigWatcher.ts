@@ -0,0 +1,31 @@
+import { useEffect, useRef } from "react";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+
+/**
+ * 設定ファイル（config.toml）の変更を受け取るhook
+ * バックエンドが書き込みが落ち着くのを待ってから通知する
+ * ハンドラは最新のものを参照するため、毎レンダー新しい関数を渡してよい
+ */
+export function useConfigWatcher(onChange: () => void) {
+  const handlerRef = useRef(onChange);
+  useEffect(() => {
+    handlerRef.current = onChange;
+  });
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let disposed = false;
+
+    listen("config_changed", () => {
+      handlerRef.current();
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+}
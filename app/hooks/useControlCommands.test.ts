@@ -0,0 +1,63 @@
+import { describe, it, expect, vi } from "vitest";
+import { dispatchControlCommand, type ControlCommandHandlers } from "./useControlCommands";
+import { logger } from "../utils/logger";
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn(async () => () => {}),
+}));
+
+const createHandlers = (): ControlCommandHandlers => ({
+  openProject: vi.fn(),
+  startPreview: vi.fn(),
+  stopPreview: vi.fn(),
+  reloadPreview: vi.fn(),
+  navigatePreview: vi.fn(),
+  setSplitRatio: vi.fn(),
+  setSplitOrientation: vi.fn(),
+});
+
+describe("dispatchControlCommand", () => {
+  it("should call the handler of each command", () => {
+    const handlers = createHandlers();
+
+    dispatchControlCommand({ command: "open_project", path: "/docs" }, null, handlers);
+    dispatchControlCommand({ command: "stop_preview" }, null, handlers);
+    dispatchControlCommand({ command: "set_split_ratio", ratio: 0.3 }, null, handlers);
+    dispatchControlCommand(
+      { command: "set_split_orientation", orientation: "vertical" },
+      null,
+      handlers
+    );
+
+    expect(handlers.openProject).toHaveBeenCalledWith("/docs");
+    expect(handlers.stopPreview).toHaveBeenCalledTimes(1);
+    expect(handlers.startPreview).not.toHaveBeenCalled();
+    expect(handlers.setSplitRatio).toHaveBeenCalledWith(0.3);
+    expect(handlers.setSplitOrientation).toHaveBeenCalledWith("vertical");
+  });
+
+  it("should navigate to the page resolved against the preview URL", () => {
+    const handlers = createHandlers();
+
+    dispatchControlCommand(
+      { command: "navigate", page: "/guide/install.html" },
+      "http://127.0.0.1:8000/index.html",
+      handlers
+    );
+
+    expect(handlers.navigatePreview).toHaveBeenCalledWith(
+      "http://127.0.0.1:8000/guide/install.html"
+    );
+  });
+
+  it("should ignore navigate while no preview is shown", () => {
+    const warn = vi.spyOn(logger, "warn").mockImplementation(() => {});
+    const handlers = createHandlers();
+
+    dispatchControlCommand({ command: "navigate", page: "index.html" }, null, handlers);
+
+    expect(handlers.navigatePreview).not.toHaveBeenCalled();
+    expect(warn).toHaveBeenCalled();
+    warn.mockRestore();
+  });
+});
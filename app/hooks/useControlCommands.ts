@@ -0,0 +1,87 @@
+import { useEffect, useRef } from "react";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import type { ControlCommand } from "../types/control";
+import type { SplitOrientation } from "../utils/layout";
+import { logger } from "../utils/logger";
+import { resolvePreviewPage } from "../utils/preview";
+
+/** 制御ソケットのコマンドごとの処理 */
+export interface ControlCommandHandlers {
+  openProject: (path: string) => void;
+  startPreview: () => void;
+  stopPreview: () => void;
+  reloadPreview: () => void;
+  /** プレビューのURLに解決したページへ移動 */
+  navigatePreview: (url: string) => void;
+  setSplitRatio: (ratio: number) => void;
+  setSplitOrientation: (orientation: SplitOrientation) => void;
+}
+
+/**
+ * コマンドを対応する処理に振り分ける
+ * navigateのページは表示中のプレビューのURLを基準に解決する（プレビューが無ければ無視）
+ */
+export function dispatchControlCommand(
+  command: ControlCommand,
+  previewUrl: string | null,
+  handlers: ControlCommandHandlers
+): void {
+  switch (command.command) {
+    case "open_project":
+      handlers.openProject(command.path);
+      break;
+    case "start_preview":
+      handlers.startPreview();
+      break;
+    case "stop_preview":
+      handlers.stopPreview();
+      break;
+    case "reload":
+      handlers.reloadPreview();
+      break;
+    case "navigate": {
+      const url = previewUrl && resolvePreviewPage(previewUrl, command.page);
+      if (!url) {
+        logger.warn("Cannot navigate preview to:", command.page);
+        break;
+      }
+      handlers.navigatePreview(url);
+      break;
+    }
+    case "set_split_ratio":
+      handlers.setSplitRatio(command.ratio);
+      break;
+    case "set_split_orientation":
+      handlers.setSplitOrientation(command.orientation);
+      break;
+  }
+}
+
+/**
+ * 制御ソケット経由のコマンドを受け取るhook
+ * ハンドラは最新のものを参照するため、毎レンダー新しい関数を渡してよい
+ */
+export function useControlCommands(previewUrl: string | null, handlers: ControlCommandHandlers) {
+  const latestRef = useRef({ previewUrl, handlers });
+  useEffect(() => {
+    latestRef.current = { previewUrl, handlers };
+  });
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let disposed = false;
+
+    listen<ControlCommand>("control_command", (event) => {
+      const { previewUrl, handlers } = latestRef.current;
+      dispatchControlCommand(event.payload, previewUrl, handlers);
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+}
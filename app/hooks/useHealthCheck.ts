@@ -0,0 +1,67 @@
+import { useState, useEffect, useRef } from "react";
+import { HealthStatus, INITIAL_HEALTH, nextHealth } from "../utils/health";
+
+// 応答を待つ時間の上限
+const PROBE_TIMEOUT_MS = 5000;
+
+interface UseHealthCheckOptions {
+  /** 確認するURL（nullなら確認しない） */
+  url: string | null;
+  /** 0なら確認しない */
+  intervalSecs: number;
+  /** 続けて失敗したらonDownを呼ぶ回数 */
+  failureThreshold: number;
+  onDown: () => void;
+}
+
+/** URLにGETを送り、応答があればtrue（中身は見ないので、別オリジンでもno-corsで届けばよい） */
+async function probe(url: string): Promise<boolean> {
+  const controller = new AbortController();
+  const timer = window.setTimeout(() => controller.abort(), PROBE_TIMEOUT_MS);
+  try {
+    await fetch(url, { mode: "no-cors", cache: "no-store", signal: controller.signal });
+    return true;
+  } catch {
+    return false;
+  } finally {
+    window.clearTimeout(timer);
+  }
+}
+
+/**
+ * プレビューのURLに定期的に接続して、サーバーが応答しているか確認するhook
+ * 失敗がfailureThreshold回続いたらonDownを一度だけ呼ぶ
+ */
+export function useHealthCheck({
+  url,
+  intervalSecs,
+  failureThreshold,
+  onDown,
+}: UseHealthCheckOptions): HealthStatus {
+  const [status, setStatus] = useState<HealthStatus>("healthy");
+  const onDownRef = useRef(onDown);
+  useEffect(() => {
+    onDownRef.current = onDown;
+  });
+
+  useEffect(() => {
+    setStatus("healthy");
+    if (!url || intervalSecs <= 0) return;
+    let state = INITIAL_HEALTH;
+    let disposed = false;
+    const timer = window.setInterval(async () => {
+      const ok = await probe(url);
+      if (disposed) return;
+      const previous = state;
+      state = nextHealth(state, ok, failureThreshold);
+      setStatus(state.status);
+      if (state.status === "down" && previous.status !== "down") onDownRef.current();
+    }, intervalSecs * 1000);
+    return () => {
+      disposed = true;
+      window.clearInterval(timer);
+    };
+  }, [url, intervalSecs, failureThreshold]);
+
+  return status;
+}
@@ -0,0 +1,78 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+import { renderHook, act } from "@testing-library/react";
+import { useIdleTimeout } from "./useIdleTimeout";
+
+describe("useIdleTimeout", () => {
+  beforeEach(() => {
+    vi.useFakeTimers();
+  });
+
+  afterEach(() => {
+    document.body.innerHTML = "";
+    vi.useRealTimers();
+  });
+
+  it("should call onTimeout after the timeout without activity", () => {
+    const onTimeout = vi.fn();
+    renderHook(() => useIdleTimeout({ timeoutSecs: 60, active: true, onTimeout }));
+
+    act(() => {
+      vi.advanceTimersByTime(55_000);
+    });
+    expect(onTimeout).not.toHaveBeenCalled();
+
+    act(() => {
+      vi.advanceTimersByTime(10_000);
+    });
+    expect(onTimeout).toHaveBeenCalledTimes(1);
+  });
+
+  it("should count focusing the preview iframe as one activity", () => {
+    const onTimeout = vi.fn();
+    renderHook(() => useIdleTimeout({ timeoutSecs: 60, active: true, onTimeout }));
+    const iframe = document.createElement("iframe");
+    document.body.appendChild(iframe);
+
+    act(() => {
+      vi.advanceTimersByTime(50_000);
+      iframe.focus();
+      vi.advanceTimersByTime(50_000);
+    });
+    expect(onTimeout).not.toHaveBeenCalled();
+
+    // フォーカスしたまま触らなければ、フォーカスした時点から数えてタイムアウトする
+    act(() => {
+      vi.advanceTimersByTime(15_000);
+    });
+    expect(document.activeElement).toBe(iframe);
+    expect(onTimeout).toHaveBeenCalledTimes(1);
+  });
+
+  it("should not count leaving the window as activity", () => {
+    const onTimeout = vi.fn();
+    renderHook(() => useIdleTimeout({ timeoutSecs: 60, active: true, onTimeout }));
+
+    // 他のウィンドウに移っただけ
+    act(() => {
+      vi.advanceTimersByTime(50_000);
+      window.dispatchEvent(new Event("blur"));
+      vi.advanceTimersByTime(15_000);
+    });
+    expect(onTimeout).toHaveBeenCalledTimes(1);
+  });
+
+  it("should reset the idle time on a window blur while the iframe is focused", () => {
+    const onTimeout = vi.fn();
+    renderHook(() => useIdleTimeout({ timeoutSecs: 60, active: true, onTimeout }));
+    const iframe = document.createElement("iframe");
+    document.body.appendChild(iframe);
+    iframe.focus();
+
+    act(() => {
+      vi.advanceTimersByTime(50_000);
+      window.dispatchEvent(new Event("blur"));
+      vi.advanceTimersByTime(50_000);
+    });
+    expect(onTimeout).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,60 @@
+import { useEffect, useRef, useCallback } from "react";
+import { isIdleExpired } from "../utils/preview";
+
+// 経過時間を確認する間隔
+const CHECK_INTERVAL_MS = 5000;
+
+interface UseIdleTimeoutOptions {
+  /** 0なら停止しない */
+  timeoutSecs: number;
+  /** 監視中か（sphinx-autobuildの起動中） */
+  active: boolean;
+  onTimeout: () => void;
+}
+
+/**
+ * 操作がないままtimeoutSecs秒経ったらonTimeoutを呼ぶhook
+ * 返り値の関数を操作のたびに呼んで経過時間をリセットする
+ * プレビューのiframe内の操作はこちらに届かないので、iframeにフォーカスが移った時
+ * （ウィンドウのblurはiframeをクリックした時にも起きる）を1回の操作とみなす
+ */
+export function useIdleTimeout({ timeoutSecs, active, onTimeout }: UseIdleTimeoutOptions) {
+  const lastActivityRef = useRef(Date.now());
+  const onTimeoutRef = useRef(onTimeout);
+  useEffect(() => {
+    onTimeoutRef.current = onTimeout;
+  });
+
+  const markActivity = useCallback(() => {
+    lastActivityRef.current = Date.now();
+  }, []);
+
+  useEffect(() => {
+    if (!active || timeoutSecs <= 0) return;
+    // 起動した時点から数え始める
+    lastActivityRef.current = Date.now();
+    // フォーカスが移った時だけ数え直す（フォーカスしたままの間は操作中とみなさない）
+    const handleFocusIn = (event: FocusEvent) => {
+      if (event.target instanceof HTMLIFrameElement) markActivity();
+    };
+    // ウィンドウから離れただけのblurは操作ではない
+    const handleBlur = () => {
+      if (document.activeElement instanceof HTMLIFrameElement) markActivity();
+    };
+    document.addEventListener("focusin", handleFocusIn);
+    window.addEventListener("blur", handleBlur);
+    const timer = window.setInterval(() => {
+      if (isIdleExpired(lastActivityRef.current, Date.now(), timeoutSecs)) {
+        window.clearInterval(timer);
+        onTimeoutRef.current();
+      }
+    }, CHECK_INTERVAL_MS);
+    return () => {
+      window.clearInterval(timer);
+      document.removeEventListener("focusin", handleFocusIn);
+      window.removeEventListener("blur", handleBlur);
+    };
+  }, [active, timeoutSecs, markActivity]);
+
+  return markActivity;
+}
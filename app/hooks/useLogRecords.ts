@@ -0,0 +1,10 @@
+import { useSyncExternalStore } from "react";
+import { logBuffer, type LogRecord } from "../utils/logger";
+
+/**
+ * アプリ内ログバッファを購読するhook
+ * 新しいログが記録されるたびに再レンダリングされる
+ */
+export function useLogRecords(): LogRecord[] {
+  return useSyncExternalStore(logBuffer.subscribe, logBuffer.getRecords);
+}
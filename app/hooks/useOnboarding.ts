@@ -0,0 +1,69 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { logger } from "../utils/logger";
+import {
+  shouldShowOnboarding,
+  isOnboardingCompleted,
+  completeOnboarding,
+} from "../utils/onboarding";
+
+interface UserConfigFile {
+  path: string;
+  exists: boolean;
+  /** 案内を終えたことが設定ファイルの隣に記録されているか */
+  onboarded: boolean;
+}
+
+interface UseOnboardingOptions {
+  devConfigLoaded: boolean;
+  hasDevProject: boolean;
+}
+
+/**
+ * 初回起動の案内を管理するhook
+ * checkingの間は表示するか未確定なので、起動時のダイアログ表示などを待たせる
+ */
+export function useOnboarding({ devConfigLoaded, hasDevProject }: UseOnboardingOptions) {
+  const [completed, setCompleted] = useState(isOnboardingCompleted);
+  const [configFile, setConfigFile] = useState<UserConfigFile | null>(null);
+
+  useEffect(() => {
+    if (completed) return;
+    invoke<UserConfigFile>("user_config_file")
+      .then((file) => {
+        setConfigFile(file);
+        if (file.onboarded) {
+          completeOnboarding();
+          setCompleted(true);
+        }
+      })
+      .catch((e) => {
+        logger.warn("Failed to check the config file:", e);
+        // 確認できない場合は案内を出さない
+        setConfigFile({ path: "", exists: true, onboarded: false });
+      });
+  }, [completed]);
+
+  const complete = useCallback(() => {
+    completeOnboarding();
+    setCompleted(true);
+    // localStorageはWebViewのデータと一緒に消えることがあるので、設定ファイルの隣にも残す
+    invoke("complete_onboarding").catch((e) =>
+      logger.warn("Failed to record the onboarding completion:", e)
+    );
+  }, []);
+
+  const show = shouldShowOnboarding({
+    devConfigLoaded,
+    hasDevProject,
+    configExists: configFile?.exists ?? null,
+    completed,
+  });
+
+  return {
+    show,
+    checking: !completed && configFile === null,
+    configPath: configFile?.path ?? null,
+    complete,
+  };
+}
@@ -0,0 +1,48 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { Heading } from "../types/outline";
+import { logger } from "../utils/logger";
+
+interface UsePreviewOutlineOptions {
+  /** プレビュー中のページのソースファイル */
+  sourcePath: string | null;
+  /** preview.outline */
+  enabled: boolean;
+  /** 値が変わるたびに読み直す（ビルド完了ごとにソースの変更を反映） */
+  refreshToken: number;
+}
+
+/**
+ * プレビュー中のページの見出し一覧を取得するhook
+ * 無効時やソースが分からない場合は空配列を返す
+ */
+export function usePreviewOutline({
+  sourcePath,
+  enabled,
+  refreshToken,
+}: UsePreviewOutlineOptions): Heading[] {
+  const [headings, setHeadings] = useState<Heading[]>([]);
+
+  useEffect(() => {
+    if (!enabled || !sourcePath) {
+      setHeadings([]);
+      return;
+    }
+
+    let cancelled = false;
+    invoke<Heading[]>("preview_outline", { sourcePath })
+      .then((result) => {
+        if (!cancelled) setHeadings(result);
+      })
+      .catch((e) => {
+        logger.warn("Failed to read preview outline:", e);
+        if (!cancelled) setHeadings([]);
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [sourcePath, enabled, refreshToken]);
+
+  return headings;
+}
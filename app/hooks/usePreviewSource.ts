@@ -0,0 +1,50 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { logger } from "../utils/logger";
+
+interface UsePreviewSourceOptions {
+  /** プレビュー中のページURL */
+  pageUrl: string | null;
+  /** sphinx-autobuildのベースURL */
+  baseUrl: string | null;
+  projectPath: string | null;
+  sourceDir: string | null;
+}
+
+/**
+ * プレビュー中のページに対応するソースファイルを解決するhook
+ * 対応付けできない場合はnullを返す
+ */
+export function usePreviewSource({
+  pageUrl,
+  baseUrl,
+  projectPath,
+  sourceDir,
+}: UsePreviewSourceOptions): string | null {
+  const [sourcePath, setSourcePath] = useState<string | null>(null);
+
+  useEffect(() => {
+    setSourcePath(null);
+    if (!pageUrl || !baseUrl || !projectPath || sourceDir === null) return;
+
+    let cancelled = false;
+    invoke<string | null>("resolve_preview_source", {
+      url: pageUrl,
+      baseUrl,
+      projectPath,
+      sourceDir,
+    })
+      .then((path) => {
+        if (!cancelled) setSourcePath(path);
+      })
+      .catch((e) => {
+        logger.warn("Failed to resolve preview source:", e);
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [pageUrl, baseUrl, projectPath, sourceDir]);
+
+  return sourcePath;
+}
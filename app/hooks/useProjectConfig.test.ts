@@ -0,0 +1,73 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor } from "@testing-library/react";
+import { useProjectConfig } from "./useProjectConfig";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+
+const projectConfig = { sphinx: { source_dir: "doc/source" } };
+
+describe("useProjectConfig", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("should load .khafre.toml of the project", async () => {
+    vi.mocked(invoke).mockResolvedValue(projectConfig);
+
+    const { result } = renderHook(() => useProjectConfig("/path/to/project"));
+
+    expect(result.current.loaded).toBe(false);
+    await waitFor(() => {
+      expect(result.current.loaded).toBe(true);
+    });
+    expect(result.current.projectConfig).toEqual(projectConfig);
+    expect(invoke).toHaveBeenCalledWith("load_project_config", {
+      projectPath: "/path/to/project",
+    });
+  });
+
+  it("should be loaded without a project", () => {
+    const { result } = renderHook(() => useProjectConfig(null));
+
+    expect(result.current.loaded).toBe(true);
+    expect(result.current.projectConfig).toBeNull();
+    expect(invoke).not.toHaveBeenCalled();
+  });
+
+  it("should not use the previous project's config after switching", async () => {
+    vi.mocked(invoke).mockResolvedValue(projectConfig);
+
+    const { result, rerender } = renderHook(({ path }) => useProjectConfig(path), {
+      initialProps: { path: "/path/to/a" },
+    });
+    await waitFor(() => {
+      expect(result.current.projectConfig).toEqual(projectConfig);
+    });
+
+    vi.mocked(invoke).mockResolvedValue(null);
+    rerender({ path: "/path/to/b" });
+
+    expect(result.current.loaded).toBe(false);
+    expect(result.current.projectConfig).toBeNull();
+    await waitFor(() => {
+      expect(result.current.loaded).toBe(true);
+    });
+    expect(result.current.projectConfig).toBeNull();
+  });
+
+  it("should report an unreadable file and fall back to the global config", async () => {
+    vi.mocked(invoke).mockRejectedValue(".khafre.tomlのパースに失敗: invalid TOML");
+
+    const { result } = renderHook(() => useProjectConfig("/path/to/project"));
+
+    await waitFor(() => {
+      expect(result.current.loaded).toBe(true);
+    });
+    expect(result.current.projectConfig).toBeNull();
+    expect(result.current.error).toBe(".khafre.tomlのパースに失敗: invalid TOML");
+  });
+});
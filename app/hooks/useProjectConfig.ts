@@ -0,0 +1,53 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { ConfigOverride } from "../types/devConfig";
+import { logger } from "../utils/logger";
+
+interface UseProjectConfigResult {
+  /** プロジェクトの.khafre.toml（無ければnull） */
+  projectConfig: ConfigOverride | null;
+  /** 開いているプロジェクトの設定を読み込み終えたか */
+  loaded: boolean;
+  error: string | null;
+  /** 読み直し、読み直した設定を返す */
+  reload: () => Promise<ConfigOverride | null>;
+}
+
+/**
+ * プロジェクトごとの設定（プロジェクトルートの.khafre.toml）を読み込むhook
+ * グローバル設定を上書きする（source_dirがdocs以外のプロジェクトなど）
+ * 読めない内容の場合はエラーにして、グローバル設定のまま使う
+ */
+export function useProjectConfig(projectPath: string | null): UseProjectConfigResult {
+  // どのプロジェクトの設定か（プロジェクトを切り替えた直後に前の設定を使わない）
+  const [state, setState] = useState<{ path: string | null; config: ConfigOverride | null }>({
+    path: null,
+    config: null,
+  });
+  const [error, setError] = useState<string | null>(null);
+
+  const reload = useCallback(async () => {
+    if (!projectPath) {
+      setState({ path: null, config: null });
+      return null;
+    }
+    try {
+      const config = await invoke<ConfigOverride | null>("load_project_config", { projectPath });
+      setState({ path: projectPath, config });
+      setError(null);
+      return config;
+    } catch (e) {
+      logger.warn("Failed to load .khafre.toml:", e);
+      setState({ path: projectPath, config: null });
+      setError(String(e));
+      return null;
+    }
+  }, [projectPath]);
+
+  useEffect(() => {
+    reload();
+  }, [reload]);
+
+  const loaded = state.path === projectPath;
+  return { projectConfig: loaded ? state.config : null, loaded, error, reload };
+}
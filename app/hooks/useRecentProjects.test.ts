@@ -0,0 +1,30 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { renderHook } from "@testing-library/react";
+import { useRecentProjects } from "./useRecentProjects";
+import { loadRecentProjects, storeRecentProjects } from "../utils/projects";
+
+describe("useRecentProjects", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should move the opened project to the top and store it", () => {
+    storeRecentProjects(["/a", "/b"]);
+    const { result, rerender } = renderHook(({ path }) => useRecentProjects(path), {
+      initialProps: { path: "/b" as string | null },
+    });
+    expect(result.current).toEqual(["/b", "/a"]);
+
+    rerender({ path: "/c" });
+    expect(result.current).toEqual(["/c", "/b", "/a"]);
+    expect(loadRecentProjects()).toEqual(["/c", "/b", "/a"]);
+  });
+
+  it("should store the same list in StrictMode", () => {
+    storeRecentProjects(["/a"]);
+    const { result } = renderHook(() => useRecentProjects("/b"), { reactStrictMode: true });
+
+    expect(result.current).toEqual(["/b", "/a"]);
+    expect(loadRecentProjects()).toEqual(["/b", "/a"]);
+  });
+});
@@ -0,0 +1,20 @@
+import { useState, useEffect } from "react";
+import { addRecentProject, loadRecentProjects, storeRecentProjects } from "../utils/projects";
+
+/**
+ * 最近開いたプロジェクト（新しい順）を管理するhook
+ * projectPathが変わるたびに先頭へ追加して保存する
+ */
+export function useRecentProjects(projectPath: string | null): string[] {
+  const [recent, setRecent] = useState<string[]>(loadRecentProjects);
+
+  useEffect(() => {
+    if (!projectPath) return;
+    // 保存済みの一覧に追加する（setStateのupdaterの中では保存しない）
+    const next = addRecentProject(loadRecentProjects(), projectPath);
+    storeRecentProjects(next);
+    setRecent(next);
+  }, [projectPath]);
+
+  return recent;
+}
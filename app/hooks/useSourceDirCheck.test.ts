@@ -0,0 +1,92 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor, act } from "@testing-library/react";
+import { useSourceDirCheck } from "./useSourceDirCheck";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("@tauri-apps/plugin-dialog", () => ({
+  open: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+
+describe("useSourceDirCheck", () => {
+  const onSaved = vi.fn(async () => {});
+  const options = {
+    projectPath: "/path/to/project",
+    sourceDir: "docs",
+    enabled: true,
+    onSaved,
+  };
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("should report a source_dir without conf.py with the detected directory", async () => {
+    vi.mocked(invoke).mockResolvedValue({ suggestion: "doc/source" });
+
+    const { result } = renderHook(() => useSourceDirCheck(options));
+
+    await waitFor(() => {
+      expect(result.current.missing).toEqual({ suggestion: "doc/source" });
+    });
+    expect(invoke).toHaveBeenCalledWith("check_sphinx_source_dir", {
+      projectPath: "/path/to/project",
+      sourceDir: "docs",
+    });
+  });
+
+  it("should not check when the preview is disabled", () => {
+    const { result } = renderHook(() => useSourceDirCheck({ ...options, enabled: false }));
+
+    expect(result.current.missing).toBeNull();
+    expect(invoke).not.toHaveBeenCalled();
+  });
+
+  it("should save the suggested directory and reload the project config", async () => {
+    vi.mocked(invoke).mockResolvedValue(null);
+
+    const { result } = renderHook(() => useSourceDirCheck(options));
+    await act(async () => {
+      await result.current.save("doc/source");
+    });
+
+    expect(invoke).toHaveBeenCalledWith("save_project_source_dir", {
+      projectPath: "/path/to/project",
+      sourceDir: "doc/source",
+    });
+    expect(onSaved).toHaveBeenCalled();
+  });
+
+  it("should save a chosen folder relative to the project", async () => {
+    vi.mocked(invoke).mockResolvedValue(null);
+    vi.mocked(open).mockResolvedValue("/path/to/project/manual");
+
+    const { result } = renderHook(() => useSourceDirCheck(options));
+    await act(async () => {
+      await result.current.choose();
+    });
+
+    expect(invoke).toHaveBeenCalledWith("save_project_source_dir", {
+      projectPath: "/path/to/project",
+      sourceDir: "manual",
+    });
+  });
+
+  it("should ignore a folder outside the project", async () => {
+    vi.mocked(invoke).mockResolvedValue(null);
+    vi.mocked(open).mockResolvedValue("/somewhere/else");
+
+    const { result } = renderHook(() => useSourceDirCheck(options));
+    await act(async () => {
+      await result.current.choose();
+    });
+
+    expect(invoke).not.toHaveBeenCalledWith("save_project_source_dir", expect.anything());
+    expect(onSaved).not.toHaveBeenCalled();
+  });
+});
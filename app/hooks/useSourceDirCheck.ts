@@ -0,0 +1,79 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import type { MissingSourceDir } from "../types/sphinx";
+import { logger } from "../utils/logger";
+import { projectRelativePath } from "../utils/projects";
+
+interface UseSourceDirCheckOptions {
+  projectPath: string | null;
+  sourceDir: string | undefined;
+  /** プレビュー無効時は確認しない */
+  enabled: boolean;
+  /** .khafre.tomlに保存した後に呼ぶ（プロジェクト設定の読み直し） */
+  onSaved: () => Promise<unknown>;
+}
+
+/**
+ * sphinx.source_dirにconf.pyがあるか確認するhook
+ * 無ければプロジェクト内で見つけたconf.pyの場所を提案し、選んだディレクトリを.khafre.tomlに保存する
+ */
+export function useSourceDirCheck({
+  projectPath,
+  sourceDir,
+  enabled,
+  onSaved,
+}: UseSourceDirCheckOptions) {
+  const [missing, setMissing] = useState<MissingSourceDir | null>(null);
+
+  useEffect(() => {
+    setMissing(null);
+    if (!projectPath || !sourceDir || !enabled) return;
+    let disposed = false;
+    invoke<MissingSourceDir | null>("check_sphinx_source_dir", { projectPath, sourceDir })
+      .then((result) => {
+        if (!disposed) setMissing(result);
+      })
+      .catch((e) => logger.error("Failed to check sphinx.source_dir:", e));
+    return () => {
+      disposed = true;
+    };
+  }, [projectPath, sourceDir, enabled]);
+
+  const save = useCallback(
+    async (dir: string) => {
+      if (!projectPath) return;
+      try {
+        await invoke("save_project_source_dir", { projectPath, sourceDir: dir });
+        logger.info(`Saved sphinx.source_dir = "${dir}" to .khafre.toml`);
+        await onSaved();
+      } catch (e) {
+        logger.error("Failed to save .khafre.toml:", e);
+      }
+    },
+    [projectPath, onSaved]
+  );
+
+  // フォルダを選んでsource_dirにする（プロジェクトの外は選べない）
+  const choose = useCallback(async () => {
+    if (!projectPath) return;
+    try {
+      const selected = await open({
+        title: "Select Sphinx Source Folder (with conf.py)",
+        directory: true,
+        defaultPath: projectPath,
+      });
+      if (!selected || typeof selected !== "string") return;
+      const dir = projectRelativePath(projectPath, selected);
+      if (dir === null) {
+        logger.warn("The source folder must be inside the project:", selected);
+        return;
+      }
+      await save(dir);
+    } catch (e) {
+      logger.error("Failed to open folder dialog:", e);
+    }
+  }, [projectPath, save]);
+
+  return { missing, save, choose };
+}
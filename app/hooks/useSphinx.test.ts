@@ -0,0 +1,130 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, act, waitFor } from "@testing-library/react";
+import { useSphinx } from "./useSphinx";
+import type { ProjectConfig } from "../types/config";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+// イベントのリスナーを呼べるように保持する
+const eventListeners = new Map<string, (event: { payload: unknown }) => void>();
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn(async (event: string, handler: (event: { payload: unknown }) => void) => {
+    eventListeners.set(event, handler);
+    return () => {};
+  }),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { logger } from "../utils/logger";
+
+const createConfig = (sourceDir: string, port: number) =>
+  ({
+    sphinx: {
+      source_dir: sourceDir,
+      build_dir: "_build/html",
+      server: { port },
+      extra_args: [],
+      idle_timeout_secs: 0,
+      max_restarts: 3,
+      success_patterns: ["build succeeded"],
+      error_patterns: ["ERROR"],
+      health_check_interval_secs: 0,
+      health_check_failures: 3,
+      env: {},
+      inherit_env: true,
+    },
+    python: { interpreter: ".venv/bin/python", runner: "auto" },
+    editor: { command: "nvim" },
+    preview: { enabled: true },
+  }) as unknown as ProjectConfig;
+
+describe("useSphinx", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(invoke).mockResolvedValue(8000);
+  });
+
+  it("should start with the config of the hook", async () => {
+    const { result } = renderHook(() =>
+      useSphinx({ sessionId: "main", projectPath: "/project", config: createConfig("docs", 0) })
+    );
+
+    await act(async () => {
+      await result.current.start();
+    });
+
+    expect(invoke).toHaveBeenCalledWith(
+      "start_sphinx",
+      expect.objectContaining({ sessionId: "main", sourceDir: "docs", port: 0 })
+    );
+  });
+
+  it("should restart with the reloaded config instead of the previous one", async () => {
+    const { result } = renderHook(() =>
+      useSphinx({ sessionId: "main", projectPath: "/project", config: createConfig("docs", 0) })
+    );
+
+    let error: string | null = "not called";
+    await act(async () => {
+      error = await result.current.restartWith(createConfig("doc/source", 8001));
+    });
+
+    expect(error).toBeNull();
+    expect(invoke).toHaveBeenCalledWith(
+      "start_sphinx",
+      expect.objectContaining({ sourceDir: "doc/source", port: 8001 })
+    );
+    expect(invoke).not.toHaveBeenCalledWith("restart_sphinx", expect.anything());
+  });
+
+  it("should stop when the reloaded config disables the preview", async () => {
+    const { result } = renderHook(() =>
+      useSphinx({ sessionId: "main", projectPath: "/project", config: createConfig("docs", 0) })
+    );
+    const disabled = { ...createConfig("docs", 0), preview: { enabled: false } } as ProjectConfig;
+
+    await act(async () => {
+      await result.current.restartWith(disabled);
+    });
+
+    expect(invoke).toHaveBeenCalledWith("stop_sphinx", { sessionId: "main" });
+    expect(invoke).not.toHaveBeenCalledWith("start_sphinx", expect.anything());
+  });
+
+  it("should report the detected interpreter used instead of a missing one", async () => {
+    const { result } = renderHook(() =>
+      useSphinx({ sessionId: "main", projectPath: "/project", config: createConfig("docs", 0) })
+    );
+    await waitFor(() => expect(eventListeners.has("sphinx_interpreter_fallback")).toBe(true));
+    const emit = (payload: unknown) =>
+      act(() => {
+        eventListeners.get("sphinx_interpreter_fallback")?.({ payload });
+      });
+
+    emit(["other", ".venv/bin/python", "/usr/bin/python3"]);
+    expect(result.current.interpreterFallback).toBeNull();
+
+    emit(["main", ".venv/bin/python", "/usr/bin/python3"]);
+    expect(result.current.interpreterFallback).toEqual({
+      configured: ".venv/bin/python",
+      actual: "/usr/bin/python3",
+    });
+  });
+
+  it("should log warnings about the interpreter of its own session", async () => {
+    const warn = vi.spyOn(logger, "warn").mockImplementation(() => {});
+    renderHook(() =>
+      useSphinx({ sessionId: "main", projectPath: "/project", config: createConfig("docs", 0) })
+    );
+    await waitFor(() => expect(eventListeners.has("sphinx_warning")).toBe(true));
+
+    eventListeners.get("sphinx_warning")?.({ payload: ["other", "ignored"] });
+    eventListeners.get("sphinx_warning")?.({ payload: ["main", "pyenv is not installed"] });
+
+    expect(warn).toHaveBeenCalledTimes(1);
+    expect(warn).toHaveBeenCalledWith("pyenv is not installed");
+    warn.mockRestore();
+  });
+});
@@ -1,7 +1,11 @@
-import { useState, useEffect, useCallback } from "react";
+import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import type { ProjectConfig } from "../types/config";
+import { logger } from "../utils/logger";
+import { nextRestart } from "../utils/restart";
+import type { HealthStatus } from "../utils/health";
+import { useHealthCheck } from "./useHealthCheck";
 
 interface UseSphinxOptions {
   sessionId: string;
@@ -9,13 +13,37 @@ interface UseSphinxOptions {
   config: ProjectConfig | null;
 }
 
+export interface PortFallback {
+  requested: number;
+  actual: number;
+}
+
+export interface InterpreterFallback {
+  configured: string;
+  actual: string;
+}
+
 interface UseSphinxResult {
   previewUrl: string | null;
   isRunning: boolean;
   error: string | null;
+  /** このセッションで完了したビルド数 */
+  buildCount: number;
+  /** プレビューサーバーへの接続確認の状態（sphinx.health_check_interval_secs） */
+  health: HealthStatus;
+  /** sphinx.server.portが使用中で、別のポートで起動した場合のポート */
+  portFallback: PortFallback | null;
+  /** python.interpreterが見つからず、自動検出したインタプリタで起動した場合のインタプリタ */
+  interpreterFallback: InterpreterFallback | null;
   start: () => Promise<void>;
   stop: () => Promise<void>;
+  /** 同じ設定のまま起動し直す */
+  restart: () => Promise<void>;
+  /** 読み直した設定で起動し直す（起動中なら止めてから。起動できなければエラーを返す） */
+  restartWith: (config: ProjectConfig) => Promise<string | null>;
   openInBrowser: () => Promise<void>;
+  revealBuildDir: () => Promise<void>;
+  copyIssueReport: () => Promise<void>;
 }
 
 /**
@@ -25,45 +53,114 @@ export function useSphinx({ sessionId, projectPath, config }: UseSphinxOptions):
   const [port, setPort] = useState<number | null>(null);
   const [isRunning, setIsRunning] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [buildCount, setBuildCount] = useState(0);
+  const [portFallback, setPortFallback] = useState<PortFallback | null>(null);
+  const [interpreterFallback, setInterpreterFallback] = useState<InterpreterFallback | null>(
+    null
+  );
+  // 異常終了後の自動再起動（sphinx.max_restarts）。回数はビルド成功でリセット
+  const restartAttemptsRef = useRef(0);
+  const restartTimerRef = useRef<number | null>(null);
+  const lastErrorRef = useRef<string | null>(null);
+  const cancelRestart = useCallback(() => {
+    if (restartTimerRef.current !== null) {
+      window.clearTimeout(restartTimerRef.current);
+      restartTimerRef.current = null;
+    }
+  }, []);
 
   const previewUrl = port ? `http://127.0.0.1:${port}` : null;
 
-  const start = useCallback(async () => {
-    if (!projectPath || !config) {
-      setError("Project path or config is missing");
-      return;
-    }
+  // 起動できなかった場合はエラーを返す（「すべて再読み込み」で失敗を報告するため）
+  const launch = useCallback(
+    async (launchConfig: ProjectConfig | null): Promise<string | null> => {
+      if (!projectPath || !launchConfig) {
+        setError("Project path or config is missing");
+        return "Project path or config is missing";
+      }
+      // ターミナルのみで使う設定ではsphinx-autobuildを起動しない
+      if (!launchConfig.preview.enabled) return null;
+      cancelRestart();
+
+      try {
+        setError(null);
+        setPortFallback(null);
+        setInterpreterFallback(null);
+        // プロセス起動のみ、ポート設定はsphinx_startedイベントで行う
+        await invoke<number>("start_sphinx", {
+          sessionId,
+          projectPath,
+          sourceDir: launchConfig.sphinx.source_dir,
+          buildDir: launchConfig.sphinx.build_dir,
+          pythonPath: launchConfig.python.interpreter,
+          pythonRunner: launchConfig.python.runner,
+          port: launchConfig.sphinx.server.port,
+          extraArgs: launchConfig.sphinx.extra_args,
+          env: launchConfig.sphinx.env,
+          inheritEnv: launchConfig.sphinx.inherit_env,
+          successPatterns: launchConfig.sphinx.success_patterns,
+          errorPatterns: launchConfig.sphinx.error_patterns,
+        });
+        // ビルド中状態（ポートはまだ設定しない）
+        setIsRunning(true);
+        return null;
+      } catch (e) {
+        setError(String(e));
+        setIsRunning(false);
+        return String(e);
+      }
+    },
+    [sessionId, projectPath, cancelRestart]
+  );
 
+  const start = useCallback(() => launch(config), [launch, config]);
+
+  const stop = useCallback(async () => {
+    cancelRestart();
+    restartAttemptsRef.current = 0;
     try {
+      await invoke("stop_sphinx", { sessionId });
+      setPort(null);
+      setIsRunning(false);
       setError(null);
-      // プロセス起動のみ、ポート設定はsphinx_startedイベントで行う
-      await invoke<number>("start_sphinx", {
-        sessionId,
-        projectPath,
-        sourceDir: config.sphinx.source_dir,
-        buildDir: config.sphinx.build_dir,
-        pythonPath: config.python.interpreter,
-        port: config.sphinx.server.port,
-        extraArgs: config.sphinx.extra_args,
-      });
-      // ビルド中状態（ポートはまだ設定しない）
-      setIsRunning(true);
+      setPortFallback(null);
     } catch (e) {
       setError(String(e));
-      setIsRunning(false);
     }
-  }, [sessionId, projectPath, config]);
+  }, [sessionId, cancelRestart]);
 
-  const stop = useCallback(async () => {
-    try {
-      await invoke("stop_sphinx", { sessionId });
+  // restart_sphinxは前回の起動時の設定を使うので、設定を読み直した場合は新しい設定で起動し直す
+  const restartWith = useCallback(
+    async (nextConfig: ProjectConfig) => {
+      if (!nextConfig.preview.enabled) {
+        await stop();
+        return null;
+      }
+      restartAttemptsRef.current = 0;
+      // 既存のプロセスはstart_sphinxが止める。ポートはsphinx_startedイベントで改めて設定する
       setPort(null);
-      setIsRunning(false);
+      return launch(nextConfig);
+    },
+    [launch, stop]
+  );
+
+  // conf.pyの変更など、sphinx-autobuildが拾わない変更を反映するために起動し直す
+  const restart = useCallback(async (): Promise<string | null> => {
+    cancelRestart();
+    restartAttemptsRef.current = 0;
+    try {
       setError(null);
+      // ポートはsphinx_startedイベントで改めて設定する
+      setPort(null);
+      setPortFallback(null);
+      await invoke<number>("restart_sphinx", { sessionId });
+      setIsRunning(true);
+      return null;
     } catch (e) {
       setError(String(e));
+      return String(e);
     }
-  }, [sessionId]);
+  }, [sessionId, cancelRestart]);
 
   const openInBrowser = useCallback(async () => {
     if (previewUrl) {
@@ -75,11 +172,100 @@ export function useSphinx({ sessionId, projectPath, config }: UseSphinxOptions):
     }
   }, [previewUrl]);
 
+  // ビルド出力ディレクトリをファイルマネージャで開く
+  const revealBuildDir = useCallback(async () => {
+    if (!projectPath || !config) return;
+    try {
+      await invoke("reveal_build_dir", { projectPath, buildDir: config.sphinx.build_dir });
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [projectPath, config]);
+
+  // エラー内容を報告用のMarkdownとしてクリップボードにコピー
+  const copyIssueReport = useCallback(async () => {
+    if (!error || !projectPath || !config) return;
+    try {
+      const report = await invoke<string>("format_sphinx_issue", {
+        error,
+        projectPath,
+        sourceDir: config.sphinx.source_dir,
+        buildDir: config.sphinx.build_dir,
+        pythonPath: config.python.interpreter,
+        pythonRunner: config.python.runner,
+        port: port ?? config.sphinx.server.port,
+        extraArgs: config.sphinx.extra_args,
+      });
+      await navigator.clipboard.writeText(report);
+    } catch (e) {
+      logger.error("Failed to copy issue report:", e);
+    }
+  }, [error, projectPath, config, port]);
+
+  // イベントハンドラから最新のstartと設定を参照する
+  const startRef = useRef(start);
+  startRef.current = start;
+  const maxRestartsRef = useRef(config?.sphinx.max_restarts ?? 0);
+  maxRestartsRef.current = config?.sphinx.max_restarts ?? 0;
+  const autoPortRef = useRef(config?.sphinx.server.port === 0);
+  autoPortRef.current = config?.sphinx.server.port === 0;
+
+  // 異常終了（または応答なし）の後、待ち時間を空けて再起動し、上限に達したらエラーとして止める
+  // ポートが使用中で終了した場合、自動割り当てなら別のポートですぐに起動し直す
+  const recover = useCallback(
+    (portInUse: boolean) => {
+      setPort(null);
+      const decision = nextRestart(
+        restartAttemptsRef.current,
+        maxRestartsRef.current,
+        lastErrorRef.current,
+        portInUse && autoPortRef.current
+      );
+      if (decision.type === "give_up") {
+        logger.error(decision.message);
+        setIsRunning(false);
+        setError(decision.message);
+        return;
+      }
+      restartAttemptsRef.current = decision.attempt;
+      logger.warn(
+        `sphinx-autobuild exited, restarting in ${decision.delayMs}ms ` +
+          `(${decision.attempt}/${maxRestartsRef.current})`
+      );
+      cancelRestart();
+      restartTimerRef.current = window.setTimeout(() => {
+        restartTimerRef.current = null;
+        startRef.current();
+      }, decision.delayMs);
+    },
+    [cancelRestart]
+  );
+
+  // 起動中はサーバーが応答しているか確認し、応答しなくなったら異常終了と同じく扱う
+  const health = useHealthCheck({
+    url: isRunning ? previewUrl : null,
+    intervalSecs: config?.sphinx.health_check_interval_secs ?? 0,
+    failureThreshold: config?.sphinx.health_check_failures ?? 1,
+    onDown: () => {
+      logger.warn("Preview server stopped responding:", previewUrl);
+      lastErrorRef.current = "the preview server stopped responding";
+      invoke("stop_sphinx", { sessionId })
+        .catch((e) => logger.error("Failed to stop sphinx-autobuild:", e))
+        .finally(() => recover(false));
+    },
+  });
+
   // Sphinxイベントをリッスン
   useEffect(() => {
     let unlistenStarted: UnlistenFn | null = null;
     let unlistenError: UnlistenFn | null = null;
     let unlistenBuilt: UnlistenFn | null = null;
+    let unlistenExited: UnlistenFn | null = null;
+    let unlistenPortFallback: UnlistenFn | null = null;
+    let unlistenInterpreterFallback: UnlistenFn | null = null;
+    let unlistenWarning: UnlistenFn | null = null;
+    restartAttemptsRef.current = 0;
+    lastErrorRef.current = null;
 
     const setup = async () => {
       unlistenStarted = await listen<[string, number]>("sphinx_started", (event) => {
@@ -93,15 +279,51 @@ export function useSphinx({ sessionId, projectPath, config }: UseSphinxOptions):
       unlistenError = await listen<[string, string]>("sphinx_error", (event) => {
         const [sid, errorMsg] = event.payload;
         if (sid === sessionId) {
+          lastErrorRef.current = errorMsg;
           setError(errorMsg);
         }
       });
 
       unlistenBuilt = await listen<string>("sphinx_built", (event) => {
         if (event.payload === sessionId) {
-          // ビルド完了時にエラーをクリア
+          // ビルド完了時にエラーと再起動回数をクリア
           setError(null);
+          lastErrorRef.current = null;
+          restartAttemptsRef.current = 0;
+          setBuildCount((count) => count + 1);
+        }
+      });
+
+      unlistenPortFallback = await listen<[string, number, number]>(
+        "sphinx_port_fallback",
+        (event) => {
+          const [sid, requested, actual] = event.payload;
+          if (sid === sessionId) {
+            logger.warn(`Port ${requested} is in use, serving the preview on port ${actual}`);
+            setPortFallback({ requested, actual });
+          }
+        }
+      );
+
+      unlistenWarning = await listen<[string, string]>("sphinx_warning", (event) => {
+        const [sid, message] = event.payload;
+        if (sid === sessionId) logger.warn(message);
+      });
+
+      unlistenInterpreterFallback = await listen<[string, string, string]>(
+        "sphinx_interpreter_fallback",
+        (event) => {
+          const [sid, configured, actual] = event.payload;
+          if (sid === sessionId) {
+            logger.warn(`Python interpreter ${configured} was not found, using ${actual}`);
+            setInterpreterFallback({ configured, actual });
+          }
         }
+      );
+
+      unlistenExited = await listen<[string, boolean]>("sphinx_exited", (event) => {
+        const [sid, portInUse] = event.payload;
+        if (sid === sessionId) recover(portInUse);
       });
     };
 
@@ -111,8 +333,13 @@ export function useSphinx({ sessionId, projectPath, config }: UseSphinxOptions):
       unlistenStarted?.();
       unlistenError?.();
       unlistenBuilt?.();
+      unlistenExited?.();
+      unlistenPortFallback?.();
+      unlistenInterpreterFallback?.();
+      unlistenWarning?.();
+      cancelRestart();
     };
-  }, [sessionId]);
+  }, [sessionId, cancelRestart, recover]);
 
   // アンマウント時にSphinxを停止
   useEffect(() => {
@@ -123,5 +350,20 @@ export function useSphinx({ sessionId, projectPath, config }: UseSphinxOptions):
     };
   }, [sessionId]);
 
-  return { previewUrl, isRunning, error, start, stop, openInBrowser };
+  return {
+    previewUrl,
+    isRunning,
+    error,
+    buildCount,
+    health,
+    portFallback,
+    interpreterFallback,
+    start,
+    stop,
+    restart,
+    restartWith,
+    openInBrowser,
+    revealBuildDir,
+    copyIssueReport,
+  };
 }
@@ -0,0 +1,84 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor, act } from "@testing-library/react";
+import { useSphinxDiagnostics } from "./useSphinxDiagnostics";
+import type { SphinxDiagnostic } from "../types/sphinx";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+// イベントのリスナーを呼べるように保持する
+const eventListeners = new Map<string, (event: { payload: unknown }) => void>();
+const unlisten = vi.fn();
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn(async (event: string, handler: (event: { payload: unknown }) => void) => {
+    eventListeners.set(event, handler);
+    return unlisten;
+  }),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+
+const emit = (event: string, payload: unknown) =>
+  act(() => {
+    eventListeners.get(event)?.({ payload });
+  });
+
+const warning: SphinxDiagnostic = {
+  file: "/docs/index.rst",
+  line: 12,
+  severity: "warning",
+  message: "Title underline too short.",
+};
+
+describe("useSphinxDiagnostics", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    eventListeners.clear();
+  });
+
+  it("should load the diagnostics of the current build", async () => {
+    vi.mocked(invoke).mockResolvedValue([warning]);
+
+    const { result } = renderHook(() => useSphinxDiagnostics("main"));
+
+    await waitFor(() => {
+      expect(result.current).toEqual([warning]);
+    });
+    expect(invoke).toHaveBeenCalledWith("get_sphinx_diagnostics", { sessionId: "main" });
+  });
+
+  it("should collect diagnostics and clear them when a build starts", async () => {
+    vi.mocked(invoke).mockResolvedValue([]);
+
+    const { result } = renderHook(() => useSphinxDiagnostics("main"));
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalled();
+    });
+
+    emit("sphinx_diagnostic", ["main", warning]);
+    emit("sphinx_diagnostic", ["other", { ...warning, file: "/other/index.rst" }]);
+    expect(result.current).toEqual([warning]);
+
+    emit("sphinx_build_started", "other");
+    expect(result.current).toEqual([warning]);
+    emit("sphinx_build_started", "main");
+    expect(result.current).toEqual([]);
+  });
+
+  it("should stop listening on unmount", async () => {
+    vi.mocked(invoke).mockResolvedValue([]);
+
+    const { unmount } = renderHook(() => useSphinxDiagnostics("main"));
+
+    await waitFor(() => {
+      expect(eventListeners.has("sphinx_diagnostic")).toBe(true);
+    });
+    unmount();
+
+    await waitFor(() => {
+      expect(unlisten).toHaveBeenCalledTimes(2);
+    });
+  });
+});
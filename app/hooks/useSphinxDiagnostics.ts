@@ -0,0 +1,55 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import type { SphinxDiagnostic } from "../types/sphinx";
+import { logger } from "../utils/logger";
+
+/**
+ * 直近のビルドの警告・エラーを購読するhook
+ * ビルドが始まるたびに前回のビルドの分は破棄する
+ */
+export function useSphinxDiagnostics(sessionId: string): SphinxDiagnostic[] {
+  const [diagnostics, setDiagnostics] = useState<SphinxDiagnostic[]>([]);
+
+  useEffect(() => {
+    let disposed = false;
+    let unlistenStarted: UnlistenFn | null = null;
+    let unlistenDiagnostic: UnlistenFn | null = null;
+    setDiagnostics([]);
+
+    const setup = async () => {
+      const started = await listen<string>("sphinx_build_started", (event) => {
+        if (event.payload === sessionId) setDiagnostics([]);
+      });
+      const diagnostic = await listen<[string, SphinxDiagnostic]>(
+        "sphinx_diagnostic",
+        (event) => {
+          const [sid, item] = event.payload;
+          if (sid === sessionId) setDiagnostics((prev) => [...prev, item]);
+        }
+      );
+      if (disposed) {
+        started();
+        diagnostic();
+        return;
+      }
+      unlistenStarted = started;
+      unlistenDiagnostic = diagnostic;
+      try {
+        setDiagnostics(await invoke<SphinxDiagnostic[]>("get_sphinx_diagnostics", { sessionId }));
+      } catch (e) {
+        logger.error("Failed to load sphinx warnings:", e);
+      }
+    };
+
+    setup();
+
+    return () => {
+      disposed = true;
+      unlistenStarted?.();
+      unlistenDiagnostic?.();
+    };
+  }, [sessionId]);
+
+  return diagnostics;
+}
@@ -0,0 +1,70 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { renderHook, waitFor, act } from "@testing-library/react";
+import { useSphinxLog } from "./useSphinxLog";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+// sphinx_logのリスナーを呼べるように保持する
+type LogHandler = (event: { payload: [string, string, string] }) => void;
+const eventListeners = new Map<string, LogHandler>();
+const unlisten = vi.fn();
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn(async (event: string, handler: LogHandler) => {
+    eventListeners.set(event, handler);
+    return unlisten;
+  }),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+
+const emitLine = (sessionId: string, line: string) =>
+  act(() => {
+    eventListeners.get("sphinx_log")?.({ payload: [sessionId, line, "stderr"] });
+  });
+
+describe("useSphinxLog", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    eventListeners.clear();
+  });
+
+  it("should not load the log while disabled", () => {
+    const { result } = renderHook(() => useSphinxLog("main", false));
+
+    expect(result.current).toEqual([]);
+    expect(invoke).not.toHaveBeenCalled();
+  });
+
+  it("should load the captured log and append new lines", async () => {
+    vi.mocked(invoke).mockResolvedValue(["Running Sphinx v8.1.3"]);
+
+    const { result } = renderHook(() => useSphinxLog("main", true));
+
+    await waitFor(() => {
+      expect(result.current).toEqual(["Running Sphinx v8.1.3"]);
+    });
+    expect(invoke).toHaveBeenCalledWith("get_sphinx_log", { sessionId: "main" });
+
+    emitLine("main", "build succeeded.");
+    emitLine("other", "from another session");
+
+    expect(result.current).toEqual(["Running Sphinx v8.1.3", "build succeeded."]);
+  });
+
+  it("should stop listening on unmount", async () => {
+    vi.mocked(invoke).mockResolvedValue([]);
+
+    const { unmount } = renderHook(() => useSphinxLog("main", true));
+
+    await waitFor(() => {
+      expect(eventListeners.has("sphinx_log")).toBe(true);
+    });
+    unmount();
+
+    await waitFor(() => {
+      expect(unlisten).toHaveBeenCalled();
+    });
+  });
+});
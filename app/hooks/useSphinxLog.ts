@@ -0,0 +1,49 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { logger } from "../utils/logger";
+
+/** 保持する最大行数（バックエンドのLOG_CAPACITYと同じ） */
+const MAX_LOG_LINES = 1000;
+
+/**
+ * sphinx-autobuildの出力を購読するhook
+ * enabledの間だけ、保持済みの出力を取得してから新しい行を追記する
+ */
+export function useSphinxLog(sessionId: string, enabled: boolean): string[] {
+  const [lines, setLines] = useState<string[]>([]);
+
+  useEffect(() => {
+    if (!enabled) return;
+    let disposed = false;
+    let unlisten: UnlistenFn | null = null;
+
+    const setup = async () => {
+      const fn = await listen<[string, string, string]>("sphinx_log", (event) => {
+        const [sid, line] = event.payload;
+        if (sid === sessionId) {
+          setLines((prev) => [...prev.slice(-(MAX_LOG_LINES - 1)), line]);
+        }
+      });
+      if (disposed) {
+        fn();
+        return;
+      }
+      unlisten = fn;
+      try {
+        setLines(await invoke<string[]>("get_sphinx_log", { sessionId }));
+      } catch (e) {
+        logger.error("Failed to load the sphinx-autobuild log:", e);
+      }
+    };
+
+    setup();
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [sessionId, enabled]);
+
+  return lines;
+}
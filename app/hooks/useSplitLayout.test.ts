@@ -0,0 +1,85 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+import { renderHook, act } from "@testing-library/react";
+import { useSplitLayout } from "./useSplitLayout";
+import { loadSplitLayout } from "../utils/layout";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(async () => undefined),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+
+describe("useSplitLayout", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.useFakeTimers();
+    localStorage.clear();
+  });
+
+  afterEach(() => {
+    vi.useRealTimers();
+  });
+
+  it("should use ui.split_ratio from the config", () => {
+    const { result, rerender } = renderHook(({ ratio }) => useSplitLayout(ratio), {
+      initialProps: { ratio: 40 as number | null },
+    });
+    expect(result.current.ratio).toBeCloseTo(0.4);
+
+    rerender({ ratio: 70 });
+    expect(result.current.ratio).toBeCloseTo(0.7);
+  });
+
+  it("should apply and store consecutive changes in one update", () => {
+    const { result } = renderHook(() => useSplitLayout(null));
+
+    act(() => {
+      result.current.setRatio(0.3);
+      result.current.setOrientation("vertical");
+    });
+
+    expect(result.current.ratio).toBeCloseTo(0.3);
+    expect(result.current.orientation).toBe("vertical");
+    expect(loadSplitLayout()).toEqual({ ratio: 0.3, orientation: "vertical" });
+  });
+
+  it("should save the ratio once the changes stop", () => {
+    const { result } = renderHook(() => useSplitLayout(null));
+
+    act(() => {
+      result.current.setRatio(0.3);
+      result.current.setRatio(0.35);
+    });
+    expect(invoke).not.toHaveBeenCalled();
+
+    act(() => {
+      vi.advanceTimersByTime(500);
+    });
+    expect(invoke).toHaveBeenCalledTimes(1);
+    expect(invoke).toHaveBeenCalledWith("save_split_ratio", { ratio: 35 });
+  });
+
+  it("should not jump back when the saved ratio is reloaded during a drag", () => {
+    const { result, rerender } = renderHook(({ ratio }) => useSplitLayout(ratio), {
+      initialProps: { ratio: 50 as number | null },
+    });
+
+    act(() => {
+      result.current.setRatio(0.3);
+      vi.advanceTimersByTime(500);
+    });
+    // 保存した値を読み直す前に、ドラッグを続けている
+    act(() => {
+      result.current.setRatio(0.6);
+    });
+    rerender({ ratio: 30 });
+    expect(result.current.ratio).toBeCloseTo(0.6);
+
+    // 保存し終えた値の読み直しでも変わらない
+    act(() => {
+      vi.advanceTimersByTime(500);
+    });
+    rerender({ ratio: 60 });
+    expect(result.current.ratio).toBeCloseTo(0.6);
+  });
+});
@@ -0,0 +1,74 @@
+import { useState, useCallback, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import {
+  MIN_SPLIT_RATIO,
+  SplitLayout,
+  SplitOrientation,
+  clampRatio,
+  configSplitRatio,
+  loadSplitLayout,
+  setSplitRatio,
+  setSplitOrientation,
+  toConfigSplitRatio,
+} from "../utils/layout";
+import { logger } from "../utils/logger";
+
+// 比率の変更が止まってから設定ファイルに保存するまでの時間
+const SAVE_DELAY_MS = 500;
+
+/**
+ * 分割ビューの比率と並べ方を管理するhook（変更は保存され、次回起動時に復元される）
+ * ドラッグ・キーボード・制御ソケットのいずれからも同じ関数で変更する
+ * 比率は設定ファイルのui.split_ratio（%）にも保存し、読み込んだ設定の値を優先する
+ */
+export function useSplitLayout(configRatio?: number | null) {
+  const [layout, setLayout] = useState<SplitLayout>(loadSplitLayout);
+  // 最新の状態。変更時の保存はsetStateの外で行う（updaterは純粋にする）
+  const layoutRef = useRef(layout);
+  const saveTimerRef = useRef<number | null>(null);
+  // 最後に保存した値（%）。保存すると設定ファイルの監視で読み直されるので、その値では戻さない
+  const savedRatioRef = useRef<number | null>(null);
+
+  const update = useCallback((change: (current: SplitLayout) => SplitLayout) => {
+    const next = change(layoutRef.current);
+    layoutRef.current = next;
+    setLayout(next);
+  }, []);
+
+  useEffect(() => {
+    // 変更を保存する前（ドラッグ中など）や、自分で保存した値の読み直しは反映しない
+    if (saveTimerRef.current !== null || configRatio === savedRatioRef.current) return;
+    const ratio = configSplitRatio(configRatio);
+    if (ratio !== null) update((current) => setSplitRatio(current, ratio));
+  }, [configRatio, update]);
+
+  useEffect(() => {
+    return () => {
+      if (saveTimerRef.current !== null) window.clearTimeout(saveTimerRef.current);
+    };
+  }, []);
+
+  const setRatio = useCallback((ratio: number, minRatio?: number) => {
+    update((current) => setSplitRatio(current, ratio, minRatio));
+    if (!Number.isFinite(ratio)) return;
+    // ドラッグ中は書き込まず、止まってから保存する
+    const saved = toConfigSplitRatio(clampRatio(ratio, minRatio ?? MIN_SPLIT_RATIO));
+    if (saveTimerRef.current !== null) window.clearTimeout(saveTimerRef.current);
+    saveTimerRef.current = window.setTimeout(() => {
+      saveTimerRef.current = null;
+      savedRatioRef.current = saved;
+      invoke("save_split_ratio", { ratio: saved }).catch((e) =>
+        logger.error("Failed to save the split ratio:", e)
+      );
+    }, SAVE_DELAY_MS);
+  }, [update]);
+
+  const setOrientation = useCallback(
+    (orientation: SplitOrientation) => {
+      update((current) => setSplitOrientation(current, orientation));
+    },
+    [update]
+  );
+
+  return { ...layout, setRatio, setOrientation };
+}
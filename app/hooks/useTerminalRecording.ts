@@ -0,0 +1,42 @@
+import { useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
+import { logger } from "../utils/logger";
+
+/**
+ * ターミナル出力の録画（asciicast v2形式）を管理するhook
+ * 録画できるのは同時に1つのターミナルのみ
+ * 録画中のターミナルを閉じた場合、ファイルはセッションの終了時に保存される
+ */
+export function useTerminalRecording() {
+  // 録画中のターミナルのセッションID
+  const [recordingId, setRecordingId] = useState<string | null>(null);
+
+  const start = useCallback(async (sessionId: string) => {
+    try {
+      const path = await save({
+        title: "Save Terminal Recording",
+        defaultPath: "khafre.cast",
+        filters: [{ name: "asciicast", extensions: ["cast"] }],
+      });
+      if (!path) return;
+      await invoke("start_recording", { sessionId, path });
+      setRecordingId(sessionId);
+      logger.info("Recording terminal output to", path);
+    } catch (e) {
+      logger.error("Failed to start recording:", e);
+    }
+  }, []);
+
+  const stop = useCallback(async () => {
+    if (!recordingId) return;
+    setRecordingId(null);
+    try {
+      await invoke("stop_recording", { sessionId: recordingId });
+    } catch (e) {
+      logger.error("Failed to save recording:", e);
+    }
+  }, [recordingId]);
+
+  return { recordingId, start, stop };
+}
@@ -0,0 +1,9 @@
+/** ビルド出力の変更の種類 */
+export type ChangeKind = "added" | "removed" | "modified";
+
+/** 変更されたファイル（back/src/build_diff.rsのChangedFileと対応） */
+export interface ChangedFile {
+  /** ビルドディレクトリからの相対パス */
+  path: string;
+  change: ChangeKind;
+}
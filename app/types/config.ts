@@ -9,16 +9,38 @@ export interface SphinxConfig {
   build_dir: string;
   server: ServerConfig;
   extra_args: string[];
+  /** プレビューを操作しない状態がこの秒数続いたらsphinx-autobuildを停止（0 = 停止しない） */
+  idle_timeout_secs: number;
+  /** sphinx-autobuildが異常終了した時に自動で再起動する回数（ビルド成功でリセット） */
+  max_restarts: number;
+  /** stderrにこの文字列を含む行があればビルド完了とみなす */
+  success_patterns: string[];
+  /** stderrにこの文字列を含む行があればエラーとして通知する */
+  error_patterns: string[];
+  /** 起動中にプレビューのURLへ接続を確認する間隔（秒、0 = 確認しない） */
+  health_check_interval_secs: number;
+  /** 接続の確認がこの回数続けて失敗したら停止したものとみなす */
+  health_check_failures: number;
+  /** sphinx-autobuildに渡す環境変数 */
+  env: Record<string, string>;
+  /** Khafreの環境変数を引き継ぐか（falseならenvとロケールの設定のみで起動する） */
+  inherit_env: boolean;
 }
 
 /** Python環境設定 */
 export interface PythonConfig {
   interpreter: string;
+  /** インタプリタの解決方法（auto: .python-version / .tool-versionsがあればpyenv / asdfで解決） */
+  runner?: PythonRunner;
 }
 
+export type PythonRunner = "auto" | "pyenv" | "asdf" | "direct";
+
 /** エディタ設定 */
 export interface EditorConfig {
   command: string;
+  /** 行番号を指定して開く時の引数（{line}と{file}を置き換える、デフォルト: "+{line} {file}"） */
+  line_format?: string;
 }
 
 /** カラースキーム（xterm.js ITheme互換） */
@@ -47,15 +69,97 @@ export interface ColorScheme {
   bright_magenta?: string;
   bright_cyan?: string;
   bright_white?: string;
+  // 256-color palette entries 16-255 (terminal.palette_file)
+  extended_ansi?: string[];
+}
+
+/** コピー時のテキスト変換 */
+export interface CopyConfig {
+  /** 各行末尾の空白を取り除くか（デフォルト: true） */
+  trim_trailing?: boolean;
+  /** 各行の先頭から取り除くプロンプトの正規表現 */
+  strip_prompt?: string;
+  /** 折り返して表示している行を1行に連結するか（デフォルト: true） */
+  join_wrapped?: boolean;
 }
 
+/** シェル終了時の動作 */
+export type TerminalExitAction = "idle" | "respawn" | "close";
+
+/** 出力があった時のスクロール（terminal.scroll_on_output） */
+export type ScrollOnOutput = "always" | "if_at_bottom" | "never";
+
+/** ターミナルの描画方式（terminal.renderer） */
+export type TerminalRenderer = "dom" | "webgl";
+
 /** ターミナル設定 */
 export interface TerminalConfig {
   shell?: string;
   font_family?: string;
   font_size?: number;
   theme_file?: string;
+  palette_file?: string;
   color_scheme?: ColorScheme;
+  /** OSがライト/ダークテーマの時に使うテーマファイル（color_schemeより優先） */
+  theme_file_light?: string;
+  theme_file_dark?: string;
+  /** theme_file_light / theme_file_darkから解決したカラースキーム */
+  color_scheme_light?: ColorScheme;
+  color_scheme_dark?: ColorScheme;
+  bold_is_bright?: boolean;
+  bold_font_weight?: boolean;
+  padding?: number;
+  on_exit?: TerminalExitAction;
+  min_cols?: number;
+  min_rows?: number;
+  cursor_color?: string;
+  cursor_text_color?: string;
+  prompt_pattern?: string;
+  word_separators?: string;
+  scrollback_lines?: number;
+  scroll_on_output?: ScrollOnOutput;
+  renderer?: TerminalRenderer;
+  tab_width?: number;
+  background_opacity?: number;
+  background_image?: string;
+  smart_cursor_contrast?: boolean;
+  visual_bell?: boolean;
+  preserve_scrollback_on_reload?: boolean;
+  enter_copies_selection?: boolean;
+  rerun_history_fallback?: boolean;
+  copy?: CopyConfig;
+}
+
+/** プレビューの表示方法 */
+export type PreviewMode = "embedded" | "external";
+
+/** プレビュー設定 */
+export interface PreviewConfig {
+  /** falseならプレビューとsphinx-autobuildを使わない（ターミナルのみ） */
+  enabled: boolean;
+  mode: PreviewMode;
+  /** 初回のビルド完了時に外部ブラウザを開くか（embeddedモードでも有効） */
+  open_browser_on_ready: boolean;
+  /** 表示中ページの見出し一覧をプレビュー横に表示するか */
+  outline: boolean;
+}
+
+/** ログレベル */
+export type LogLevel = "debug" | "info" | "warn" | "error";
+
+/** ログ設定 */
+export interface LogConfig {
+  level: LogLevel;
+}
+
+/** ステータスバーの表示量 */
+export type StatusBarMode = "full" | "minimal" | "hidden";
+
+/** 画面表示の設定 */
+export interface UiConfig {
+  status_bar: StatusBarMode;
+  /** プレビューのペインの割合（%）。スプリッターを動かすと保存される（未設定 = 50） */
+  split_ratio?: number | null;
 }
 
 /** プロジェクト設定全体 */
@@ -64,4 +168,7 @@ export interface ProjectConfig {
   python: PythonConfig;
   editor: EditorConfig;
   terminal: TerminalConfig;
+  preview: PreviewConfig;
+  log: LogConfig;
+  ui: UiConfig;
 }
@@ -0,0 +1,11 @@
+import type { SplitOrientation } from "../utils/layout";
+
+/** 制御ソケットから転送されるコマンド（back/src/control.rsのControlCommandと対応） */
+export type ControlCommand =
+  | { command: "open_project"; path: string }
+  | { command: "start_preview" }
+  | { command: "stop_preview" }
+  | { command: "reload" }
+  | { command: "navigate"; page: string }
+  | { command: "set_split_ratio"; ratio: number }
+  | { command: "set_split_orientation"; orientation: SplitOrientation };
@@ -0,0 +1,50 @@
+import { describe, it, expect } from "vitest";
+import { mergeConfig } from "./devConfig";
+import type { ProjectConfig } from "./config";
+
+const base = {
+  sphinx: { server: { port: 0 } },
+  python: {},
+  editor: { command: "nvim" },
+  terminal: {
+    theme_file: "global.toml",
+    theme_file_light: "global-light.toml",
+    theme_file_dark: "global-dark.toml",
+    color_scheme_light: { background: "#ffffff" },
+    color_scheme_dark: { background: "#000000" },
+  },
+  preview: {},
+  log: {},
+  ui: {},
+} as unknown as ProjectConfig;
+
+describe("mergeConfig", () => {
+  it("should keep the base light/dark themes when the override has no theme", () => {
+    const merged = mergeConfig(base, { terminal: { font_size: 16 } });
+    expect(merged.terminal.font_size).toBe(16);
+    expect(merged.terminal.theme_file_light).toBe("global-light.toml");
+    expect(merged.terminal.color_scheme_dark).toEqual({ background: "#000000" });
+  });
+
+  it("should drop the base light/dark themes when the override sets a theme", () => {
+    for (const terminal of [
+      { theme_file: "project.toml" },
+      { color_scheme: { background: "#282a36" } },
+    ]) {
+      const merged = mergeConfig(base, { terminal });
+      expect(merged.terminal.theme_file_light).toBeUndefined();
+      expect(merged.terminal.theme_file_dark).toBeUndefined();
+      expect(merged.terminal.color_scheme_light).toBeUndefined();
+      expect(merged.terminal.color_scheme_dark).toBeUndefined();
+    }
+  });
+
+  it("should keep the light/dark themes set in the override itself", () => {
+    const merged = mergeConfig(base, {
+      terminal: { theme_file: "project.toml", theme_file_dark: "project-dark.toml" },
+    });
+    expect(merged.terminal.theme_file).toBe("project.toml");
+    expect(merged.terminal.theme_file_light).toBeUndefined();
+    expect(merged.terminal.theme_file_dark).toBe("project-dark.toml");
+  });
+});
@@ -1,4 +1,16 @@
-import type { ProjectConfig, ColorScheme } from "./config";
+import type {
+  ProjectConfig,
+  ColorScheme,
+  PreviewMode,
+  LogLevel,
+  StatusBarMode,
+  TerminalExitAction,
+  ScrollOnOutput,
+  TerminalRenderer,
+  PythonRunner,
+  CopyConfig,
+  TerminalConfig,
+} from "./config";
 
 /** 設定の部分上書き用型 */
 export type ConfigOverride = {
@@ -9,19 +21,69 @@ export type ConfigOverride = {
       port?: number;
     };
     extra_args?: string[];
+    idle_timeout_secs?: number;
+    max_restarts?: number;
+    success_patterns?: string[];
+    error_patterns?: string[];
+    health_check_interval_secs?: number;
+    health_check_failures?: number;
+    env?: Record<string, string>;
+    inherit_env?: boolean;
   };
   python?: {
     interpreter?: string;
+    runner?: PythonRunner;
   };
   editor?: {
     command?: string;
+    line_format?: string;
   };
   terminal?: {
     shell?: string;
     font_family?: string;
     font_size?: number;
     theme_file?: string;
+    palette_file?: string;
     color_scheme?: ColorScheme;
+    theme_file_light?: string;
+    theme_file_dark?: string;
+    color_scheme_light?: ColorScheme;
+    color_scheme_dark?: ColorScheme;
+    bold_is_bright?: boolean;
+    bold_font_weight?: boolean;
+    padding?: number;
+    on_exit?: TerminalExitAction;
+    min_cols?: number;
+    min_rows?: number;
+    cursor_color?: string;
+    cursor_text_color?: string;
+    prompt_pattern?: string;
+    word_separators?: string;
+    scrollback_lines?: number;
+    scroll_on_output?: ScrollOnOutput;
+    renderer?: TerminalRenderer;
+    tab_width?: number;
+    background_opacity?: number;
+    background_image?: string;
+    smart_cursor_contrast?: boolean;
+    visual_bell?: boolean;
+    preserve_scrollback_on_reload?: boolean;
+    enter_copies_selection?: boolean;
+    rerun_history_fallback?: boolean;
+    copy?: CopyConfig;
+  };
+  preview?: {
+    enabled?: boolean;
+    mode?: PreviewMode;
+    open_browser_on_ready?: boolean;
+    outline?: boolean;
+  };
+  log?: {
+    level?: LogLevel;
+  };
+  ui?: {
+    status_bar?: StatusBarMode;
+    split_ratio?: number;
   };
 };
 
@@ -42,6 +104,12 @@ export function mergeConfig(
 ): ProjectConfig {
   if (!override) return base;
 
+  // ライト/ダーク用のテーマはcolor_scheme / theme_fileより優先されるので、
+  // 上書き側でテーマを指定した場合はベース側のライト/ダーク用のテーマを使わない
+  const overridesTheme =
+    override.terminal?.color_scheme !== undefined || override.terminal?.theme_file !== undefined;
+  const baseVariants: TerminalConfig = overridesTheme ? {} : base.terminal;
+
   return {
     sphinx: {
       source_dir: override.sphinx?.source_dir ?? base.sphinx.source_dir,
@@ -50,19 +118,77 @@ export function mergeConfig(
         port: override.sphinx?.server?.port ?? base.sphinx.server.port,
       },
       extra_args: override.sphinx?.extra_args ?? base.sphinx.extra_args,
+      idle_timeout_secs: override.sphinx?.idle_timeout_secs ?? base.sphinx.idle_timeout_secs,
+      max_restarts: override.sphinx?.max_restarts ?? base.sphinx.max_restarts,
+      success_patterns: override.sphinx?.success_patterns ?? base.sphinx.success_patterns,
+      error_patterns: override.sphinx?.error_patterns ?? base.sphinx.error_patterns,
+      health_check_interval_secs:
+        override.sphinx?.health_check_interval_secs ?? base.sphinx.health_check_interval_secs,
+      health_check_failures:
+        override.sphinx?.health_check_failures ?? base.sphinx.health_check_failures,
+      env: override.sphinx?.env ?? base.sphinx.env,
+      inherit_env: override.sphinx?.inherit_env ?? base.sphinx.inherit_env,
     },
     python: {
       interpreter: override.python?.interpreter ?? base.python.interpreter,
+      runner: override.python?.runner ?? base.python.runner,
     },
     editor: {
       command: override.editor?.command ?? base.editor.command,
+      line_format: override.editor?.line_format ?? base.editor.line_format,
     },
     terminal: {
       shell: override.terminal?.shell ?? base.terminal.shell,
       font_family: override.terminal?.font_family ?? base.terminal.font_family,
       font_size: override.terminal?.font_size ?? base.terminal.font_size,
       theme_file: override.terminal?.theme_file ?? base.terminal.theme_file,
+      palette_file: override.terminal?.palette_file ?? base.terminal.palette_file,
       color_scheme: override.terminal?.color_scheme ?? base.terminal.color_scheme,
+      theme_file_light: override.terminal?.theme_file_light ?? baseVariants.theme_file_light,
+      theme_file_dark: override.terminal?.theme_file_dark ?? baseVariants.theme_file_dark,
+      color_scheme_light: override.terminal?.color_scheme_light ?? baseVariants.color_scheme_light,
+      color_scheme_dark: override.terminal?.color_scheme_dark ?? baseVariants.color_scheme_dark,
+      bold_is_bright: override.terminal?.bold_is_bright ?? base.terminal.bold_is_bright,
+      bold_font_weight: override.terminal?.bold_font_weight ?? base.terminal.bold_font_weight,
+      padding: override.terminal?.padding ?? base.terminal.padding,
+      on_exit: override.terminal?.on_exit ?? base.terminal.on_exit,
+      min_cols: override.terminal?.min_cols ?? base.terminal.min_cols,
+      min_rows: override.terminal?.min_rows ?? base.terminal.min_rows,
+      cursor_color: override.terminal?.cursor_color ?? base.terminal.cursor_color,
+      cursor_text_color: override.terminal?.cursor_text_color ?? base.terminal.cursor_text_color,
+      prompt_pattern: override.terminal?.prompt_pattern ?? base.terminal.prompt_pattern,
+      word_separators: override.terminal?.word_separators ?? base.terminal.word_separators,
+      scrollback_lines: override.terminal?.scrollback_lines ?? base.terminal.scrollback_lines,
+      scroll_on_output: override.terminal?.scroll_on_output ?? base.terminal.scroll_on_output,
+      renderer: override.terminal?.renderer ?? base.terminal.renderer,
+      tab_width: override.terminal?.tab_width ?? base.terminal.tab_width,
+      background_opacity: override.terminal?.background_opacity ?? base.terminal.background_opacity,
+      background_image: override.terminal?.background_image ?? base.terminal.background_image,
+      smart_cursor_contrast:
+        override.terminal?.smart_cursor_contrast ?? base.terminal.smart_cursor_contrast,
+      visual_bell: override.terminal?.visual_bell ?? base.terminal.visual_bell,
+      preserve_scrollback_on_reload:
+        override.terminal?.preserve_scrollback_on_reload ??
+        base.terminal.preserve_scrollback_on_reload,
+      enter_copies_selection:
+        override.terminal?.enter_copies_selection ?? base.terminal.enter_copies_selection,
+      rerun_history_fallback:
+        override.terminal?.rerun_history_fallback ?? base.terminal.rerun_history_fallback,
+      copy: override.terminal?.copy ?? base.terminal.copy,
+    },
+    preview: {
+      enabled: override.preview?.enabled ?? base.preview.enabled,
+      mode: override.preview?.mode ?? base.preview.mode,
+      open_browser_on_ready:
+        override.preview?.open_browser_on_ready ?? base.preview.open_browser_on_ready,
+      outline: override.preview?.outline ?? base.preview.outline,
+    },
+    log: {
+      level: override.log?.level ?? base.log.level,
+    },
+    ui: {
+      status_bar: override.ui?.status_bar ?? base.ui.status_bar,
+      split_ratio: override.ui?.split_ratio ?? base.ui.split_ratio,
     },
   };
 }
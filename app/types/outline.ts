@@ -0,0 +1,8 @@
+/** 見出し（back/src/outline.rsのHeadingと対応） */
+export interface Heading {
+  /** 1始まりの階層 */
+  level: number;
+  title: string;
+  /** Sphinxが生成するセクションのid */
+  anchor: string;
+}
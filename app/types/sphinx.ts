@@ -0,0 +1,17 @@
+/** sphinx.source_dirにconf.pyが無い時の確認結果（back/src/config.rsのMissingSourceDirと対応） */
+export interface MissingSourceDir {
+  /** プロジェクト内で見つけたconf.pyのディレクトリ（見つからなければnull） */
+  suggestion: string | null;
+}
+
+/** 診断の重要度 */
+export type Severity = "warning" | "error";
+
+/** Sphinxの警告・エラー（back/src/sphinx.rsのSphinxDiagnosticと対応） */
+export interface SphinxDiagnostic {
+  file: string;
+  /** 行番号が分からない警告ではnull */
+  line: number | null;
+  severity: Severity;
+  message: string;
+}
@@ -0,0 +1,59 @@
+import { describe, it, expect, vi, afterEach } from "vitest";
+import { getClipboard, setClipboard } from "./clipboard";
+import { logger } from "./logger";
+
+function mockClipboard(clipboard: Partial<Clipboard> | undefined) {
+  Object.defineProperty(navigator, "clipboard", { value: clipboard, configurable: true });
+}
+
+describe("setClipboard", () => {
+  afterEach(() => {
+    vi.restoreAllMocks();
+    mockClipboard(undefined);
+  });
+
+  it("should write the text to the clipboard", async () => {
+    const writeText = vi.fn().mockResolvedValue(undefined);
+    mockClipboard({ writeText });
+
+    expect(await setClipboard("make html")).toBe(true);
+    expect(writeText).toHaveBeenCalledWith("make html");
+  });
+
+  it("should warn instead of throwing when the write fails", async () => {
+    const warn = vi.spyOn(logger, "warn").mockImplementation(() => {});
+    mockClipboard({ writeText: vi.fn().mockRejectedValue(new Error("denied")) });
+
+    expect(await setClipboard("make html")).toBe(false);
+    expect(warn).toHaveBeenCalled();
+  });
+
+  it("should warn when the clipboard is unavailable", async () => {
+    const warn = vi.spyOn(logger, "warn").mockImplementation(() => {});
+    mockClipboard(undefined);
+
+    expect(await setClipboard("make html")).toBe(false);
+    expect(warn).toHaveBeenCalledWith("Clipboard is not available");
+  });
+});
+
+describe("getClipboard", () => {
+  afterEach(() => {
+    vi.restoreAllMocks();
+    mockClipboard(undefined);
+  });
+
+  it("should read the text from the clipboard", async () => {
+    mockClipboard({ readText: vi.fn().mockResolvedValue("make html") });
+
+    expect(await getClipboard()).toBe("make html");
+  });
+
+  it("should warn and return null when the read fails", async () => {
+    const warn = vi.spyOn(logger, "warn").mockImplementation(() => {});
+    mockClipboard({ readText: vi.fn().mockRejectedValue(new Error("denied")) });
+
+    expect(await getClipboard()).toBeNull();
+    expect(warn).toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,33 @@
+import { logger } from "./logger";
+
+/**
+ * テキストをOSのクリップボードに書き込む
+ * クリップボードが使えない環境では警告ログを出すだけで例外は投げない
+ */
+export async function setClipboard(text: string): Promise<boolean> {
+  if (!navigator.clipboard) {
+    logger.warn("Clipboard is not available");
+    return false;
+  }
+  try {
+    await navigator.clipboard.writeText(text);
+    return true;
+  } catch (e) {
+    logger.warn("Failed to write to the clipboard:", e);
+    return false;
+  }
+}
+
+/** OSのクリップボードからテキストを読み込む（読めなければ警告ログを出してnull） */
+export async function getClipboard(): Promise<string | null> {
+  if (!navigator.clipboard) {
+    logger.warn("Clipboard is not available");
+    return null;
+  }
+  try {
+    return await navigator.clipboard.readText();
+  } catch (e) {
+    logger.warn("Failed to read the clipboard:", e);
+    return null;
+  }
+}
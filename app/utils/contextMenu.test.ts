@@ -0,0 +1,52 @@
+import { describe, it, expect } from "vitest";
+import { contextMenuEffect, contextMenuItems } from "./contextMenu";
+
+describe("contextMenuItems", () => {
+  it("should offer copying and revealing the project path", () => {
+    expect(contextMenuItems("project_path").map((item) => item.action)).toEqual([
+      "copy_path",
+      "reveal_path",
+    ]);
+  });
+
+  it("should offer copying and opening the preview URL", () => {
+    expect(contextMenuItems("preview_url").map((item) => item.action)).toEqual([
+      "copy_url",
+      "open_url",
+    ]);
+  });
+});
+
+describe("contextMenuEffect", () => {
+  it("should copy the project path", () => {
+    expect(contextMenuEffect("copy_path", "/home/user/docs")).toEqual({
+      type: "copy",
+      text: "/home/user/docs",
+    });
+  });
+
+  it("should reveal the project path in the file manager", () => {
+    expect(contextMenuEffect("reveal_path", "/home/user/docs")).toEqual({
+      type: "reveal",
+      path: "/home/user/docs",
+    });
+  });
+
+  it("should copy the preview URL", () => {
+    const url = "http://127.0.0.1:8000/guide.html#install";
+    expect(contextMenuEffect("copy_url", url)).toEqual({ type: "copy", text: url });
+  });
+
+  it("should open the preview URL in the browser", () => {
+    const url = "http://127.0.0.1:8000/guide.html";
+    expect(contextMenuEffect("open_url", url)).toEqual({ type: "open_url", url });
+  });
+
+  it("should map every menu item to an effect", () => {
+    for (const target of ["project_path", "preview_url"] as const) {
+      for (const item of contextMenuItems(target)) {
+        expect(contextMenuEffect(item.action, "value")).toBeDefined();
+      }
+    }
+  });
+});
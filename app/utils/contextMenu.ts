@@ -0,0 +1,54 @@
+/** 右クリックメニューを出す場所 */
+export type ContextMenuTarget = "project_path" | "preview_url";
+
+export type ContextMenuAction = "copy_path" | "reveal_path" | "copy_url" | "open_url";
+
+export interface ContextMenuItem {
+  action: ContextMenuAction;
+  label: string;
+}
+
+/** 開いているメニュー */
+export interface ContextMenuState {
+  target: ContextMenuTarget;
+  /** 右クリックした場所のパスまたはURL */
+  value: string;
+  /** 右クリックした位置（ウィンドウ座標） */
+  x: number;
+  y: number;
+}
+
+/** メニューの操作で実際に行うこと */
+export type ContextMenuEffect =
+  | { type: "copy"; text: string }
+  | { type: "reveal"; path: string }
+  | { type: "open_url"; url: string };
+
+const MENU_ITEMS: Record<ContextMenuTarget, ContextMenuItem[]> = {
+  project_path: [
+    { action: "copy_path", label: "Copy Path" },
+    { action: "reveal_path", label: "Open in File Manager" },
+  ],
+  preview_url: [
+    { action: "copy_url", label: "Copy URL" },
+    { action: "open_url", label: "Open in Browser" },
+  ],
+};
+
+/** 右クリックした場所のメニュー項目 */
+export function contextMenuItems(target: ContextMenuTarget): ContextMenuItem[] {
+  return MENU_ITEMS[target];
+}
+
+/** メニューの操作を、右クリックした場所の値（パスまたはURL）に対する処理に対応付ける */
+export function contextMenuEffect(action: ContextMenuAction, value: string): ContextMenuEffect {
+  switch (action) {
+    case "copy_path":
+    case "copy_url":
+      return { type: "copy", text: value };
+    case "reveal_path":
+      return { type: "reveal", path: value };
+    case "open_url":
+      return { type: "open_url", url: value };
+  }
+}
@@ -0,0 +1,212 @@
+import { describe, it, expect } from "vitest";
+import type { IBuffer, IBufferCell, IBufferLine } from "@xterm/xterm";
+import { normalizeBlockRange, readSelection, selectionToAnsi, transformCopy } from "./copy";
+
+/** 全角文字か（テスト用の簡易判定） */
+const isWide = (char: string) => /[\u3000-\u9fff\uff00-\uffef]/.test(char);
+
+/** 固定幅の行を持つテスト用バッファ（textの1文字を1セルとして扱う） */
+function createBuffer(rows: { text: string; isWrapped?: boolean }[], cols = 10): IBuffer {
+  const lines = rows.map(
+    ({ text, isWrapped = false }) =>
+      ({
+        isWrapped,
+        length: cols,
+        getCell: (x: number) => {
+          const chars = text[x] ?? "";
+          return {
+            getChars: () => chars,
+            getWidth: () => (isWide(chars) ? 2 : 1),
+          } as unknown as IBufferCell;
+        },
+        translateToString: (trimRight?: boolean, start = 0, end = cols) => {
+          const padded = text.padEnd(cols).slice(start, end);
+          return trimRight ? padded.trimEnd() : padded;
+        },
+      }) as unknown as IBufferLine
+  );
+  return { getLine: (y: number) => lines[y] } as unknown as IBuffer;
+}
+
+describe("readSelection", () => {
+  it("should keep trailing whitespace up to the selection end", () => {
+    const buffer = createBuffer([{ text: "$ ls" }, { text: "a  b" }]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 6, y: 1 } };
+    expect(readSelection(buffer, range)).toBe("$ ls      \na  b  ");
+  });
+
+  it("should honor the start and end columns", () => {
+    const buffer = createBuffer([{ text: "hello world" }], 12);
+    const range = { start: { x: 6, y: 0 }, end: { x: 11, y: 0 } };
+    expect(readSelection(buffer, range)).toBe("world");
+  });
+
+  it("should join wrapped rows into one line", () => {
+    const buffer = createBuffer([{ text: "0123456789" }, { text: "abc", isWrapped: true }]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 3, y: 1 } };
+    expect(readSelection(buffer, range)).toBe("0123456789abc");
+  });
+
+  it("should drop the empty cell left by a wide character pushed to the next row", () => {
+    const buffer = createBuffer([{ text: "012345678" }, { text: "漢字", isWrapped: true }]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 2, y: 1 } };
+    expect(readSelection(buffer, range)).toBe("012345678漢字");
+  });
+
+  it("should keep the empty cell of a row ending with a hard line break", () => {
+    const buffer = createBuffer([{ text: "012345678" }, { text: "漢字" }]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 2, y: 1 } };
+    expect(readSelection(buffer, range)).toBe("012345678 \n漢字");
+  });
+
+  it("should keep the line break of wrapped rows when joinWrapped is off", () => {
+    const buffer = createBuffer([{ text: "0123456789" }, { text: "abc", isWrapped: true }]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 3, y: 1 } };
+    expect(readSelection(buffer, range, false)).toBe("0123456789\nabc");
+  });
+
+  it("should take the same columns of each row in block mode", () => {
+    const rows = [
+      { text: "-rw-r--r-- 1 a.txt" },
+      { text: "drwxr-xr-x 2 docs" },
+      { text: "-rw------- 1 b.txt" },
+    ];
+    const buffer = createBuffer(rows, 20);
+    const range = { start: { x: 13, y: 0 }, end: { x: 18, y: 2 } };
+    expect(readSelection(buffer, range, true, "block")).toBe("a.txt\ndocs \nb.txt");
+  });
+
+  it("should not join wrapped rows in block mode", () => {
+    const buffer = createBuffer([{ text: "0123456789" }, { text: "abc", isWrapped: true }]);
+    const range = { start: { x: 1, y: 0 }, end: { x: 3, y: 1 } };
+    expect(readSelection(buffer, range, true, "block")).toBe("12\nbc");
+  });
+});
+
+describe("normalizeBlockRange", () => {
+  it("should keep a selection dragged from top-left to bottom-right", () => {
+    const range = { start: { x: 1, y: 2 }, end: { x: 5, y: 4 } };
+    expect(normalizeBlockRange(range)).toEqual(range);
+  });
+
+  it("should swap the columns and rows of a selection dragged backwards", () => {
+    const expected = { start: { x: 1, y: 2 }, end: { x: 5, y: 4 } };
+    // 右上から左下
+    expect(normalizeBlockRange({ start: { x: 5, y: 2 }, end: { x: 1, y: 4 } })).toEqual(expected);
+    // 右下から左上
+    expect(normalizeBlockRange({ start: { x: 5, y: 4 }, end: { x: 1, y: 2 } })).toEqual(expected);
+  });
+});
+
+describe("transformCopy", () => {
+  it("should trim trailing whitespace by default", () => {
+    expect(transformCopy("foo   \nbar\t \n")).toBe("foo\nbar\n");
+  });
+
+  it("should keep trailing whitespace when trim_trailing is off", () => {
+    expect(transformCopy("foo   \nbar ", { trim_trailing: false })).toBe("foo   \nbar ");
+  });
+
+  it("should strip the prompt from each line", () => {
+    const text = "$ npm install\nadded 1 package\n$ npm test";
+    expect(transformCopy(text, { strip_prompt: "\\$ " })).toBe(
+      "npm install\nadded 1 package\nnpm test"
+    );
+  });
+
+  it("should only strip prompts at the start of a line", () => {
+    expect(transformCopy("echo $ ", { strip_prompt: "^\\$ " })).toBe("echo $");
+  });
+
+  it("should ignore an invalid prompt pattern", () => {
+    expect(transformCopy("$ ls", { strip_prompt: "(" })).toBe("$ ls");
+  });
+});
+
+interface FakeCell {
+  chars: string;
+  bold?: boolean;
+  /** パレット番号 */
+  fg?: number;
+  bg?: number;
+}
+
+/** 装飾付きのセルを持つテスト用バッファ */
+function createStyledBuffer(rows: FakeCell[][], wrappedRows: number[] = []): IBuffer {
+  const toCell = ({ chars, bold = false, fg, bg }: FakeCell) =>
+    ({
+      getChars: () => chars,
+      getWidth: () => 1,
+      isBold: () => (bold ? 1 : 0),
+      isDim: () => 0,
+      isItalic: () => 0,
+      isUnderline: () => 0,
+      isBlink: () => 0,
+      isInverse: () => 0,
+      isInvisible: () => 0,
+      isStrikethrough: () => 0,
+      isFgDefault: () => fg === undefined,
+      isFgPalette: () => fg !== undefined,
+      getFgColor: () => fg ?? 0,
+      isBgDefault: () => bg === undefined,
+      isBgPalette: () => bg !== undefined,
+      getBgColor: () => bg ?? 0,
+    }) as unknown as IBufferCell;
+  const lines = rows.map(
+    (cells, y) =>
+      ({
+        isWrapped: wrappedRows.includes(y),
+        length: cells.length,
+        getCell: (x: number) => toCell(cells[x]),
+      }) as unknown as IBufferLine
+  );
+  return { getLine: (y: number) => lines[y] } as unknown as IBuffer;
+}
+
+describe("selectionToAnsi", () => {
+  it("should emit SGR only where the style changes", () => {
+    const buffer = createStyledBuffer([
+      [
+        { chars: "o", fg: 1 },
+        { chars: "k", fg: 1 },
+        { chars: "!", fg: 1, bold: true },
+        { chars: " " },
+        { chars: "x", fg: 12, bg: 0 },
+      ],
+    ]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 5, y: 0 } };
+    expect(selectionToAnsi(buffer, range)).toBe("\x1b[31mok\x1b[1m!\x1b[0m \x1b[94;40mx\x1b[0m");
+  });
+
+  it("should reset the style at the end of each line", () => {
+    const buffer = createStyledBuffer([[{ chars: "a", fg: 2 }], [{ chars: "b", fg: 2 }]]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 1, y: 1 } };
+    expect(selectionToAnsi(buffer, range)).toBe("\x1b[32ma\x1b[0m\n\x1b[32mb\x1b[0m");
+  });
+
+  it("should continue a wrapped row without a line break", () => {
+    const rows = [[{ chars: "a", fg: 2 }], [{ chars: "b", fg: 2 }]];
+    const range = { start: { x: 0, y: 0 }, end: { x: 1, y: 1 } };
+    expect(selectionToAnsi(createStyledBuffer(rows, [1]), range)).toBe("\x1b[32mab\x1b[0m");
+    expect(selectionToAnsi(createStyledBuffer(rows, [1]), range, false)).toBe(
+      "\x1b[32ma\x1b[0m\n\x1b[32mb\x1b[0m"
+    );
+  });
+
+  it("should leave plain text without escape sequences", () => {
+    const buffer = createStyledBuffer([[{ chars: "h" }, { chars: "i" }]]);
+    const range = { start: { x: 0, y: 0 }, end: { x: 2, y: 0 } };
+    expect(selectionToAnsi(buffer, range)).toBe("hi");
+  });
+
+  it("should keep the colors of the selected columns in block mode", () => {
+    const buffer = createStyledBuffer([
+      [{ chars: "a" }, { chars: "b", fg: 1 }],
+      [{ chars: "c" }, { chars: "d", fg: 2 }],
+    ]);
+    const range = { start: { x: 2, y: 1 }, end: { x: 1, y: 0 } };
+    expect(selectionToAnsi(buffer, range, true, "block")).toBe(
+      "\x1b[31mb\x1b[0m\n\x1b[32md\x1b[0m"
+    );
+  });
+});
@@ -0,0 +1,210 @@
+import type { IBuffer, IBufferCell, IBufferLine, IBufferRange } from "@xterm/xterm";
+import type { CopyConfig } from "../types/config";
+
+/**
+ * 次の行へ折り返している行の、連結する時の末尾の位置
+ * 全角文字が行末に収まらずに次の行へ送られた場合、行末に残る空きセルは出力された文字ではないので含めない
+ */
+function wrappedRowEnd(line: IBufferLine, next: IBufferLine | undefined): number {
+  if (!next?.isWrapped) return line.length;
+  const last = line.getCell(line.length - 1);
+  const first = next.getCell(0);
+  const pushedWide = last?.getChars() === "" && last.getWidth() === 1 && first?.getWidth() === 2;
+  return pushedWide ? line.length - 1 : line.length;
+}
+
+/**
+ * 選択の形
+ * normal: 開始位置から終了位置まで行をまたいで連続、block: 開始・終了の列で囲んだ矩形（Altを押しながらドラッグ）
+ */
+export type SelectionMode = "normal" | "block";
+
+/**
+ * 矩形選択の範囲を左上・右下の順に揃える
+ * 右から左・下から上にドラッグした場合も、各行で取り出す列が開始・終了の列の間になるようにする
+ */
+export function normalizeBlockRange(range: IBufferRange): IBufferRange {
+  return {
+    start: { x: Math.min(range.start.x, range.end.x), y: Math.min(range.start.y, range.end.y) },
+    end: { x: Math.max(range.start.x, range.end.x), y: Math.max(range.start.y, range.end.y) },
+  };
+}
+
+/**
+ * 選択範囲のうちy行目（line）で取り出す列の範囲 [開始, 終了)
+ * 折り返しを連結する場合は行末の空きセルを除く。矩形選択ではどの行も同じ列
+ */
+function rowColumns(
+  buffer: IBuffer,
+  line: IBufferLine,
+  y: number,
+  range: IBufferRange,
+  joinWrapped: boolean,
+  mode: SelectionMode
+): [number, number] {
+  if (mode === "block") return [range.start.x, range.end.x];
+  const start = y === range.start.y ? range.start.x : 0;
+  if (y === range.end.y) return [start, range.end.x];
+  return [start, joinWrapped ? wrappedRowEnd(line, buffer.getLine(y + 1)) : line.length];
+}
+
+/**
+ * 選択範囲のテキストをバッファから取り出す
+ * 末尾の空白は残したまま返す（削るかはtransformCopyで決める）。
+ * 折り返しで分割された行は1行に連結する（joinWrappedがfalseなら画面どおりに改行する）。
+ * 矩形選択では各行の同じ列だけを取り出し、折り返しは連結しない
+ */
+export function readSelection(
+  buffer: IBuffer,
+  range: IBufferRange,
+  joinWrapped = true,
+  mode: SelectionMode = "normal"
+): string {
+  const area = mode === "block" ? normalizeBlockRange(range) : range;
+  const join = joinWrapped && mode === "normal";
+  const lines: string[] = [];
+  for (let y = area.start.y; y <= area.end.y; y++) {
+    const line = buffer.getLine(y);
+    if (!line) continue;
+
+    const [startColumn, endColumn] = rowColumns(buffer, line, y, area, join, mode);
+    const text = line.translateToString(false, startColumn, endColumn);
+
+    if (join && line.isWrapped && y !== area.start.y && lines.length > 0) {
+      lines[lines.length - 1] += text;
+    } else {
+      lines.push(text);
+    }
+  }
+  return lines.join("\n");
+}
+
+/** プロンプトの正規表現を行頭に固定して作成（不正なパターンはnull） */
+function compilePromptPattern(pattern: string): RegExp | null {
+  try {
+    return new RegExp(`^(?:${pattern.replace(/^\^/, "")})`);
+  } catch {
+    return null;
+  }
+}
+
+/** コピーするテキストに terminal.copy の変換を適用 */
+export function transformCopy(text: string, config: CopyConfig = {}): string {
+  const prompt = config.strip_prompt ? compilePromptPattern(config.strip_prompt) : null;
+  const trimTrailing = config.trim_trailing ?? true;
+
+  return text
+    .split("\n")
+    .map((line) => {
+      let result = prompt ? line.replace(prompt, "") : line;
+      if (trimTrailing) result = result.trimEnd();
+      return result;
+    })
+    .join("\n");
+}
+
+/** SGRで表現するセルの装飾 */
+interface CellStyle {
+  flags: number[];
+  fg: number[];
+  bg: number[];
+}
+
+const PLAIN_STYLE: CellStyle = { flags: [], fg: [], bg: [] };
+
+/** 色のSGRパラメータ（base: 前景30 / 背景40） */
+function colorParams(
+  base: number,
+  isDefault: boolean,
+  isPalette: boolean,
+  color: number
+): number[] {
+  if (isDefault) return [];
+  if (isPalette) {
+    if (color < 8) return [base + color];
+    if (color < 16) return [base + 60 + color - 8];
+    return [base + 8, 5, color];
+  }
+  return [base + 8, 2, (color >> 16) & 0xff, (color >> 8) & 0xff, color & 0xff];
+}
+
+function cellStyle(cell: IBufferCell): CellStyle {
+  const flags: number[] = [];
+  if (cell.isBold()) flags.push(1);
+  if (cell.isDim()) flags.push(2);
+  if (cell.isItalic()) flags.push(3);
+  if (cell.isUnderline()) flags.push(4);
+  if (cell.isBlink()) flags.push(5);
+  if (cell.isInverse()) flags.push(7);
+  if (cell.isInvisible()) flags.push(8);
+  if (cell.isStrikethrough()) flags.push(9);
+  return {
+    flags,
+    fg: colorParams(30, cell.isFgDefault(), cell.isFgPalette(), cell.getFgColor()),
+    bg: colorParams(40, cell.isBgDefault(), cell.isBgPalette(), cell.getBgColor()),
+  };
+}
+
+const sameParams = (a: number[], b: number[]) =>
+  a.length === b.length && a.every((value, i) => value === b[i]);
+
+/**
+ * 装飾の切り替えに必要なSGRシーケンス（変化が無ければ空文字）
+ * 解除される属性や既定色に戻る色がある場合のみリセットしてから付け直す
+ */
+function sgrTransition(from: CellStyle, to: CellStyle): string {
+  const needsReset =
+    from.flags.some((flag) => !to.flags.includes(flag)) ||
+    (from.fg.length > 0 && to.fg.length === 0) ||
+    (from.bg.length > 0 && to.bg.length === 0);
+  if (needsReset) {
+    return `\x1b[${[0, ...to.flags, ...to.fg, ...to.bg].join(";")}m`;
+  }
+  const params = [
+    ...to.flags.filter((flag) => !from.flags.includes(flag)),
+    ...(sameParams(from.fg, to.fg) ? [] : to.fg),
+    ...(sameParams(from.bg, to.bg) ? [] : to.bg),
+  ];
+  return params.length > 0 ? `\x1b[${params.join(";")}m` : "";
+}
+
+/**
+ * 選択範囲を色・装飾付きのテキストとして取り出す
+ * 別のターミナルや `less -R` に貼り付けても色が残るよう、セル間の装飾の変化をSGRで出力する。
+ * 色はパレット番号のまま出力するため、貼り付け先のカラースキームで表示される
+ * 折り返し・矩形選択の扱いはreadSelectionと同じ
+ */
+export function selectionToAnsi(
+  buffer: IBuffer,
+  range: IBufferRange,
+  joinWrapped = true,
+  mode: SelectionMode = "normal"
+): string {
+  const area = mode === "block" ? normalizeBlockRange(range) : range;
+  const join = joinWrapped && mode === "normal";
+  let result = "";
+  let style = PLAIN_STYLE;
+  let cell: IBufferCell | undefined;
+
+  for (let y = area.start.y; y <= area.end.y; y++) {
+    const line = buffer.getLine(y);
+    if (!line) continue;
+
+    if (y !== area.start.y && !(join && line.isWrapped)) {
+      // 行末の装飾（背景色など）を次の行に持ち越さない
+      result += sgrTransition(style, PLAIN_STYLE) + "\n";
+      style = PLAIN_STYLE;
+    }
+
+    const [startColumn, endColumn] = rowColumns(buffer, line, y, area, join, mode);
+    for (let x = startColumn; x < endColumn; x++) {
+      cell = line.getCell(x, cell);
+      // 全角文字の後半セルは幅0
+      if (!cell || cell.getWidth() === 0) continue;
+      const next = cellStyle(cell);
+      result += sgrTransition(style, next) + (cell.getChars() || " ");
+      style = next;
+    }
+  }
+  return result + sgrTransition(style, PLAIN_STYLE);
+}
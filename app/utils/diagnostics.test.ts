@@ -0,0 +1,62 @@
+import { describe, it, expect } from "vitest";
+import { countBySeverity, diagnosticSource, previewErrorOverlay } from "./diagnostics";
+import type { SphinxDiagnostic } from "../types/sphinx";
+
+const diagnostic = (overrides: Partial<SphinxDiagnostic>): SphinxDiagnostic => ({
+  file: "/docs/index.rst",
+  line: 12,
+  severity: "warning",
+  message: "Title underline too short.",
+  ...overrides,
+});
+
+describe("diagnosticSource", () => {
+  it("should open the file at the line", () => {
+    expect(diagnosticSource(diagnostic({}))).toEqual({ path: "/docs/index.rst", line: 12 });
+  });
+
+  it("should open the top of the file when the line is unknown", () => {
+    expect(diagnosticSource(diagnostic({ line: null }))).toEqual({
+      path: "/docs/index.rst",
+      line: undefined,
+    });
+  });
+
+  it("should open the module for a warning in a docstring", () => {
+    const source = diagnosticSource(
+      diagnostic({ file: "/src/pkg/mod.py:docstring of pkg.mod.func", line: 5 })
+    );
+    expect(source).toEqual({ path: "/src/pkg/mod.py" });
+  });
+});
+
+describe("previewErrorOverlay", () => {
+  it("should show only the errors of the build", () => {
+    const error = diagnostic({ severity: "error", message: "Unknown directive type." });
+    expect(previewErrorOverlay([diagnostic({}), error], false)).toEqual([error]);
+  });
+
+  it("should not show an overlay for warnings only", () => {
+    expect(previewErrorOverlay([diagnostic({})], false)).toBeNull();
+    expect(previewErrorOverlay([], false)).toBeNull();
+  });
+
+  it("should not show an overlay once dismissed", () => {
+    expect(previewErrorOverlay([diagnostic({ severity: "error" })], true)).toBeNull();
+  });
+});
+
+describe("countBySeverity", () => {
+  it("should count warnings and errors", () => {
+    const diagnostics = [
+      diagnostic({}),
+      diagnostic({ severity: "error" }),
+      diagnostic({ line: 3 }),
+    ];
+    expect(countBySeverity(diagnostics)).toEqual({ warning: 2, error: 1 });
+  });
+
+  it("should return zeros for a clean build", () => {
+    expect(countBySeverity([])).toEqual({ warning: 0, error: 0 });
+  });
+});
@@ -0,0 +1,40 @@
+import type { Severity, SphinxDiagnostic } from "../types/sphinx";
+
+/** docstring内の警告の場所（"mod.py:docstring of pkg.mod.func"） */
+const DOCSTRING_MARKER = ":docstring of ";
+
+/** エディタで開く場所 */
+export interface DiagnosticSource {
+  path: string;
+  line?: number;
+}
+
+/**
+ * 診断をエディタで開く場所
+ * docstring内の警告は行番号がdocstringの中の位置なので、ファイルの先頭を開く
+ */
+export function diagnosticSource(diagnostic: SphinxDiagnostic): DiagnosticSource {
+  const index = diagnostic.file.indexOf(DOCSTRING_MARKER);
+  if (index >= 0) return { path: diagnostic.file.slice(0, index) };
+  return { path: diagnostic.file, line: diagnostic.line ?? undefined };
+}
+
+/**
+ * プレビューに重ねて表示するエラー
+ * 警告だけのビルドや、閉じた後は表示しない（nullを返す）
+ */
+export function previewErrorOverlay(
+  diagnostics: SphinxDiagnostic[],
+  dismissed: boolean
+): SphinxDiagnostic[] | null {
+  if (dismissed) return null;
+  const errors = diagnostics.filter((diagnostic) => diagnostic.severity === "error");
+  return errors.length > 0 ? errors : null;
+}
+
+/** 重要度ごとの件数 */
+export function countBySeverity(diagnostics: SphinxDiagnostic[]): Record<Severity, number> {
+  const counts: Record<Severity, number> = { warning: 0, error: 0 };
+  diagnostics.forEach((diagnostic) => counts[diagnostic.severity]++);
+  return counts;
+}
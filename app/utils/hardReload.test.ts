@@ -0,0 +1,65 @@
+import { describe, it, expect, vi } from "vitest";
+import { formatHardReloadResult, runHardReload } from "./hardReload";
+
+describe("runHardReload", () => {
+  it("should run each step in order", async () => {
+    const calls: string[] = [];
+    const step = (label: string) => ({
+      label,
+      run: async () => {
+        calls.push(label);
+      },
+    });
+
+    const result = await runHardReload([step("config"), step("terminal"), step("preview")]);
+
+    expect(calls).toEqual(["config", "terminal", "preview"]);
+    expect(result).toEqual({ type: "done", skipped: [] });
+  });
+
+  it("should stop at the first failing step", async () => {
+    const preview = vi.fn(async () => {});
+
+    const result = await runHardReload([
+      { label: "config", run: async () => {} },
+      {
+        label: "terminal",
+        run: async () => {
+          throw new Error("Session not found");
+        },
+      },
+      { label: "preview", run: preview },
+    ]);
+
+    expect(result).toEqual({
+      type: "failed",
+      step: "terminal",
+      error: "Session not found",
+    });
+    expect(preview).not.toHaveBeenCalled();
+  });
+
+  it("should report skipped steps and keep going", async () => {
+    const preview = vi.fn(async () => {});
+
+    const result = await runHardReload([
+      { label: "terminal", run: async () => "skipped" as const },
+      { label: "preview", run: preview },
+    ]);
+
+    expect(result).toEqual({ type: "done", skipped: ["terminal"] });
+    expect(preview).toHaveBeenCalled();
+  });
+});
+
+describe("formatHardReloadResult", () => {
+  it("should describe the result", () => {
+    expect(formatHardReloadResult({ type: "done", skipped: [] })).toBe("Reloaded everything");
+    expect(formatHardReloadResult({ type: "done", skipped: ["terminal"] })).toBe(
+      "Reloaded (skipped terminal)"
+    );
+    expect(formatHardReloadResult({ type: "failed", step: "config", error: "invalid TOML" })).toBe(
+      "Reload failed at config: invalid TOML"
+    );
+  });
+});
@@ -0,0 +1,37 @@
+/**
+ * 「すべて再読み込み」の1ステップ
+ * runが"skipped"を返した場合は、実行しなかったものとして報告する
+ */
+export interface HardReloadStep {
+  label: string;
+  run: () => Promise<void | "skipped">;
+}
+
+/** 「すべて再読み込み」の結果 */
+export type HardReloadResult =
+  | { type: "done"; skipped: string[] }
+  | { type: "failed"; step: string; error: string };
+
+/**
+ * ステップを順番に実行する
+ * 失敗したステップで止め、後のステップは実行しない（前の状態のまま残す）
+ */
+export async function runHardReload(steps: HardReloadStep[]): Promise<HardReloadResult> {
+  const skipped: string[] = [];
+  for (const step of steps) {
+    try {
+      if ((await step.run()) === "skipped") skipped.push(step.label);
+    } catch (e) {
+      const error = e instanceof Error ? e.message : String(e);
+      return { type: "failed", step: step.label, error };
+    }
+  }
+  return { type: "done", skipped };
+}
+
+/** 結果をヘッダーに表示するメッセージにする */
+export function formatHardReloadResult(result: HardReloadResult): string {
+  if (result.type === "failed") return `Reload failed at ${result.step}: ${result.error}`;
+  if (result.skipped.length === 0) return "Reloaded everything";
+  return `Reloaded (skipped ${result.skipped.join(", ")})`;
+}
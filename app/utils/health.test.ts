@@ -0,0 +1,42 @@
+import { describe, it, expect } from "vitest";
+import { HealthState, INITIAL_HEALTH, nextHealth } from "./health";
+
+/** 接続確認の結果を順に適用した時の状態の推移 */
+function run(results: boolean[], threshold: number): HealthState[] {
+  const states: HealthState[] = [];
+  let state = INITIAL_HEALTH;
+  for (const ok of results) {
+    state = nextHealth(state, ok, threshold);
+    states.push(state);
+  }
+  return states;
+}
+
+describe("nextHealth", () => {
+  it("should stay healthy while the server responds", () => {
+    expect(run([true, true], 3).map(({ status }) => status)).toEqual(["healthy", "healthy"]);
+  });
+
+  it("should go down after threshold failures in a row", () => {
+    expect(run([false, false, false], 3)).toEqual([
+      { status: "degraded", failures: 1 },
+      { status: "degraded", failures: 2 },
+      { status: "down", failures: 3 },
+    ]);
+  });
+
+  it("should reset the count when a check succeeds", () => {
+    const states = run([false, false, true, false, false], 3);
+    expect(states.map(({ status }) => status)).toEqual([
+      "degraded",
+      "degraded",
+      "healthy",
+      "degraded",
+      "degraded",
+    ]);
+  });
+
+  it("should treat a threshold below 1 as 1", () => {
+    expect(run([false], 0)[0].status).toBe("down");
+  });
+});
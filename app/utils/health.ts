@@ -0,0 +1,20 @@
+/** プレビューサーバーへの接続確認の状態 */
+export type HealthStatus = "healthy" | "degraded" | "down";
+
+export interface HealthState {
+  status: HealthStatus;
+  /** 続けて失敗した回数 */
+  failures: number;
+}
+
+export const INITIAL_HEALTH: HealthState = { status: "healthy", failures: 0 };
+
+/**
+ * 接続確認の結果から次の状態を求める
+ * 失敗がthreshold回続いたらdown、それまではdegraded。1回でも成功すればhealthyに戻る
+ */
+export function nextHealth(state: HealthState, ok: boolean, threshold: number): HealthState {
+  if (ok) return INITIAL_HEALTH;
+  const failures = state.failures + 1;
+  return { status: failures >= Math.max(1, threshold) ? "down" : "degraded", failures };
+}
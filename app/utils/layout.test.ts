@@ -0,0 +1,203 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  clampRatio,
+  splitMinRatio,
+  pointerRatio,
+  configSplitRatio,
+  toConfigSplitRatio,
+  stepRatioByKey,
+  loadSplitLayout,
+  setSplitRatio,
+  setSplitOrientation,
+  DEFAULT_SPLIT_LAYOUT,
+  statusBarSections,
+  nextDragging,
+  DividerDragEvent,
+} from "./layout";
+import { STORAGE_KEYS } from "./storage";
+
+describe("clampRatio", () => {
+  it("should keep ratios within the minimum pane widths", () => {
+    expect(clampRatio(0.5, 0.2)).toBe(0.5);
+    expect(clampRatio(0.1, 0.2)).toBe(0.2);
+    expect(clampRatio(0.9, 0.2)).toBe(0.8);
+  });
+});
+
+describe("splitMinRatio", () => {
+  it("should keep the minimum pane size", () => {
+    expect(splitMinRatio(200, 1000)).toBe(0.2);
+  });
+
+  it("should clamp between 10% and 90% on large containers", () => {
+    expect(splitMinRatio(200, 4000)).toBe(0.1);
+    expect(clampRatio(0.95, splitMinRatio(200, 4000))).toBe(0.9);
+  });
+
+  it("should not exceed half of a small container", () => {
+    expect(splitMinRatio(200, 300)).toBe(0.5);
+    expect(splitMinRatio(200, 0)).toBe(0.1);
+  });
+});
+
+describe("pointerRatio", () => {
+  it("should follow the pointer relative to the container", () => {
+    expect(pointerRatio(300, 100, 800)).toBe(0.25);
+    expect(pointerRatio(900, 100, 800)).toBe(1);
+  });
+
+  it("should fall back to the default ratio before the container is measured", () => {
+    expect(pointerRatio(300, 0, 0)).toBe(0.5);
+  });
+});
+
+describe("configSplitRatio", () => {
+  it("should convert the percentage to a ratio", () => {
+    expect(configSplitRatio(62.5)).toBe(0.625);
+    expect(configSplitRatio(10)).toBe(0.1);
+  });
+
+  it("should keep the stored layout when the value is missing", () => {
+    expect(configSplitRatio(undefined)).toBeNull();
+    expect(configSplitRatio(null)).toBeNull();
+  });
+
+  it("should fall back to 50% when the value is out of range", () => {
+    expect(configSplitRatio(0.6)).toBe(0.5);
+    expect(configSplitRatio(95)).toBe(0.5);
+    expect(configSplitRatio(-20)).toBe(0.5);
+    expect(configSplitRatio(NaN)).toBe(0.5);
+  });
+
+  it("should round trip the saved percentage", () => {
+    expect(toConfigSplitRatio(0.62549)).toBe(62.5);
+    expect(configSplitRatio(toConfigSplitRatio(0.3))).toBe(0.3);
+  });
+});
+
+describe("stepRatioByKey", () => {
+  const key = (key: string, shiftKey = false) => ({ key, shiftKey });
+
+  it("should step by 1% with the arrow keys", () => {
+    expect(stepRatioByKey(0.5, key("ArrowRight"), 0.1)).toBeCloseTo(0.51);
+    expect(stepRatioByKey(0.5, key("ArrowLeft"), 0.1)).toBeCloseTo(0.49);
+    expect(stepRatioByKey(0.5, key("ArrowDown"), 0.1)).toBeCloseTo(0.51);
+    expect(stepRatioByKey(0.5, key("ArrowUp"), 0.1)).toBeCloseTo(0.49);
+  });
+
+  it("should step by 5% with Shift", () => {
+    expect(stepRatioByKey(0.5, key("ArrowRight", true), 0.1)).toBeCloseTo(0.55);
+  });
+
+  it("should clamp to the minimum pane widths", () => {
+    expect(stepRatioByKey(0.11, key("ArrowLeft", true), 0.1)).toBe(0.1);
+    expect(stepRatioByKey(0.5, key("End"), 0.1)).toBe(0.9);
+    expect(stepRatioByKey(0.5, key("Home"), 0.1)).toBe(0.1);
+  });
+
+  it("should ignore other keys", () => {
+    expect(stepRatioByKey(0.5, key("Enter"), 0.1)).toBeNull();
+  });
+});
+
+describe("split layout", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should use the default layout when nothing is stored", () => {
+    expect(loadSplitLayout()).toEqual(DEFAULT_SPLIT_LAYOUT);
+  });
+
+  it("should clamp and persist the split ratio", () => {
+    const layout = setSplitRatio(DEFAULT_SPLIT_LAYOUT, 0.95);
+    expect(layout.ratio).toBe(0.9);
+    expect(localStorage.getItem(STORAGE_KEYS.splitRatio)).toBe("0.9");
+    expect(loadSplitLayout().ratio).toBe(0.9);
+  });
+
+  it("should clamp with a custom minimum ratio", () => {
+    expect(setSplitRatio(DEFAULT_SPLIT_LAYOUT, 0.1, 0.25).ratio).toBe(0.25);
+  });
+
+  it("should ignore ratios that are not numbers", () => {
+    expect(setSplitRatio(DEFAULT_SPLIT_LAYOUT, NaN)).toBe(DEFAULT_SPLIT_LAYOUT);
+    expect(localStorage.getItem(STORAGE_KEYS.splitRatio)).toBeNull();
+  });
+
+  it("should persist the orientation without changing the ratio", () => {
+    const layout = setSplitOrientation({ ratio: 0.3, orientation: "horizontal" }, "vertical");
+    expect(layout).toEqual({ ratio: 0.3, orientation: "vertical" });
+    expect(loadSplitLayout().orientation).toBe("vertical");
+  });
+
+  it("should sanitize invalid stored values", () => {
+    localStorage.setItem(STORAGE_KEYS.splitRatio, "2");
+    localStorage.setItem(STORAGE_KEYS.splitOrientation, "diagonal");
+    expect(loadSplitLayout()).toEqual({ ratio: 0.9, orientation: "horizontal" });
+  });
+});
+
+describe("statusBarSections", () => {
+  it("should show everything in full mode and by default", () => {
+    const full = { visible: true, project: true, status: true, actions: true };
+    expect(statusBarSections("full")).toEqual(full);
+    expect(statusBarSections(undefined)).toEqual(full);
+  });
+
+  it("should show only the Sphinx status in minimal mode", () => {
+    expect(statusBarSections("minimal")).toEqual({
+      visible: true,
+      project: false,
+      status: true,
+      actions: false,
+    });
+  });
+
+  it("should hide the header in hidden mode", () => {
+    expect(statusBarSections("hidden").visible).toBe(false);
+  });
+});
+
+describe("nextDragging", () => {
+  const run = (events: DividerDragEvent[]) =>
+    events.reduce<boolean[]>((states, event) => {
+      const previous = states.length > 0 ? states[states.length - 1] : false;
+      return [...states, nextDragging(previous, event)];
+    }, []);
+
+  it("should drag from press until release", () => {
+    expect(run([{ type: "down" }, { type: "move", buttons: 1 }, { type: "up" }])).toEqual([
+      true,
+      true,
+      false,
+    ]);
+  });
+
+  it("should keep dragging when the pointer leaves and re-enters with the button held", () => {
+    expect(
+      run([
+        { type: "down" },
+        { type: "leave" },
+        { type: "move", buttons: 1 },
+        { type: "move", buttons: 1 },
+      ])
+    ).toEqual([true, true, true, true]);
+  });
+
+  it("should stop when the button was released outside the window", () => {
+    expect(run([{ type: "down" }, { type: "leave" }, { type: "move", buttons: 0 }])).toEqual([
+      true,
+      true,
+      false,
+    ]);
+  });
+
+  it("should stop when the pointer is cancelled", () => {
+    expect(run([{ type: "down" }, { type: "cancel" }])).toEqual([true, false]);
+  });
+
+  it("should not start dragging by moving with the button held", () => {
+    expect(run([{ type: "move", buttons: 1 }, { type: "leave" }])).toEqual([false, false]);
+  });
+});
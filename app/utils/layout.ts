@@ -0,0 +1,183 @@
+import { STORAGE_KEYS, loadNumber, loadString, storeNumber, storeString } from "./storage";
+import type { StatusBarMode } from "../types/config";
+
+// キーボードでスプリッターを動かす量（Shift押下時は大きく動かす）
+export const RATIO_STEP = 0.01;
+export const RATIO_STEP_LARGE = 0.05;
+
+// 各ペインに常に確保する割合（画面サイズが分からない時もこれだけは残す）
+export const MIN_SPLIT_RATIO = 0.1;
+
+/** ペインの並べ方（horizontal: 左右、vertical: 上下） */
+export type SplitOrientation = "horizontal" | "vertical";
+
+/** 分割ビューの状態 */
+export interface SplitLayout {
+  /** 先頭（左または上）のペインの割合 */
+  ratio: number;
+  orientation: SplitOrientation;
+}
+
+export const DEFAULT_SPLIT_LAYOUT: SplitLayout = { ratio: 0.5, orientation: "horizontal" };
+
+/** 分割比率を両ペインの最小幅を確保できる範囲に収める */
+export function clampRatio(ratio: number, minRatio: number): number {
+  return Math.max(minRatio, Math.min(1 - minRatio, ratio));
+}
+
+/**
+ * 両ペインの最小サイズ（px）を確保するための比率の下限
+ * 大きな画面でもMIN_SPLIT_RATIO（10%〜90%）の範囲に収める
+ */
+export function splitMinRatio(minSize: number, containerSize: number): number {
+  if (containerSize <= 0) return MIN_SPLIT_RATIO;
+  return Math.min(0.5, Math.max(MIN_SPLIT_RATIO, minSize / containerSize));
+}
+
+/** ポインター位置から分割比率を求める（containerStartはコンテナの左端・上端） */
+export function pointerRatio(
+  pointer: number,
+  containerStart: number,
+  containerSize: number
+): number {
+  if (containerSize <= 0) return DEFAULT_SPLIT_LAYOUT.ratio;
+  return (pointer - containerStart) / containerSize;
+}
+
+interface DividerKeyEvent {
+  key: string;
+  shiftKey: boolean;
+}
+
+/**
+ * スプリッターにフォーカスがある時の矢印キー操作で新しい比率を返す（対象外のキーはnull）
+ * 左右・上下どちらの矢印キーでも動かせる
+ */
+export function stepRatioByKey(
+  ratio: number,
+  event: DividerKeyEvent,
+  minRatio: number
+): number | null {
+  const step = event.shiftKey ? RATIO_STEP_LARGE : RATIO_STEP;
+  switch (event.key) {
+    case "ArrowLeft":
+    case "ArrowUp":
+      return clampRatio(ratio - step, minRatio);
+    case "ArrowRight":
+    case "ArrowDown":
+      return clampRatio(ratio + step, minRatio);
+    case "Home":
+      return clampRatio(0, minRatio);
+    case "End":
+      return clampRatio(1, minRatio);
+    default:
+      return null;
+  }
+}
+
+/** 保存された分割ビューの状態を読み込む（不正な値はデフォルト） */
+export function loadSplitLayout(): SplitLayout {
+  const ratio = loadNumber(STORAGE_KEYS.splitRatio);
+  const orientation = loadString(STORAGE_KEYS.splitOrientation);
+  return {
+    ratio: ratio === null ? DEFAULT_SPLIT_LAYOUT.ratio : clampRatio(ratio, MIN_SPLIT_RATIO),
+    orientation:
+      orientation === "horizontal" || orientation === "vertical"
+        ? orientation
+        : DEFAULT_SPLIT_LAYOUT.orientation,
+  };
+}
+
+/**
+ * 設定ファイルのui.split_ratio（%）を分割比率に変換
+ * 未設定ならnull（保存済みのレイアウトを使う）、範囲外や数値でなければデフォルトの50%
+ */
+export function configSplitRatio(percent: number | null | undefined): number | null {
+  if (percent === null || percent === undefined) return null;
+  const ratio = percent / 100;
+  if (!Number.isFinite(ratio) || ratio < MIN_SPLIT_RATIO || ratio > 1 - MIN_SPLIT_RATIO) {
+    return DEFAULT_SPLIT_LAYOUT.ratio;
+  }
+  return ratio;
+}
+
+/** 分割比率を設定ファイルに保存する値（%、小数第1位まで）に変換 */
+export function toConfigSplitRatio(ratio: number): number {
+  return Math.round(ratio * 1000) / 10;
+}
+
+/** 分割比率を変更して保存（数値でなければ変更しない） */
+export function setSplitRatio(
+  layout: SplitLayout,
+  ratio: number,
+  minRatio = MIN_SPLIT_RATIO
+): SplitLayout {
+  if (!Number.isFinite(ratio)) return layout;
+  const next = clampRatio(ratio, minRatio);
+  storeNumber(STORAGE_KEYS.splitRatio, next);
+  return { ...layout, ratio: next };
+}
+
+/** ペインの並べ方を変更して保存 */
+export function setSplitOrientation(
+  layout: SplitLayout,
+  orientation: SplitOrientation
+): SplitLayout {
+  storeString(STORAGE_KEYS.splitOrientation, orientation);
+  return { ...layout, orientation };
+}
+
+/** スプリッターのドラッグ中に起きるポインターイベント */
+export type DividerDragEvent =
+  | { type: "down" }
+  /** buttonsは押されているボタン（MouseEvent.buttons） */
+  | { type: "move"; buttons: number }
+  /** ポインターがウィンドウやスプリッターの外に出た */
+  | { type: "leave" }
+  | { type: "up" }
+  /** OSやブラウザにポインターを奪われた */
+  | { type: "cancel" };
+
+/**
+ * イベント後にドラッグ中かを返す
+ * ボタンを離すまではウィンドウの外に出てもドラッグを続ける。
+ * 外で離してupを受け取れなかった場合も、次のmoveでボタンが離れていれば終える
+ */
+export function nextDragging(dragging: boolean, event: DividerDragEvent): boolean {
+  switch (event.type) {
+    case "down":
+      return true;
+    case "move":
+      // 主ボタン（左ボタン）が離れていれば終了
+      return dragging && (event.buttons & 1) === 1;
+    case "leave":
+      return dragging;
+    case "up":
+    case "cancel":
+      return false;
+  }
+}
+
+/** ステータスバー（ヘッダー）に表示する要素 */
+export interface StatusBarSections {
+  /** ヘッダー自体を表示するか */
+  visible: boolean;
+  /** プロジェクトパス */
+  project: boolean;
+  /** Sphinxの状態（ビルド中・起動中・エラーなど） */
+  status: boolean;
+  /** プレビューの操作ボタンとLogs・Open Project */
+  actions: boolean;
+}
+
+/** ui.status_bar から表示する要素を決める（未指定はfull） */
+export function statusBarSections(mode: StatusBarMode | undefined): StatusBarSections {
+  switch (mode) {
+    case "hidden":
+      return { visible: false, project: false, status: false, actions: false };
+    case "minimal":
+      return { visible: true, project: false, status: true, actions: false };
+    default:
+      return { visible: true, project: true, status: true, actions: true };
+  }
+}
@@ -0,0 +1,92 @@
+import { describe, it, expect } from "vitest";
+import type { IBuffer, IBufferCell, IBufferLine } from "@xterm/xterm";
+import { detectUrls, isLinkClick, trimUrl } from "./links";
+
+/** 全角文字か（テスト用の簡易判定） */
+const isWide = (char: string) => /[\u3000-\u9fff\uff00-\uffef]/.test(char);
+
+/** 固定幅の行を持つテスト用バッファ（全角文字は2セル） */
+function createBuffer(rows: { text: string; isWrapped?: boolean }[], cols = 20): IBuffer {
+  const lines = rows.map(({ text, isWrapped = false }) => {
+    const cells: { chars: string; width: number }[] = [];
+    for (const char of text) {
+      if (isWide(char)) {
+        cells.push({ chars: char, width: 2 }, { chars: "", width: 0 });
+      } else {
+        cells.push({ chars: char, width: 1 });
+      }
+    }
+    return {
+      isWrapped,
+      length: cols,
+      getCell: (x: number) => {
+        const cell = cells[x] ?? { chars: "", width: 1 };
+        return {
+          getChars: () => cell.chars,
+          getWidth: () => cell.width,
+        } as unknown as IBufferCell;
+      },
+    } as unknown as IBufferLine;
+  });
+  return { getLine: (y: number) => lines[y] } as unknown as IBuffer;
+}
+
+describe("detectUrls", () => {
+  it("should find http, https and file URLs with their columns", () => {
+    const buffer = createBuffer([{ text: "see http://a.io and file:///tmp/x" }], 40);
+    expect(detectUrls(buffer, 0)).toEqual([
+      { url: "http://a.io", start: { x: 4, y: 0 }, end: { x: 14, y: 0 } },
+      { url: "file:///tmp/x", start: { x: 20, y: 0 }, end: { x: 32, y: 0 } },
+    ]);
+  });
+
+  it("should follow a URL onto the wrapped row", () => {
+    const buffer = createBuffer(
+      [{ text: "go https://exam" }, { text: "ple.com/docs now", isWrapped: true }],
+      15
+    );
+    const span = { url: "https://example.com/docs", start: { x: 3, y: 0 }, end: { x: 11, y: 1 } };
+    expect(detectUrls(buffer, 0)).toEqual([span]);
+    expect(detectUrls(buffer, 1)).toEqual([span]);
+  });
+
+  it("should not return URLs of other rows", () => {
+    const buffer = createBuffer([{ text: "https://a.io" }, { text: "plain text" }]);
+    expect(detectUrls(buffer, 1)).toEqual([]);
+  });
+
+  it("should count wide characters as two cells", () => {
+    const buffer = createBuffer([{ text: "資料 https://a.io" }]);
+    expect(detectUrls(buffer, 0)).toEqual([
+      { url: "https://a.io", start: { x: 5, y: 0 }, end: { x: 16, y: 0 } },
+    ]);
+  });
+});
+
+describe("trimUrl", () => {
+  it("should drop trailing punctuation", () => {
+    expect(trimUrl("https://example.com.")).toBe("https://example.com");
+    expect(trimUrl("https://example.com/a,")).toBe("https://example.com/a");
+  });
+
+  it("should drop an unmatched closing bracket", () => {
+    expect(trimUrl("https://example.com)")).toBe("https://example.com");
+    expect(trimUrl("https://example.com/a).")).toBe("https://example.com/a");
+  });
+
+  it("should keep brackets that are part of the URL", () => {
+    expect(trimUrl("https://en.wikipedia.org/wiki/Sphinx_(documentation_generator)")).toBe(
+      "https://en.wikipedia.org/wiki/Sphinx_(documentation_generator)"
+    );
+  });
+});
+
+describe("isLinkClick", () => {
+  it("should require Cmd on macOS and Ctrl elsewhere", () => {
+    const click = (init: MouseEventInit) => new MouseEvent("click", init);
+    expect(isLinkClick(click({ metaKey: true }), true)).toBe(true);
+    expect(isLinkClick(click({ ctrlKey: true }), true)).toBe(false);
+    expect(isLinkClick(click({ ctrlKey: true }), false)).toBe(true);
+    expect(isLinkClick(click({}), false)).toBe(false);
+  });
+});
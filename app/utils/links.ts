@@ -0,0 +1,91 @@
+import type { IBuffer, IBufferCell } from "@xterm/xterm";
+
+// http(s)://とfile://のURL（空白・引用符・山括弧で終わる）
+const URL_PATTERN = /(?:https?|file):\/\/[^\s"'<>`]+/g;
+
+const OPENING_BRACKETS: Record<string, string> = { ")": "(", "]": "[", "}": "{" };
+
+/** ターミナル上のセルの位置（0始まり） */
+export interface CellPosition {
+  x: number;
+  y: number;
+}
+
+/** 出力中のURLと、画面上の範囲（endは最後の文字のセル） */
+export interface UrlSpan {
+  url: string;
+  start: CellPosition;
+  end: CellPosition;
+}
+
+/**
+ * URLの末尾に付いた句読点や閉じ括弧を除く
+ * 「(https://example.com)」や文末の「.」をURLに含めない。URL内で対応の取れている括弧は残す
+ */
+export function trimUrl(url: string): string {
+  let end = url.length;
+  while (end > 0) {
+    const last = url[end - 1];
+    if (".,;:!?".includes(last)) {
+      end--;
+      continue;
+    }
+    const open = OPENING_BRACKETS[last];
+    if (open) {
+      const text = url.slice(0, end);
+      if (text.split(open).length < text.split(last).length) {
+        end--;
+        continue;
+      }
+    }
+    break;
+  }
+  return url.slice(0, end);
+}
+
+/**
+ * y行目を含む論理行（折り返しで分割された行をまとめたもの）のテキストと、各文字のセル位置
+ * 全角文字は1文字で2セルを使うので、文字の位置とセルの列は一致しない
+ */
+function logicalLine(buffer: IBuffer, y: number): { text: string; cells: CellPosition[] } {
+  let first = y;
+  while (first > 0 && buffer.getLine(first)?.isWrapped) first--;
+
+  let text = "";
+  const cells: CellPosition[] = [];
+  let cell: IBufferCell | undefined;
+  for (let row = first; ; row++) {
+    const line = buffer.getLine(row);
+    if (!line || (row !== first && !line.isWrapped)) break;
+    for (let x = 0; x < line.length; x++) {
+      cell = line.getCell(x, cell);
+      // 全角文字の後半セルは幅0
+      if (!cell || cell.getWidth() === 0) continue;
+      const chars = cell.getChars() || " ";
+      text += chars;
+      for (let i = 0; i < chars.length; i++) cells.push({ x, y: row });
+    }
+  }
+  return { text, cells };
+}
+
+/**
+ * y行目に表示されているURLを探す
+ * 折り返しで次の行に続くURLも1つのURLとして、行をまたいだ範囲を返す
+ */
+export function detectUrls(buffer: IBuffer, y: number): UrlSpan[] {
+  const { text, cells } = logicalLine(buffer, y);
+  const spans: UrlSpan[] = [];
+  for (const match of text.matchAll(URL_PATTERN)) {
+    const url = trimUrl(match[0]);
+    const start = cells[match.index];
+    const end = cells[match.index + url.length - 1];
+    if (start.y <= y && end.y >= y) spans.push({ url, start, end });
+  }
+  return spans;
+}
+
+/** リンクを開くクリックか（選択操作と区別するため、macOSはCmd、それ以外はCtrlを押しながら） */
+export function isLinkClick(event: MouseEvent, isMac: boolean): boolean {
+  return isMac ? event.metaKey : event.ctrlKey;
+}
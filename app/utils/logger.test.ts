@@ -1,5 +1,12 @@
 import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
-import { logger } from "./logger";
+import {
+  logger,
+  logBuffer,
+  setLogLevel,
+  filterLogRecords,
+  LogBuffer,
+  type LogRecord,
+} from "./logger";
 
 describe("logger", () => {
   const originalEnv = import.meta.env.DEV;
@@ -68,4 +75,77 @@ describe("logger", () => {
       expect(console.warn).not.toHaveBeenCalled();
     });
   });
+
+  describe("log level", () => {
+    beforeEach(() => {
+      logBuffer.clear();
+    });
+
+    afterEach(() => {
+      setLogLevel("info");
+    });
+
+    it("should record messages at or above the configured level", () => {
+      setLogLevel("warn");
+      logger.log("ignored");
+      logger.warn("kept");
+      logger.error("also kept", { code: 1 });
+
+      const records = logBuffer.getRecords();
+      expect(records.map((r) => r.message)).toEqual(["kept", 'also kept {"code":1}']);
+      expect(records.map((r) => r.level)).toEqual(["warn", "error"]);
+    });
+
+    it("should record in production mode too", () => {
+      import.meta.env.DEV = false;
+      logger.error("production error");
+      import.meta.env.DEV = originalEnv;
+
+      expect(logBuffer.getRecords().map((r) => r.message)).toEqual(["production error"]);
+    });
+  });
+});
+
+describe("LogBuffer", () => {
+  it("should keep only the most recent records", () => {
+    const buffer = new LogBuffer(3);
+    ["a", "b", "c", "d", "e"].forEach((message) => buffer.push("info", message));
+
+    expect(buffer.getRecords().map((r) => r.message)).toEqual(["c", "d", "e"]);
+  });
+
+  it("should notify subscribers until they unsubscribe", () => {
+    const buffer = new LogBuffer(3);
+    const listener = vi.fn();
+    const unsubscribe = buffer.subscribe(listener);
+
+    buffer.push("info", "first");
+    unsubscribe();
+    buffer.push("info", "second");
+
+    expect(listener).toHaveBeenCalledTimes(1);
+  });
+
+  it("should return a stable snapshot between changes", () => {
+    const buffer = new LogBuffer(3);
+    buffer.push("info", "a");
+    expect(buffer.getRecords()).toBe(buffer.getRecords());
+  });
+});
+
+describe("filterLogRecords", () => {
+  const records: LogRecord[] = [
+    { id: 0, timestamp: 0, level: "debug", message: "d" },
+    { id: 1, timestamp: 0, level: "info", message: "i" },
+    { id: 2, timestamp: 0, level: "warn", message: "w" },
+    { id: 3, timestamp: 0, level: "error", message: "e" },
+  ];
+
+  it("should keep records at or above the minimum level", () => {
+    expect(filterLogRecords(records, "warn").map((r) => r.message)).toEqual(["w", "e"]);
+  });
+
+  it("should keep everything at debug", () => {
+    expect(filterLogRecords(records, "debug")).toHaveLength(4);
+  });
 });
@@ -1,22 +1,116 @@
+import type { LogLevel } from "../types/config";
+
+const LEVEL_ORDER: Record<LogLevel, number> = { debug: 0, info: 1, warn: 2, error: 3 };
+
+/** アプリ内ログビューアで保持する最大件数 */
+const MAX_LOG_RECORDS = 1000;
+
+export interface LogRecord {
+  id: number;
+  timestamp: number;
+  level: LogLevel;
+  message: string;
+}
+
+/** levelがminLevel以上か */
+export function isLevelEnabled(level: LogLevel, minLevel: LogLevel): boolean {
+  return LEVEL_ORDER[level] >= LEVEL_ORDER[minLevel];
+}
+
+/** minLevel以上のレコードのみを返す */
+export function filterLogRecords(records: LogRecord[], minLevel: LogLevel): LogRecord[] {
+  return records.filter((record) => isLevelEnabled(record.level, minLevel));
+}
+
+/** ログ引数を1行のメッセージに整形 */
+function formatLogArgs(args: unknown[]): string {
+  return args
+    .map((arg) => {
+      if (typeof arg === "string") return arg;
+      if (arg instanceof Error) return `${arg.name}: ${arg.message}`;
+      try {
+        return JSON.stringify(arg);
+      } catch {
+        return String(arg);
+      }
+    })
+    .join(" ");
+}
+
 /**
- * 開発時のみログを出力するロガー
- * 本番ビルドではログが出力されない
+ * 直近のログを保持するリングバッファ
+ * getRecordsは変更があるまで同じ配列を返す（useSyncExternalStore用）
+ */
+export class LogBuffer {
+  private records: LogRecord[] = [];
+  private nextId = 0;
+  private listeners = new Set<() => void>();
+
+  constructor(private readonly capacity: number) {}
+
+  push(level: LogLevel, message: string) {
+    const record = { id: this.nextId++, timestamp: Date.now(), level, message };
+    this.records = [...this.records.slice(-(this.capacity - 1)), record];
+    this.listeners.forEach((listener) => listener());
+  }
+
+  clear() {
+    this.records = [];
+    this.listeners.forEach((listener) => listener());
+  }
+
+  getRecords = (): LogRecord[] => this.records;
+
+  subscribe = (listener: () => void): (() => void) => {
+    this.listeners.add(listener);
+    return () => {
+      this.listeners.delete(listener);
+    };
+  };
+}
+
+/** アプリ内ログビューア用バッファ（本番ビルドでも記録する） */
+export const logBuffer = new LogBuffer(MAX_LOG_RECORDS);
+
+let currentLevel: LogLevel = "info";
+
+/** 記録する最低レベルを設定 */
+export function setLogLevel(level: LogLevel) {
+  currentLevel = level;
+}
+
+/** レベルが有効ならバッファに記録し、trueを返す */
+function record(level: LogLevel, args: unknown[]): boolean {
+  if (!isLevelEnabled(level, currentLevel)) return false;
+  logBuffer.push(level, formatLogArgs(args));
+  return true;
+}
+
+/**
+ * ロガー
+ * 設定レベル以上のログをバッファに記録し、開発時のみコンソールにも出力する
  */
 export const logger = {
+  debug: (...args: unknown[]) => {
+    if (record("debug", args) && import.meta.env.DEV) {
+      // eslint-disable-next-line no-console
+      console.debug(...args);
+    }
+  },
   log: (...args: unknown[]) => {
-    if (import.meta.env.DEV) {
+    if (record("info", args) && import.meta.env.DEV) {
       // eslint-disable-next-line no-console
       console.log(...args);
     }
   },
   error: (...args: unknown[]) => {
-    if (import.meta.env.DEV) {
+    if (record("error", args) && import.meta.env.DEV) {
       // eslint-disable-next-line no-console
       console.error(...args);
     }
   },
   warn: (...args: unknown[]) => {
-    if (import.meta.env.DEV) {
+    if (record("warn", args) && import.meta.env.DEV) {
       // eslint-disable-next-line no-console
       console.warn(...args);
     }
@@ -0,0 +1,49 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  OnboardingGate,
+  shouldShowOnboarding,
+  isOnboardingCompleted,
+  completeOnboarding,
+} from "./onboarding";
+
+describe("shouldShowOnboarding", () => {
+  const firstRun: OnboardingGate = {
+    devConfigLoaded: true,
+    hasDevProject: false,
+    configExists: false,
+    completed: false,
+  };
+
+  it("should show on the first run without a config file", () => {
+    expect(shouldShowOnboarding(firstRun)).toBe(true);
+  });
+
+  it("should not show once completed", () => {
+    expect(shouldShowOnboarding({ ...firstRun, completed: true })).toBe(false);
+  });
+
+  it("should not show when the user already has a config file", () => {
+    expect(shouldShowOnboarding({ ...firstRun, configExists: true })).toBe(false);
+  });
+
+  it("should not show when the dev config opens a project", () => {
+    expect(shouldShowOnboarding({ ...firstRun, hasDevProject: true })).toBe(false);
+  });
+
+  it("should wait until the dev config and the config file are checked", () => {
+    expect(shouldShowOnboarding({ ...firstRun, devConfigLoaded: false })).toBe(false);
+    expect(shouldShowOnboarding({ ...firstRun, configExists: null })).toBe(false);
+  });
+});
+
+describe("onboarding completion", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should remember that the onboarding was completed", () => {
+    expect(isOnboardingCompleted()).toBe(false);
+    completeOnboarding();
+    expect(isOnboardingCompleted()).toBe(true);
+  });
+});
@@ -0,0 +1,33 @@
+import { STORAGE_KEYS, loadString, storeString } from "./storage";
+
+/** 初回起動の案内を表示するかの判定材料 */
+export interface OnboardingGate {
+  /** dev configの読み込みが終わったか */
+  devConfigLoaded: boolean;
+  /** dev configでプロジェクトが指定されているか */
+  hasDevProject: boolean;
+  /** ユーザー設定ファイルが作成済みか（確認中はnull） */
+  configExists: boolean | null;
+  /** 案内を最後まで進めた、またはスキップしたか */
+  completed: boolean;
+}
+
+/**
+ * 初回起動の案内を表示するか
+ * 設定ファイルもdev configのプロジェクトも無く、まだ案内を終えていない場合のみ表示する
+ */
+export function shouldShowOnboarding(gate: OnboardingGate): boolean {
+  return (
+    gate.devConfigLoaded && !gate.hasDevProject && gate.configExists === false && !gate.completed
+  );
+}
+
+/** 案内を終えたことが保存されているか */
+export function isOnboardingCompleted(): boolean {
+  return loadString(STORAGE_KEYS.onboardingDone) !== null;
+}
+
+/** 案内を終えたことを保存（次回以降は表示しない） */
+export function completeOnboarding(): void {
+  storeString(STORAGE_KEYS.onboardingDone, "1");
+}
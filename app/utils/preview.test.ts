@@ -0,0 +1,307 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  shouldRenderIframe,
+  shouldAutoOpenBrowser,
+  shouldAutoStartSphinx,
+  resolvePreviewPage,
+  relativePageLink,
+  rebuildPauseReducer,
+  previewFrameKey,
+  headingUrl,
+  pinnedReloadUrl,
+  isIdleExpired,
+  INITIAL_REBUILD_PAUSE,
+  previewLoadReducer,
+  previewLoadStatus,
+  INITIAL_PREVIEW_LOAD,
+  previewDeviceFrame,
+  loadPreviewDevice,
+  storePreviewDevice,
+} from "./preview";
+import { STORAGE_KEYS } from "./storage";
+import type { ProjectConfig } from "../types/config";
+
+describe("shouldRenderIframe", () => {
+  it("should render the iframe in embedded mode", () => {
+    expect(shouldRenderIframe("embedded")).toBe(true);
+  });
+
+  it("should skip the iframe in external mode", () => {
+    expect(shouldRenderIframe("external")).toBe(false);
+  });
+});
+
+describe("shouldAutoOpenBrowser", () => {
+  const ready = {
+    mode: "external" as const,
+    openOnReady: false,
+    previewUrl: "http://127.0.0.1:8000",
+    alreadyOpened: false,
+  };
+
+  it("should open once the server is ready in external mode", () => {
+    expect(shouldAutoOpenBrowser(ready)).toBe(true);
+  });
+
+  it("should not open again in the same session", () => {
+    expect(shouldAutoOpenBrowser({ ...ready, alreadyOpened: true })).toBe(false);
+  });
+
+  it("should wait until the server has started", () => {
+    expect(shouldAutoOpenBrowser({ ...ready, previewUrl: null })).toBe(false);
+  });
+
+  it("should not open in embedded mode by default", () => {
+    expect(shouldAutoOpenBrowser({ ...ready, mode: "embedded" })).toBe(false);
+  });
+
+  it("should open once in embedded mode when open_browser_on_ready is set", () => {
+    const embedded = { ...ready, mode: "embedded" as const, openOnReady: true };
+    expect(shouldAutoOpenBrowser(embedded)).toBe(true);
+    expect(shouldAutoOpenBrowser({ ...embedded, alreadyOpened: true })).toBe(false);
+    expect(shouldAutoOpenBrowser({ ...embedded, previewUrl: null })).toBe(false);
+  });
+});
+
+describe("shouldAutoStartSphinx", () => {
+  const config = (enabled: boolean) => ({ preview: { enabled } }) as unknown as ProjectConfig;
+  const ready = {
+    config: config(true),
+    projectPath: "/project",
+    isRunning: false,
+    autoStart: true,
+  };
+
+  it("should start once a project and config are ready", () => {
+    expect(shouldAutoStartSphinx(ready)).toBe(true);
+  });
+
+  it("should never start when the preview is disabled", () => {
+    expect(shouldAutoStartSphinx({ ...ready, config: config(false) })).toBe(false);
+  });
+
+  it("should not start twice", () => {
+    expect(shouldAutoStartSphinx({ ...ready, isRunning: true })).toBe(false);
+  });
+
+  it("should respect auto_start_sphinx", () => {
+    expect(shouldAutoStartSphinx({ ...ready, autoStart: false })).toBe(false);
+  });
+
+  it("should wait for the project and config", () => {
+    expect(shouldAutoStartSphinx({ ...ready, projectPath: null })).toBe(false);
+    expect(shouldAutoStartSphinx({ ...ready, config: null })).toBe(false);
+  });
+});
+
+describe("resolvePreviewPage", () => {
+  const base = "http://127.0.0.1:8000";
+
+  it("should resolve a page relative to the server root", () => {
+    expect(resolvePreviewPage(base, "guide/install.html")).toBe(
+      "http://127.0.0.1:8000/guide/install.html"
+    );
+    expect(resolvePreviewPage(base, "/index.html#usage")).toBe(
+      "http://127.0.0.1:8000/index.html#usage"
+    );
+  });
+
+  it("should refuse pages on other origins", () => {
+    expect(resolvePreviewPage(base, "https://example.com/")).toBeNull();
+    expect(resolvePreviewPage(base, "javascript:alert(1)")).toBeNull();
+  });
+
+  it("should keep protocol-relative paths on the preview server", () => {
+    expect(resolvePreviewPage(base, "//example.com/index.html")).toBe(
+      "http://127.0.0.1:8000/example.com/index.html"
+    );
+  });
+});
+
+describe("relativePageLink", () => {
+  const base = "http://127.0.0.1:8000/";
+
+  it("should return the path from the server root", () => {
+    expect(relativePageLink("http://127.0.0.1:8000/guide/install.html", base)).toBe(
+      "guide/install.html"
+    );
+    expect(relativePageLink("http://127.0.0.1:8000/", base)).toBe("");
+  });
+
+  it("should keep the query and the anchor", () => {
+    expect(relativePageLink("http://127.0.0.1:8000/search.html?q=toctree#results", base)).toBe(
+      "search.html?q=toctree#results"
+    );
+  });
+
+  it("should round-trip through resolvePreviewPage", () => {
+    const url = "http://127.0.0.1:8000/guide/install.html#usage";
+    const link = relativePageLink(url, base);
+    expect(link && resolvePreviewPage(base, link)).toBe(url);
+  });
+
+  it("should reject pages on other origins", () => {
+    expect(relativePageLink("http://127.0.0.1:9000/index.html", base)).toBeNull();
+    expect(relativePageLink("not a url", base)).toBeNull();
+  });
+});
+
+describe("rebuildPauseReducer", () => {
+  it("should ignore builds while not paused", () => {
+    expect(rebuildPauseReducer(INITIAL_REBUILD_PAUSE, { type: "built" })).toBe(
+      INITIAL_REBUILD_PAUSE
+    );
+  });
+
+  it("should count builds while paused", () => {
+    let state = rebuildPauseReducer(INITIAL_REBUILD_PAUSE, { type: "toggle" });
+    state = rebuildPauseReducer(state, { type: "built" });
+    state = rebuildPauseReducer(state, { type: "built" });
+    expect(state).toEqual({ paused: true, missedBuilds: 2 });
+  });
+
+  it("should clear missed builds on resume", () => {
+    const state = rebuildPauseReducer({ paused: true, missedBuilds: 3 }, { type: "toggle" });
+    expect(state).toEqual({ paused: false, missedBuilds: 0 });
+  });
+});
+
+describe("previewLoadStatus", () => {
+  const fail = (times: number) => {
+    let state = INITIAL_PREVIEW_LOAD;
+    for (let i = 0; i < times; i++) state = previewLoadReducer(state, { type: "failed" });
+    return state;
+  };
+
+  it("should hide the overlay while the page loads", () => {
+    expect(previewLoadStatus(INITIAL_PREVIEW_LOAD, 3)).toBe("ok");
+  });
+
+  it("should retry automatically before showing the overlay", () => {
+    expect(previewLoadStatus(fail(1), 3)).toBe("retrying");
+    expect(previewLoadStatus(fail(3), 3)).toBe("retrying");
+    expect(previewLoadStatus(fail(4), 3)).toBe("failed");
+  });
+
+  it("should show the overlay immediately without automatic retries", () => {
+    expect(previewLoadStatus(fail(1), 0)).toBe("failed");
+  });
+
+  it("should hide the overlay once a retry loads", () => {
+    const state = previewLoadReducer(fail(2), { type: "loaded" });
+    expect(previewLoadStatus(state, 3)).toBe("ok");
+  });
+
+  it("should reset the retries when reloaded from the overlay", () => {
+    const state = previewLoadReducer(fail(4), { type: "retry" });
+    expect(previewLoadStatus(state, 3)).toBe("ok");
+    expect(previewLoadStatus(previewLoadReducer(state, { type: "failed" }), 3)).toBe("retrying");
+  });
+});
+
+describe("previewFrameKey", () => {
+  it("should recreate the iframe when pausing and resuming", () => {
+    const live = previewFrameKey(1, false);
+    const paused = previewFrameKey(1, true);
+    expect(paused).not.toBe(live);
+    // 再開時は停止中とは別のkeyになり、最新のビルドを読み込み直す
+    expect(previewFrameKey(1, false)).not.toBe(paused);
+  });
+});
+
+describe("headingUrl", () => {
+  it("should append the anchor to the page URL", () => {
+    expect(headingUrl("http://127.0.0.1:8000/guide.html", "install")).toBe(
+      "http://127.0.0.1:8000/guide.html#install"
+    );
+  });
+
+  it("should replace an existing fragment", () => {
+    expect(headingUrl("http://127.0.0.1:8000/guide.html#usage", "install")).toBe(
+      "http://127.0.0.1:8000/guide.html#install"
+    );
+  });
+});
+
+describe("pinnedReloadUrl", () => {
+  const page = "http://127.0.0.1:8000/guide.html";
+  const pinned = { page, anchor: "install" };
+  const headings = [
+    { level: 1, title: "Guide", anchor: "guide" },
+    { level: 2, title: "Install", anchor: "install" },
+  ];
+
+  it("should return to the pinned heading after a rebuild", () => {
+    expect(pinnedReloadUrl(pinned, `${page}#install`, headings)).toBe(`${page}#install`);
+  });
+
+  it("should restore the heading even if the page was scrolled to another one", () => {
+    expect(pinnedReloadUrl(pinned, `${page}#guide`, headings)).toBe(`${page}#install`);
+  });
+
+  it("should fall back to the top when the heading no longer exists", () => {
+    expect(pinnedReloadUrl(pinned, `${page}#install`, headings.slice(0, 1))).toBe(page);
+  });
+
+  it("should not reload when another page is shown", () => {
+    expect(pinnedReloadUrl(pinned, "http://127.0.0.1:8000/index.html", headings)).toBeNull();
+  });
+});
+
+describe("isIdleExpired", () => {
+  const lastActivity = 1_000_000;
+
+  it("should expire once the timeout has elapsed since the last activity", () => {
+    expect(isIdleExpired(lastActivity, lastActivity + 59_999, 60)).toBe(false);
+    expect(isIdleExpired(lastActivity, lastActivity + 60_000, 60)).toBe(true);
+  });
+
+  it("should never expire when the timeout is disabled", () => {
+    expect(isIdleExpired(lastActivity, lastActivity + 86_400_000, 0)).toBe(false);
+  });
+});
+
+describe("previewDeviceFrame", () => {
+  it("should fill the pane on desktop", () => {
+    expect(previewDeviceFrame("desktop")).toEqual({
+      width: "100%",
+      framed: false,
+      className: "border-0",
+    });
+  });
+
+  it("should constrain the width with a device frame on tablet and mobile", () => {
+    expect(previewDeviceFrame("tablet")).toMatchObject({ width: "768px", framed: true });
+    expect(previewDeviceFrame("mobile")).toMatchObject({ width: "375px", framed: true });
+  });
+
+  it("should keep the device frame outside the page width", () => {
+    // border-box（Tailwindの既定）のままだと、枠の分（8px × 2）だけページの表示幅が狭くなる
+    for (const device of ["tablet", "mobile"] as const) {
+      const classes = previewDeviceFrame(device).className.split(" ");
+      expect(classes).toContain("box-content");
+      expect(classes).toContain("border-8");
+    }
+  });
+});
+
+describe("loadPreviewDevice", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should default to desktop", () => {
+    expect(loadPreviewDevice()).toBe("desktop");
+  });
+
+  it("should restore the stored device", () => {
+    storePreviewDevice("mobile");
+    expect(localStorage.getItem(STORAGE_KEYS.previewDevice)).toBe("mobile");
+    expect(loadPreviewDevice()).toBe("mobile");
+  });
+
+  it("should ignore an unknown device", () => {
+    localStorage.setItem(STORAGE_KEYS.previewDevice, "watch");
+    expect(loadPreviewDevice()).toBe("desktop");
+  });
+});
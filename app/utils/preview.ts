@@ -0,0 +1,225 @@
+import type { PreviewMode, ProjectConfig } from "../types/config";
+import type { Heading } from "../types/outline";
+import { STORAGE_KEYS, loadString, storeString } from "./storage";
+
+/** iframeでプレビューを描画するか（externalモードではiframe自体を作らない） */
+export function shouldRenderIframe(mode: PreviewMode): boolean {
+  return mode === "embedded";
+}
+
+interface AutoOpenOptions {
+  mode: PreviewMode;
+  /** preview.open_browser_on_ready */
+  openOnReady: boolean;
+  previewUrl: string | null;
+  /** このセッションで既に開いたか */
+  alreadyOpened: boolean;
+}
+
+/**
+ * 外部ブラウザを自動で開くか（サーバー起動後、セッション中1回のみ）
+ * externalモードか、open_browser_on_readyが有効な場合に開く
+ */
+export function shouldAutoOpenBrowser({
+  mode,
+  openOnReady,
+  previewUrl,
+  alreadyOpened,
+}: AutoOpenOptions): boolean {
+  if (mode !== "external" && !openOnReady) return false;
+  return previewUrl !== null && !alreadyOpened;
+}
+
+interface AutoStartOptions {
+  config: ProjectConfig | null;
+  projectPath: string | null;
+  isRunning: boolean;
+  /** .khafre.dev.jsonのauto_start_sphinx */
+  autoStart: boolean;
+}
+
+/** sphinx-autobuildを自動起動するか（preview.enabledがfalseなら起動しない） */
+export function shouldAutoStartSphinx({
+  config,
+  projectPath,
+  isRunning,
+  autoStart,
+}: AutoStartOptions): boolean {
+  if (!config || !projectPath || isRunning || !autoStart) return false;
+  return config.preview.enabled;
+}
+
+/**
+ * 制御コマンドで指定されたページをプレビューサーバー上のURLに解決
+ * サーバー外のURLは開かない
+ */
+export function resolvePreviewPage(baseUrl: string, page: string): string | null {
+  try {
+    const base = new URL(baseUrl);
+    const target = new URL(page.replace(/^\/+/, ""), `${base.origin}/`);
+    return target.origin === base.origin ? target.href : null;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * プレビューのページURLをサーバールートからの相対パスに変換（共有用）
+ * navigateコマンドのpageにそのまま渡せる形式。サーバー外のURLはnull
+ */
+export function relativePageLink(pageUrl: string, baseUrl: string): string | null {
+  try {
+    const base = new URL(baseUrl);
+    const page = new URL(pageUrl);
+    if (page.origin !== base.origin) return null;
+    return `${page.pathname.replace(/^\/+/, "")}${page.search}${page.hash}`;
+  } catch {
+    return null;
+  }
+}
+
+/** 自動再ビルドの一時停止状態 */
+export interface RebuildPauseState {
+  paused: boolean;
+  /** 一時停止中に完了したビルド数（再開時に反映される） */
+  missedBuilds: number;
+}
+
+export type RebuildPauseAction = { type: "toggle" } | { type: "built" };
+
+export const INITIAL_REBUILD_PAUSE: RebuildPauseState = { paused: false, missedBuilds: 0 };
+
+/** 一時停止の切り替えと、停止中のビルド完了の記録 */
+export function rebuildPauseReducer(
+  state: RebuildPauseState,
+  action: RebuildPauseAction
+): RebuildPauseState {
+  switch (action.type) {
+    case "toggle":
+      return { paused: !state.paused, missedBuilds: 0 };
+    case "built":
+      return state.paused ? { ...state, missedBuilds: state.missedBuilds + 1 } : state;
+  }
+}
+
+/** 読み込みに失敗した時に自動でやり直す回数 */
+export const PREVIEW_LOAD_RETRIES = 3;
+
+/** プレビューiframeの読み込み状態 */
+export interface PreviewLoadState {
+  /** 続けて読み込みに失敗した回数 */
+  failures: number;
+}
+
+export type PreviewLoadAction = { type: "loaded" } | { type: "failed" } | { type: "retry" };
+
+export const INITIAL_PREVIEW_LOAD: PreviewLoadState = { failures: 0 };
+
+/** 読み込みの成否を記録（手動でやり直すと自動再試行の回数も戻す） */
+export function previewLoadReducer(
+  state: PreviewLoadState,
+  action: PreviewLoadAction
+): PreviewLoadState {
+  switch (action.type) {
+    case "loaded":
+    case "retry":
+      return state.failures === 0 ? state : INITIAL_PREVIEW_LOAD;
+    case "failed":
+      return { failures: state.failures + 1 };
+  }
+}
+
+/**
+ * 読み込みに失敗した後の表示
+ * 自動再試行の回数が残っていればretrying、使い切ったら再読み込みボタンを出す（failed）
+ */
+export function previewLoadStatus(
+  state: PreviewLoadState,
+  maxRetries: number
+): "ok" | "retrying" | "failed" {
+  if (state.failures === 0) return "ok";
+  return state.failures <= maxRetries ? "retrying" : "failed";
+}
+
+/**
+ * プレビューiframeのkey
+ * 一時停止の切り替えでiframeを作り直す。停止中はスクリプトを無効にしてlivereloadを止め、
+ * 再開時は最新のビルド結果を読み込み直して追いつく
+ */
+export function previewFrameKey(reloadToken: number, paused: boolean): string {
+  return paused ? `${reloadToken}-paused` : String(reloadToken);
+}
+
+/** 表示中ページの見出しへのURL（既存のフラグメントは置き換える） */
+export function headingUrl(pageUrl: string, anchor: string): string {
+  return `${pageUrl.split("#")[0]}#${anchor}`;
+}
+
+/** アウトラインで選んだ、再ビルド後も表示を保つ見出し */
+export interface PinnedHeading {
+  /** 見出しのあるページ（フラグメントなし） */
+  page: string;
+  anchor: string;
+}
+
+/**
+ * 再ビルド後にプレビューを開き直すURL
+ * 別のページに移っていればnull（開き直さない）。見出しが無くなっていればページの先頭
+ */
+export function pinnedReloadUrl(
+  pinned: PinnedHeading,
+  pageUrl: string,
+  headings: Heading[]
+): string | null {
+  if (pageUrl.split("#")[0] !== pinned.page) return null;
+  const exists = headings.some((heading) => heading.anchor === pinned.anchor);
+  return exists ? headingUrl(pinned.page, pinned.anchor) : pinned.page;
+}
+
+/** 最後の操作からtimeoutSecs秒以上経ったか（0以下なら停止しない） */
+export function isIdleExpired(lastActivity: number, now: number, timeoutSecs: number): boolean {
+  return timeoutSecs > 0 && now - lastActivity >= timeoutSecs * 1000;
+}
+
+/** プレビューの表示幅（desktop: ペインの幅いっぱい） */
+export type PreviewDevice = "mobile" | "tablet" | "desktop";
+
+/** ツールバーで選べる表示幅（widthはpx、nullなら幅いっぱい） */
+export const PREVIEW_DEVICES: { device: PreviewDevice; label: string; width: number | null }[] = [
+  { device: "desktop", label: "Desktop", width: null },
+  { device: "tablet", label: "Tablet (768px)", width: 768 },
+  { device: "mobile", label: "Mobile (375px)", width: 375 },
+];
+
+export const DEFAULT_PREVIEW_DEVICE: PreviewDevice = "desktop";
+
+/** 端末の枠（box-contentで枠を幅の外側に付け、ページの表示幅をプリセットの幅に合わせる） */
+const DEVICE_FRAME_CLASS = "box-content border-8 border-gray-700 rounded-xl";
+
+/**
+ * iframeの幅と枠のクラス（幅を絞る時は中央に寄せて枠を付ける）
+ * widthはページの表示幅で、枠の分はその外側に足される
+ */
+export function previewDeviceFrame(device: PreviewDevice): {
+  width: string;
+  framed: boolean;
+  className: string;
+} {
+  const width = PREVIEW_DEVICES.find((preset) => preset.device === device)?.width ?? null;
+  return width === null
+    ? { width: "100%", framed: false, className: "border-0" }
+    : { width: `${width}px`, framed: true, className: DEVICE_FRAME_CLASS };
+}
+
+/** 保存された表示幅を読み込む（未保存・不正な値はデフォルト） */
+export function loadPreviewDevice(): PreviewDevice {
+  const device = loadString(STORAGE_KEYS.previewDevice);
+  return PREVIEW_DEVICES.some((preset) => preset.device === device)
+    ? (device as PreviewDevice)
+    : DEFAULT_PREVIEW_DEVICE;
+}
+
+/** 表示幅を保存 */
+export function storePreviewDevice(device: PreviewDevice): void {
+  storeString(STORAGE_KEYS.previewDevice, device);
+}
@@ -0,0 +1,115 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  MAX_RECENT_PROJECTS,
+  addRecentProject,
+  filterProjects,
+  fuzzyScore,
+  loadRecentProjects,
+  projectName,
+  projectRelativePath,
+  storeRecentProjects,
+} from "./projects";
+
+describe("fuzzyScore", () => {
+  it("should match the query characters in order", () => {
+    expect(fuzzyScore("kfr", "khafre")).not.toBeNull();
+    expect(fuzzyScore("rfk", "khafre")).toBeNull();
+  });
+
+  it("should ignore case and spaces in the query", () => {
+    expect(fuzzyScore("Kha fre", "KHAFRE")).not.toBeNull();
+  });
+
+  it("should prefer consecutive and word-start matches", () => {
+    expect(fuzzyScore("doc", "docs-site")!).toBeGreaterThan(fuzzyScore("doc", "random-doc")!);
+    expect(fuzzyScore("ds", "docs-site")!).toBeGreaterThan(fuzzyScore("ds", "odds")!);
+  });
+});
+
+describe("filterProjects", () => {
+  const paths = [
+    "/home/me/src/khafre",
+    "/home/me/work/docs-site",
+    "/home/me/src/kernel-docs",
+    "/tmp/k/h/a/f",
+  ];
+
+  it("should keep the recent order when the query is empty", () => {
+    expect(filterProjects("", paths)).toEqual(paths);
+    expect(filterProjects("  ", paths)).toEqual(paths);
+  });
+
+  it("should rank the best match first", () => {
+    expect(filterProjects("kd", paths)[0]).toBe("/home/me/src/kernel-docs");
+    expect(filterProjects("docs", paths)).toEqual([
+      "/home/me/work/docs-site",
+      "/home/me/src/kernel-docs",
+    ]);
+  });
+
+  it("should rank matches in the project name above matches in the path", () => {
+    expect(filterProjects("khaf", paths)).toEqual(["/home/me/src/khafre", "/tmp/k/h/a/f"]);
+  });
+
+  it("should match the full path", () => {
+    expect(filterProjects("work", paths)).toEqual(["/home/me/work/docs-site"]);
+  });
+
+  it("should return nothing when no project matches", () => {
+    expect(filterProjects("zzz", paths)).toEqual([]);
+  });
+});
+
+describe("projectName", () => {
+  it("should return the last directory name", () => {
+    expect(projectName("/home/me/src/khafre")).toBe("khafre");
+    expect(projectName("/home/me/src/khafre/")).toBe("khafre");
+    expect(projectName("C:\\Users\\me\\docs")).toBe("docs");
+  });
+});
+
+describe("projectRelativePath", () => {
+  it("should return the path relative to the project root", () => {
+    expect(projectRelativePath("/home/me/proj", "/home/me/proj/doc/source")).toBe("doc/source");
+    expect(projectRelativePath("/home/me/proj/", "/home/me/proj")).toBe(".");
+    expect(projectRelativePath("C:\\proj", "C:\\proj\\docs")).toBe("docs");
+  });
+
+  it("should return null outside the project", () => {
+    expect(projectRelativePath("/home/me/proj", "/home/me/other/docs")).toBeNull();
+    expect(projectRelativePath("/home/me/proj", "/home/me/project/docs")).toBeNull();
+    expect(projectRelativePath("/home/me/proj", "/home/me")).toBeNull();
+  });
+});
+
+describe("recent projects", () => {
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should move the opened project to the front without duplicates", () => {
+    expect(addRecentProject(["/a", "/b", "/c"], "/b")).toEqual(["/b", "/a", "/c"]);
+    expect(addRecentProject([], "/a")).toEqual(["/a"]);
+  });
+
+  it("should keep at most MAX_RECENT_PROJECTS entries", () => {
+    const recent = Array.from({ length: MAX_RECENT_PROJECTS }, (_, i) => `/p${i}`);
+    const next = addRecentProject(recent, "/new");
+    expect(next).toHaveLength(MAX_RECENT_PROJECTS);
+    expect(next[0]).toBe("/new");
+    expect(next).not.toContain(`/p${MAX_RECENT_PROJECTS - 1}`);
+  });
+
+  it("should save and restore the recent projects", () => {
+    expect(loadRecentProjects()).toEqual([]);
+    storeRecentProjects(["/a", "/b"]);
+    expect(loadRecentProjects()).toEqual(["/a", "/b"]);
+  });
+
+  it("should ignore a broken saved value", () => {
+    localStorage.setItem("khafre.projects.recent", "{not json");
+    expect(loadRecentProjects()).toEqual([]);
+    localStorage.setItem("khafre.projects.recent", JSON.stringify(["/a", 1, null]));
+    expect(loadRecentProjects()).toEqual(["/a"]);
+  });
+});
@@ -0,0 +1,88 @@
+import { STORAGE_KEYS, loadString, storeString } from "./storage";
+
+/** 保存する最近のプロジェクトの数 */
+export const MAX_RECENT_PROJECTS = 10;
+
+// この文字の直後は単語の先頭とみなす
+const WORD_SEPARATORS = "/\\-_. ";
+// プロジェクト名（パスの末尾）に一致した場合の加点
+const NAME_MATCH_BONUS = 10;
+
+/** 保存された最近のプロジェクト（新しい順） */
+export function loadRecentProjects(): string[] {
+  const value = loadString(STORAGE_KEYS.recentProjects);
+  if (value === null) return [];
+  try {
+    const parsed: unknown = JSON.parse(value);
+    if (!Array.isArray(parsed)) return [];
+    return parsed.filter((path): path is string => typeof path === "string");
+  } catch {
+    return [];
+  }
+}
+
+/** 最近のプロジェクトを保存 */
+export function storeRecentProjects(paths: string[]): void {
+  storeString(STORAGE_KEYS.recentProjects, JSON.stringify(paths));
+}
+
+/** 開いたプロジェクトを先頭に移す（重複は除き、MAX_RECENT_PROJECTS件まで） */
+export function addRecentProject(recent: string[], path: string): string[] {
+  return [path, ...recent.filter((p) => p !== path)].slice(0, MAX_RECENT_PROJECTS);
+}
+
+/** パスの末尾のディレクトリ名 */
+export function projectName(path: string): string {
+  const parts = path.split(/[/\\]/).filter(Boolean);
+  return parts[parts.length - 1] ?? path;
+}
+
+/**
+ * プロジェクト内のパスをプロジェクトルートからの相対パスにする（区切りは/）
+ * プロジェクトの外ならnull、ルート自体なら"."
+ */
+export function projectRelativePath(projectPath: string, path: string): string | null {
+  const split = (p: string) => p.split(/[/\\]/).filter(Boolean);
+  const root = split(projectPath);
+  const parts = split(path);
+  if (parts.length < root.length || root.some((part, i) => parts[i] !== part)) return null;
+  return parts.slice(root.length).join("/") || ".";
+}
+
+/**
+ * queryの文字がtextに順に含まれていればスコアを返す（含まれなければnull）
+ * 連続した一致と単語の先頭での一致を高く評価し、短いtextほど僅かに高くする
+ * 大文字小文字と、queryの空白は無視する
+ */
+export function fuzzyScore(query: string, text: string): number | null {
+  const target = text.toLowerCase();
+  let score = 0;
+  let from = 0;
+  let previous = -2;
+  for (const char of query.toLowerCase()) {
+    if (char === " ") continue;
+    const index = target.indexOf(char, from);
+    if (index === -1) return null;
+    score += 1;
+    if (index === previous + 1) score += 2;
+    if (index === 0 || WORD_SEPARATORS.includes(target[index - 1])) score += 3;
+    previous = index;
+    from = index + 1;
+  }
+  return score - target.length * 0.01;
+}
+
+/**
+ * queryに一致するプロジェクトをスコアの高い順に返す
+ * プロジェクト名での一致をパス全体での一致より優先し、同点なら元の順（新しい順）を保つ
+ */
+export function filterProjects(query: string, paths: string[]): string[] {
+  if (query.trim() === "") return paths;
+  const scored: { path: string; score: number }[] = [];
+  for (const path of paths) {
+    const nameScore = fuzzyScore(query, projectName(path));
+    const score = nameScore !== null ? nameScore + NAME_MATCH_BONUS : fuzzyScore(query, path);
+    if (score !== null) scored.push({ path, score });
+  }
+  return scored.sort((a, b) => b.score - a.score).map(({ path }) => path);
+}
@@ -0,0 +1,44 @@
+import { describe, it, expect } from "vitest";
+import { adjacentPromptLine, findPromptLines } from "./prompt";
+
+const scrollback = [
+  "user@host:~/docs$ make html",
+  "Running Sphinx v7.2.6",
+  "build succeeded.",
+  "[user@host docs]$ git status",
+  "On branch main",
+  "~/docs % ls",
+  "conf.py  index.rst",
+  "❯ python",
+  ">>> import sphinx",
+  "total: $ 5",
+  "",
+];
+
+describe("findPromptLines", () => {
+  it("should detect common shell prompts with the default pattern", () => {
+    expect(findPromptLines(scrollback)).toEqual([0, 3, 7, 8]);
+  });
+
+  it("should use a custom pattern", () => {
+    expect(findPromptLines(scrollback, "^~/docs % ")).toEqual([5]);
+  });
+
+  it("should return no lines for an invalid pattern", () => {
+    expect(findPromptLines(scrollback, "(")).toEqual([]);
+  });
+});
+
+describe("adjacentPromptLine", () => {
+  const prompts = [0, 3, 7, 8];
+
+  it("should find the previous prompt above the current line", () => {
+    expect(adjacentPromptLine(prompts, 7, "previous")).toBe(3);
+    expect(adjacentPromptLine(prompts, 0, "previous")).toBeNull();
+  });
+
+  it("should find the next prompt below the current line", () => {
+    expect(adjacentPromptLine(prompts, 3, "next")).toBe(7);
+    expect(adjacentPromptLine(prompts, 8, "next")).toBeNull();
+  });
+});
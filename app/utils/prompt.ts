@@ -0,0 +1,34 @@
+/**
+ * terminal.prompt_pattern未設定時のプロンプト判定
+ * 先頭の語（または [user@host dir] のような括弧）の直後に $ # % > ❯ と空白が続く行
+ */
+export const DEFAULT_PROMPT_PATTERN = "^(?:\\[[^\\]]*\\]|\\S*)[$#%>❯] ";
+
+/** プロンプトの正規表現を作成（不正なパターンはnull） */
+function compilePattern(pattern: string): RegExp | null {
+  try {
+    return new RegExp(pattern);
+  } catch {
+    return null;
+  }
+}
+
+/** スクロールバックの各行のうちプロンプト行の行番号を返す */
+export function findPromptLines(lines: string[], pattern = DEFAULT_PROMPT_PATTERN): number[] {
+  const regex = compilePattern(pattern);
+  if (!regex) return [];
+  return lines.flatMap((line, index) => (regex.test(line) ? [index] : []));
+}
+
+/** 現在の表示位置から前後のプロンプト行を探す（無ければnull） */
+export function adjacentPromptLine(
+  promptLines: number[],
+  currentLine: number,
+  direction: "previous" | "next"
+): number | null {
+  if (direction === "previous") {
+    const previous = promptLines.filter((line) => line < currentLine);
+    return previous.length > 0 ? previous[previous.length - 1] : null;
+  }
+  return promptLines.find((line) => line > currentLine) ?? null;
+}
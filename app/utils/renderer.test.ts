@@ -0,0 +1,60 @@
+import { describe, it, expect, vi, afterEach } from "vitest";
+import { loadRenderer, type WebglRendererAddon } from "./renderer";
+import { logger } from "./logger";
+
+/** onContextLossのリスナーを呼べるアドオンの代わり */
+function fakeAddon() {
+  let contextLoss: (() => void) | null = null;
+  const addon = {
+    activate: vi.fn(),
+    dispose: vi.fn(),
+    onContextLoss: vi.fn((listener: () => void) => {
+      contextLoss = listener;
+      return { dispose: () => {} };
+    }),
+  } satisfies WebglRendererAddon;
+  return { addon, loseContext: () => contextLoss?.() };
+}
+
+describe("loadRenderer", () => {
+  afterEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it("should keep the DOM renderer by default", () => {
+    const terminal = { loadAddon: vi.fn() };
+    const createAddon = vi.fn();
+
+    loadRenderer(terminal, undefined, createAddon);
+    loadRenderer(terminal, "dom", createAddon);
+
+    expect(createAddon).not.toHaveBeenCalled();
+    expect(terminal.loadAddon).not.toHaveBeenCalled();
+  });
+
+  it("should load the WebGL renderer and drop it when the context is lost", () => {
+    vi.spyOn(logger, "warn").mockImplementation(() => {});
+    const terminal = { loadAddon: vi.fn() };
+    const { addon, loseContext } = fakeAddon();
+
+    loadRenderer(terminal, "webgl", () => addon);
+    expect(terminal.loadAddon).toHaveBeenCalledWith(addon);
+    expect(addon.dispose).not.toHaveBeenCalled();
+
+    // アドオンを外すとxterm.jsはDOMでの描画に戻る
+    loseContext();
+    expect(addon.dispose).toHaveBeenCalledTimes(1);
+  });
+
+  it("should fall back to the DOM renderer when WebGL is not available", () => {
+    const warn = vi.spyOn(logger, "warn").mockImplementation(() => {});
+    const terminal = {
+      loadAddon: vi.fn(() => {
+        throw new Error("WebGL2 not supported");
+      }),
+    };
+
+    expect(() => loadRenderer(terminal, "webgl", () => fakeAddon().addon)).not.toThrow();
+    expect(warn).toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,34 @@
+import type { IDisposable, ITerminalAddon, Terminal } from "@xterm/xterm";
+import { WebglAddon } from "@xterm/addon-webgl";
+import type { TerminalRenderer } from "../types/config";
+import { logger } from "./logger";
+
+/** WebGLの描画アドオン（コンテキストが失われた時に通知する） */
+export interface WebglRendererAddon extends ITerminalAddon {
+  onContextLoss: (listener: () => void) => IDisposable;
+}
+
+/**
+ * terminal.rendererが"webgl"ならWebGLで描画する（terminal.openの後に呼ぶ）
+ * WebGLが使えない場合や、GPUのリセットなどでコンテキストが失われた場合は
+ * アドオンを外してxterm.js標準のDOMでの描画に戻す
+ */
+export function loadRenderer(
+  terminal: Pick<Terminal, "loadAddon">,
+  renderer: TerminalRenderer | undefined,
+  createAddon: () => WebglRendererAddon = () => new WebglAddon()
+): void {
+  if (renderer !== "webgl") return;
+  let addon: WebglRendererAddon;
+  try {
+    addon = createAddon();
+    terminal.loadAddon(addon);
+  } catch (e) {
+    logger.warn("WebGL is not available, using the DOM renderer:", e);
+    return;
+  }
+  addon.onContextLoss(() => {
+    logger.warn("Lost the WebGL context, using the DOM renderer");
+    addon.dispose();
+  });
+}
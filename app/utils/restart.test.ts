@@ -0,0 +1,39 @@
+import { describe, it, expect } from "vitest";
+import { nextRestart, restartDelay, RESTART_MAX_DELAY_MS } from "./restart";
+
+describe("restartDelay", () => {
+  it("should double the delay for each attempt up to the maximum", () => {
+    expect(restartDelay(1)).toBe(1000);
+    expect(restartDelay(2)).toBe(2000);
+    expect(restartDelay(3)).toBe(4000);
+    expect(restartDelay(10)).toBe(RESTART_MAX_DELAY_MS);
+  });
+});
+
+describe("nextRestart", () => {
+  it("should restart with backoff until max_restarts is reached", () => {
+    expect(nextRestart(0, 3, null)).toEqual({ type: "restart", attempt: 1, delayMs: 1000 });
+    expect(nextRestart(2, 3, null)).toEqual({ type: "restart", attempt: 3, delayMs: 4000 });
+  });
+
+  it("should retry immediately when the auto-assigned port was taken", () => {
+    expect(nextRestart(1, 3, null, true)).toEqual({ type: "restart", attempt: 2, delayMs: 0 });
+    expect(nextRestart(3, 3, null, true).type).toBe("give_up");
+  });
+
+  it("should give up with a summary after exhausting the restarts", () => {
+    expect(nextRestart(3, 3, "ModuleNotFoundError: No module named 'sphinx'")).toEqual({
+      type: "give_up",
+      message:
+        "sphinx-autobuild kept crashing (gave up after 3 restarts): " +
+        "ModuleNotFoundError: No module named 'sphinx'",
+    });
+  });
+
+  it("should not restart when max_restarts is 0", () => {
+    expect(nextRestart(0, 0, null)).toEqual({
+      type: "give_up",
+      message: "sphinx-autobuild exited unexpectedly",
+    });
+  });
+});
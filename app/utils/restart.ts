@@ -0,0 +1,35 @@
+// 再起動までの待ち時間（1回目は1秒、以降倍々で上限まで）
+export const RESTART_BASE_DELAY_MS = 1000;
+export const RESTART_MAX_DELAY_MS = 30000;
+
+/** 異常終了後の再起動の判断 */
+export type RestartDecision =
+  | { type: "restart"; attempt: number; delayMs: number }
+  | { type: "give_up"; message: string };
+
+/** attempt回目（1始まり）の再起動までの待ち時間 */
+export function restartDelay(attempt: number): number {
+  return Math.min(RESTART_BASE_DELAY_MS * 2 ** (attempt - 1), RESTART_MAX_DELAY_MS);
+}
+
+/**
+ * 異常終了時に再起動するか判断する
+ * attemptsはビルド成功以降に再起動した回数。lastErrorは最後に出力されたエラー
+ * retryNow: 自動割り当てのポートが他のプロセスに取られた時など、待たずに起動し直す
+ */
+export function nextRestart(
+  attempts: number,
+  maxRestarts: number,
+  lastError: string | null,
+  retryNow = false
+): RestartDecision {
+  if (attempts < maxRestarts) {
+    const attempt = attempts + 1;
+    return { type: "restart", attempt, delayMs: retryNow ? 0 : restartDelay(attempt) };
+  }
+  const summary =
+    maxRestarts > 0
+      ? `sphinx-autobuild kept crashing (gave up after ${maxRestarts} restarts)`
+      : "sphinx-autobuild exited unexpectedly";
+  return { type: "give_up", message: lastError ? `${summary}: ${lastError}` : summary };
+}
@@ -0,0 +1,38 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { loadNumber, storeNumber, loadString, storeString, STORAGE_KEYS } from "./storage";
+
+describe("storage", () => {
+  const key = STORAGE_KEYS.terminalFontSize;
+
+  beforeEach(() => {
+    localStorage.clear();
+  });
+
+  it("should return null when nothing is stored", () => {
+    expect(loadNumber(key)).toBeNull();
+  });
+
+  it("should round-trip a number", () => {
+    storeNumber(key, 16);
+    expect(loadNumber(key)).toBe(16);
+  });
+
+  it("should remove the value when storing null", () => {
+    storeNumber(key, 16);
+    storeNumber(key, null);
+    expect(localStorage.getItem(key)).toBeNull();
+  });
+
+  it("should ignore values that are not numbers", () => {
+    localStorage.setItem(key, "large");
+    expect(loadNumber(key)).toBeNull();
+  });
+
+  it("should round-trip a string", () => {
+    const stringKey = STORAGE_KEYS.splitOrientation;
+    storeString(stringKey, "vertical");
+    expect(loadString(stringKey)).toBe("vertical");
+    storeString(stringKey, null);
+    expect(loadString(stringKey)).toBeNull();
+  });
+});
@@ -0,0 +1,58 @@
+/** localStorageに保存するUI状態のキー */
+export const STORAGE_KEYS = {
+  terminalFontSize: "khafre.terminal.fontSize",
+  splitRatio: "khafre.layout.splitRatio",
+  splitOrientation: "khafre.layout.splitOrientation",
+  onboardingDone: "khafre.onboarding.done",
+  recentProjects: "khafre.projects.recent",
+  previewDevice: "khafre.preview.device",
+} as const;
+
+type StorageKey = (typeof STORAGE_KEYS)[keyof typeof STORAGE_KEYS];
+
+/** 保存された数値を読み込む（未保存・不正な値はnull） */
+export function loadNumber(key: StorageKey): number | null {
+  try {
+    const value = localStorage.getItem(key);
+    if (value === null) return null;
+    const parsed = Number(value);
+    return Number.isFinite(parsed) ? parsed : null;
+  } catch {
+    return null;
+  }
+}
+
+/** 数値を保存（nullなら削除） */
+export function storeNumber(key: StorageKey, value: number | null): void {
+  try {
+    if (value === null) {
+      localStorage.removeItem(key);
+    } else {
+      localStorage.setItem(key, String(value));
+    }
+  } catch {
+    // ストレージが使えない環境では保存しない
+  }
+}
+
+/** 保存された文字列を読み込む（未保存ならnull） */
+export function loadString(key: StorageKey): string | null {
+  try {
+    return localStorage.getItem(key);
+  } catch {
+    return null;
+  }
+}
+
+/** 文字列を保存（nullなら削除） */
+export function storeString(key: StorageKey, value: string | null): void {
+  try {
+    if (value === null) {
+      localStorage.removeItem(key);
+    } else {
+      localStorage.setItem(key, value);
+    }
+  } catch {
+    // ストレージが使えない環境では保存しない
+  }
+}
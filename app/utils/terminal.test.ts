@@ -0,0 +1,772 @@
+import { describe, it, expect, vi } from "vitest";
+import { Terminal as XTerm } from "@xterm/xterm";
+import type { ITerminalOptions } from "@xterm/xterm";
+import {
+  selectColorScheme,
+  isScrolledUp,
+  outputScrollTarget,
+  buildTerminalOptions,
+  buildEditorCommand,
+  buildContainerStyle,
+  clampBackgroundOpacity,
+  hasTranslucentBackground,
+  withAlpha,
+  MIN_BACKGROUND_OPACITY,
+  applyCursorColors,
+  ensureContrast,
+  parseHexColor,
+  dispatchExitAction,
+  fontSizeShortcut,
+  isAnsiCopyShortcut,
+  isEnterCopy,
+  isPasteShortcut,
+  isProjectSwitcherShortcut,
+  promptJumpShortcut,
+  stepFontSize,
+  createResizeGate,
+  clampTerminalSize,
+  minTerminalSize,
+  parseOsc7Cwd,
+  duplicateCwd,
+  terminalReloadAction,
+  formatPreservedScrollback,
+  scrollTargetAfterWrite,
+  rerunCommandData,
+  markedCommand,
+  titleReportReply,
+  MIN_FONT_SIZE,
+  MAX_FONT_SIZE,
+  shellQuote,
+  DEFAULT_FONT_SIZE,
+  DEFAULT_WORD_SEPARATORS,
+  MAX_SCROLLBACK_LINES,
+  clampScrollbackLines,
+  clampTabWidth,
+  DEFAULT_TAB_WIDTH,
+  MAX_TAB_WIDTH,
+} from "./terminal";
+
+describe("buildTerminalOptions", () => {
+  it("should fall back to defaults for an empty config", () => {
+    const options = buildTerminalOptions({});
+    expect(options.fontSize).toBe(DEFAULT_FONT_SIZE);
+    expect(options.scrollback).toBe(10000);
+  });
+
+  it("should allow block selection with Option-drag on macOS", () => {
+    expect(buildTerminalOptions({}).macOptionClickForcesSelection).toBe(true);
+  });
+
+  it("should draw bold text in bright colors with a bold weight by default", () => {
+    const options = buildTerminalOptions({});
+    expect(options.drawBoldTextInBrightColors).toBe(true);
+    expect(options.fontWeightBold).toBe("bold");
+  });
+
+  it("should keep normal ANSI colors for bold text when bold_is_bright is off", () => {
+    const options = buildTerminalOptions({ bold_is_bright: false });
+    expect(options.drawBoldTextInBrightColors).toBe(false);
+    expect(options.fontWeightBold).toBe("bold");
+  });
+
+  it("should allow brightness-only bold", () => {
+    const options = buildTerminalOptions({ bold_is_bright: true, bold_font_weight: false });
+    expect(options.drawBoldTextInBrightColors).toBe(true);
+    expect(options.fontWeightBold).toBe("normal");
+  });
+
+  it("should use the configured word separators for double-click selection", () => {
+    expect(buildTerminalOptions({}).wordSeparator).toBe(DEFAULT_WORD_SEPARATORS);
+    expect(buildTerminalOptions({ word_separators: " :=" }).wordSeparator).toBe(" :=");
+  });
+});
+
+describe("clampTabWidth", () => {
+  it("should default to 8 columns", () => {
+    expect(clampTabWidth(undefined)).toBe(DEFAULT_TAB_WIDTH);
+    expect(clampTabWidth(NaN)).toBe(DEFAULT_TAB_WIDTH);
+    expect(buildTerminalOptions({}).tabStopWidth).toBe(8);
+  });
+
+  it("should keep the width within 1 and the limit", () => {
+    expect(clampTabWidth(4)).toBe(4);
+    expect(clampTabWidth(0)).toBe(1);
+    expect(clampTabWidth(1000)).toBe(MAX_TAB_WIDTH);
+  });
+});
+
+describe("tab stops", () => {
+  /** dataを書き込んだ1行目のテキスト */
+  const renderLine = (options: ITerminalOptions, data: string) =>
+    new Promise<string>((resolve) => {
+      const terminal = new XTerm({ ...options, cols: 40, rows: 2 });
+      terminal.write(data, () => {
+        resolve(terminal.buffer.active.getLine(0)?.translateToString(true) ?? "");
+        terminal.dispose();
+      });
+    });
+
+  it("should align tabs to every 8 columns by default", async () => {
+    expect(await renderLine(buildTerminalOptions({}), "a\tbc\tdef\tg")).toBe(
+      "a       bc      def     g"
+    );
+  });
+
+  it("should align tabs to terminal.tab_width", async () => {
+    expect(await renderLine(buildTerminalOptions({ tab_width: 4 }), "a\tbc\tdef\tg")).toBe(
+      "a   bc  def g"
+    );
+  });
+});
+
+describe("clampScrollbackLines", () => {
+  it("should default to 10000 lines", () => {
+    expect(clampScrollbackLines(undefined)).toBe(10000);
+  });
+
+  it("should pass through values within the limit", () => {
+    expect(clampScrollbackLines(50000)).toBe(50000);
+    expect(buildTerminalOptions({ scrollback_lines: 50000 }).scrollback).toBe(50000);
+  });
+
+  it("should clamp to the maximum and to zero", () => {
+    expect(clampScrollbackLines(10_000_000)).toBe(MAX_SCROLLBACK_LINES);
+    expect(clampScrollbackLines(-1)).toBe(0);
+  });
+});
+
+describe("selectColorScheme", () => {
+  const light = { background: "#ffffff" };
+  const dark = { background: "#000000" };
+  const common = { background: "#1e1e1e" };
+
+  it("should select the scheme for the system theme", () => {
+    const config = { color_scheme_light: light, color_scheme_dark: dark, color_scheme: common };
+    expect(selectColorScheme(config, "light")).toBe(light);
+    expect(selectColorScheme(config, "dark")).toBe(dark);
+  });
+
+  it("should fall back to color_scheme when the mode has no theme file", () => {
+    const config = { color_scheme_dark: dark, color_scheme: common };
+    expect(selectColorScheme(config, "light")).toBe(common);
+    expect(selectColorScheme(config, "dark")).toBe(dark);
+  });
+
+  it("should return undefined without any scheme", () => {
+    expect(selectColorScheme({}, "dark")).toBeUndefined();
+  });
+});
+
+describe("applyCursorColors", () => {
+  const theme = { background: "#1e1e1e", cursor: "#d4d4d4", cursorAccent: "#1e1e1e" };
+
+  it("should prefer the config over the theme", () => {
+    const result = applyCursorColors(theme, {
+      cursor_color: "#ffcc00",
+      cursor_text_color: "#000000",
+    });
+    expect(result.cursor).toBe("#ffcc00");
+    expect(result.cursorAccent).toBe("#000000");
+    expect(result.background).toBe("#1e1e1e");
+  });
+
+  it("should keep the theme colors when not configured", () => {
+    const result = applyCursorColors(theme, { cursor_color: "#ffcc00" });
+    expect(result.cursor).toBe("#ffcc00");
+    expect(result.cursorAccent).toBe("#1e1e1e");
+  });
+
+  it("should keep an invisible cursor color unless smart_cursor_contrast is enabled", () => {
+    expect(applyCursorColors(theme, { cursor_color: "#202020" }).cursor).toBe("#202020");
+  });
+
+  it("should fix cursor colors that blend into the background with smart_cursor_contrast", () => {
+    const result = applyCursorColors(theme, {
+      cursor_color: "#202020",
+      smart_cursor_contrast: true,
+    });
+    expect(result.cursor).toBe("#ffffff");
+    // カーソル上の文字（背景色）は白いカーソルと見分けられる
+    expect(result.cursorAccent).toBe("#1e1e1e");
+  });
+
+  it("should fix the character under the cursor with smart_cursor_contrast", () => {
+    const result = applyCursorColors(theme, {
+      cursor_text_color: "#e0e0e0",
+      smart_cursor_contrast: true,
+    });
+    expect(result.cursor).toBe("#d4d4d4");
+    expect(result.cursorAccent).toBe("#000000");
+  });
+});
+
+describe("ensureContrast", () => {
+  it("should keep colors that differ enough", () => {
+    expect(ensureContrast("#1e1e1e", "#d4d4d4")).toBe("#d4d4d4");
+    expect(ensureContrast("#ffffff", "#0000cc")).toBe("#0000cc");
+  });
+
+  it("should pick black or white for similar colors", () => {
+    expect(ensureContrast("#1e1e1e", "#2a2a2a")).toBe("#ffffff");
+    expect(ensureContrast("#ffffff", "#eeeeee")).toBe("#000000");
+    expect(ensureContrast("#fdf6e3", "#eee8d5")).toBe("#000000");
+  });
+
+  it("should leave colors it cannot parse unchanged", () => {
+    expect(ensureContrast("#1e1e1e", "red")).toBe("red");
+  });
+});
+
+describe("parseHexColor", () => {
+  it("should parse long and short hex colors", () => {
+    expect(parseHexColor("#ff8000")).toEqual([255, 128, 0]);
+    expect(parseHexColor("#0f0")).toEqual([0, 255, 0]);
+    expect(parseHexColor("rgb(0, 0, 0)")).toBeNull();
+  });
+
+  it("should fall back to the xterm.js default without theme colors", () => {
+    const result = applyCursorColors({ background: "#000000" }, {});
+    expect(result.cursor).toBeUndefined();
+    expect(result.cursorAccent).toBeUndefined();
+  });
+});
+
+describe("buildContainerStyle", () => {
+  it("should have no padding by default", () => {
+    expect(buildContainerStyle({}, "#1e1e1e")).toEqual({ padding: 0, backgroundColor: "#1e1e1e" });
+  });
+
+  it("should put the configured padding on the outer frame with the terminal background", () => {
+    const style = buildContainerStyle({ padding: 8 }, "#000000");
+    expect(style.padding).toBe(8);
+    expect(style.backgroundColor).toBe("#000000");
+  });
+
+  it("should make the background translucent with background_opacity", () => {
+    const style = buildContainerStyle({ background_opacity: 0.8 }, "#1e1e1e");
+    expect(style.backgroundColor).toBe("rgba(30, 30, 30, 0.8)");
+    expect(style.backgroundImage).toBeUndefined();
+  });
+
+  it("should overlay the theme background on the image", () => {
+    const style = buildContainerStyle({ background_opacity: 0.6 }, "#000", "blob:bg");
+    expect(style.backgroundColor).toBe("#000");
+    expect(style.backgroundImage).toBe(
+      'linear-gradient(rgba(0, 0, 0, 0.6), rgba(0, 0, 0, 0.6)), url("blob:bg")'
+    );
+    expect(style.backgroundSize).toBe("cover");
+  });
+
+  it("should keep the image covered by an opaque background by default", () => {
+    const style = buildContainerStyle({}, "#ffffff", "blob:bg");
+    expect(style.backgroundImage).toContain("rgba(255, 255, 255, 1)");
+  });
+});
+
+describe("clampBackgroundOpacity", () => {
+  it("should be opaque when not set", () => {
+    expect(clampBackgroundOpacity(undefined)).toBe(1);
+    expect(clampBackgroundOpacity(NaN)).toBe(1);
+  });
+
+  it("should clamp to the readable range", () => {
+    expect(clampBackgroundOpacity(0)).toBe(MIN_BACKGROUND_OPACITY);
+    expect(clampBackgroundOpacity(0.75)).toBe(0.75);
+    expect(clampBackgroundOpacity(2)).toBe(1);
+  });
+});
+
+describe("hasTranslucentBackground", () => {
+  it("should be false for the default config", () => {
+    expect(hasTranslucentBackground({})).toBe(false);
+    expect(hasTranslucentBackground({ background_opacity: 1 })).toBe(false);
+  });
+
+  it("should be true with an image or a lower opacity", () => {
+    expect(hasTranslucentBackground({ background_image: "/bg.png" })).toBe(true);
+    expect(hasTranslucentBackground({ background_opacity: 0.9 })).toBe(true);
+  });
+});
+
+describe("withAlpha", () => {
+  it("should convert hex colors to rgba", () => {
+    expect(withAlpha("#ff8000", 0.5)).toBe("rgba(255, 128, 0, 0.5)");
+    expect(withAlpha("#FFF", 1)).toBe("rgba(255, 255, 255, 1)");
+  });
+
+  it("should leave other color formats unchanged", () => {
+    expect(withAlpha("rgb(1, 2, 3)", 0.5)).toBe("rgb(1, 2, 3)");
+  });
+});
+
+describe("outputScrollTarget", () => {
+  it("should leave following the output to xterm.js by default", () => {
+    expect(outputScrollTarget(undefined, 120, 120)).toBeNull();
+    expect(outputScrollTarget(undefined, 80, 120)).toBeNull();
+    expect(outputScrollTarget("if_at_bottom", 80, 120)).toBeNull();
+  });
+
+  it("should jump to the bottom from the scrollback when set to always", () => {
+    expect(outputScrollTarget("always", 80, 120)).toBe("bottom");
+    expect(outputScrollTarget("always", 120, 120)).toBeNull();
+  });
+
+  it("should keep the viewport at the bottom line when set to never", () => {
+    expect(outputScrollTarget("never", 120, 120)).toBe(120);
+    expect(outputScrollTarget("never", 0, 0)).toBe(0);
+    // スクロールバックを見ている間はxterm.jsも動かさない
+    expect(outputScrollTarget("never", 80, 120)).toBeNull();
+  });
+});
+
+describe("scrollTargetAfterWrite", () => {
+  it("should apply the target while the same buffer is shown", () => {
+    expect(scrollTargetAfterWrite(120, "normal", "normal")).toBe(120);
+    expect(scrollTargetAfterWrite("bottom", "alternate", "alternate")).toBe("bottom");
+  });
+
+  it("should not move the primary scrollback when leaving the alternate screen", () => {
+    // 代替スクリーンでの位置（0行目）を通常のバッファに適用しない
+    expect(scrollTargetAfterWrite(0, "alternate", "normal")).toBeNull();
+    expect(scrollTargetAfterWrite(120, "normal", "alternate")).toBeNull();
+  });
+
+  it("should keep the primary scrollback of a real terminal across vim", async () => {
+    const terminal = new XTerm({ cols: 20, rows: 5, scrollback: 100 });
+    // Terminal.tsxと同じく、書き込む前の位置からscroll_on_output = "never"のスクロール先を決める
+    const write = (data: string) =>
+      new Promise<void>((resolve) => {
+        const buffer = terminal.buffer.active;
+        const target = outputScrollTarget("never", buffer.viewportY, buffer.baseY);
+        const bufferType = buffer.type;
+        terminal.write(data, () => {
+          const next = scrollTargetAfterWrite(target, bufferType, terminal.buffer.active.type);
+          if (next === "bottom") terminal.scrollToBottom();
+          else if (next !== null) terminal.scrollToLine(next);
+          resolve();
+        });
+      });
+
+    await write(Array.from({ length: 20 }, (_, i) => `line ${i}`).join("\r\n"));
+    const { baseY } = terminal.buffer.normal;
+    expect(baseY).toBeGreaterThan(0);
+
+    await write("\x1b[?1049h\x1b[Hvim screen");
+    expect(terminal.buffer.active.type).toBe("alternate");
+    await write("\x1b[?1049l");
+
+    const normal = terminal.buffer.active;
+    expect(normal.type).toBe("normal");
+    expect(normal.viewportY).toBe(baseY);
+    expect(normal.getLine(0)?.translateToString(true)).toBe("line 0");
+    expect(normal.getLine(19)?.translateToString(true)).toBe("line 19");
+    terminal.dispose();
+  });
+});
+
+describe("isScrolledUp", () => {
+  it("should be false when the viewport is at the bottom", () => {
+    expect(isScrolledUp(120, 120)).toBe(false);
+  });
+
+  it("should be false when there is no scrollback", () => {
+    expect(isScrolledUp(0, 0)).toBe(false);
+  });
+
+  it("should be true when the viewport is above the bottom", () => {
+    expect(isScrolledUp(80, 120)).toBe(true);
+  });
+});
+
+describe("shellQuote", () => {
+  it("should wrap paths with spaces in single quotes", () => {
+    expect(shellQuote("/docs/my guide.rst")).toBe("'/docs/my guide.rst'");
+  });
+
+  it("should escape embedded single quotes", () => {
+    expect(shellQuote("it's.rst")).toBe("'it'\\''s.rst'");
+  });
+});
+
+describe("buildEditorCommand", () => {
+  it("should run the editor on the quoted path and press enter", () => {
+    expect(buildEditorCommand("nvim", "/docs/index.rst")).toBe("nvim '/docs/index.rst'\r");
+  });
+
+  it("should jump to the line when one is given", () => {
+    expect(buildEditorCommand("nvim", "/docs/index.rst", 12)).toBe("nvim +12 '/docs/index.rst'\r");
+  });
+
+  it("should use editor.line_format for the line argument", () => {
+    expect(buildEditorCommand("code", "/docs/index.rst", 12, "-g {file}:{line}")).toBe(
+      "code -g '/docs/index.rst':12\r"
+    );
+    // {file}が無ければ最後にファイルを付ける
+    expect(buildEditorCommand("micro", "/docs/index.rst", 12, "+{line}")).toBe(
+      "micro +12 '/docs/index.rst'\r"
+    );
+  });
+
+  it("should not add the line when editor.line_format is empty", () => {
+    expect(buildEditorCommand("gedit", "/docs/index.rst", 12, "")).toBe(
+      "gedit '/docs/index.rst'\r"
+    );
+  });
+});
+
+describe("dispatchExitAction", () => {
+  const createHandlers = () => ({ idle: vi.fn(), respawn: vi.fn(), close: vi.fn() });
+
+  it("should go idle when on_exit is not set", () => {
+    const handlers = createHandlers();
+    dispatchExitAction(undefined, handlers);
+    expect(handlers.idle).toHaveBeenCalledOnce();
+    expect(handlers.respawn).not.toHaveBeenCalled();
+    expect(handlers.close).not.toHaveBeenCalled();
+  });
+
+  it.each(["idle", "respawn", "close"] as const)("should run only the %s handler", (action) => {
+    const handlers = createHandlers();
+    dispatchExitAction(action, handlers);
+    for (const [name, handler] of Object.entries(handlers)) {
+      expect(handler).toHaveBeenCalledTimes(name === action ? 1 : 0);
+    }
+  });
+});
+
+describe("fontSizeShortcut", () => {
+  const key = (key: string, modifiers: Partial<{ metaKey: boolean; ctrlKey: boolean }>) => ({
+    key,
+    metaKey: false,
+    ctrlKey: false,
+    altKey: false,
+    ...modifiers,
+  });
+
+  it("should use Cmd on macOS", () => {
+    expect(fontSizeShortcut(key("=", { metaKey: true }), true)).toBe("increase");
+    expect(fontSizeShortcut(key("+", { metaKey: true }), true)).toBe("increase");
+    expect(fontSizeShortcut(key("-", { metaKey: true }), true)).toBe("decrease");
+    expect(fontSizeShortcut(key("0", { metaKey: true }), true)).toBe("reset");
+    expect(fontSizeShortcut(key("-", { ctrlKey: true }), true)).toBeNull();
+  });
+
+  it("should use Ctrl on other platforms", () => {
+    expect(fontSizeShortcut(key("=", { ctrlKey: true }), false)).toBe("increase");
+    expect(fontSizeShortcut(key("-", { metaKey: true }), false)).toBeNull();
+  });
+
+  it("should ignore other keys", () => {
+    expect(fontSizeShortcut(key("c", { metaKey: true }), true)).toBeNull();
+    expect(fontSizeShortcut(key("0", {}), true)).toBeNull();
+  });
+});
+
+describe("isAnsiCopyShortcut", () => {
+  const key = (
+    code: string,
+    modifiers: Partial<{ metaKey: boolean; ctrlKey: boolean; altKey: boolean }>
+  ) => ({
+    key: "",
+    code,
+    metaKey: false,
+    ctrlKey: false,
+    altKey: false,
+    ...modifiers,
+  });
+
+  it("should match Mod + Alt + C by physical key", () => {
+    expect(isAnsiCopyShortcut(key("KeyC", { metaKey: true, altKey: true }), true)).toBe(true);
+    expect(isAnsiCopyShortcut(key("KeyC", { ctrlKey: true, altKey: true }), false)).toBe(true);
+  });
+
+  it("should not match without Alt or with another key", () => {
+    expect(isAnsiCopyShortcut(key("KeyC", { metaKey: true }), true)).toBe(false);
+    expect(isAnsiCopyShortcut(key("KeyV", { metaKey: true, altKey: true }), true)).toBe(false);
+    expect(isAnsiCopyShortcut(key("KeyC", { ctrlKey: true, altKey: true }), true)).toBe(false);
+  });
+});
+
+describe("isEnterCopy", () => {
+  const key = (
+    name: string,
+    modifiers: Partial<{
+      metaKey: boolean;
+      ctrlKey: boolean;
+      altKey: boolean;
+      shiftKey: boolean;
+    }> = {}
+  ) => ({
+    key: name,
+    metaKey: false,
+    ctrlKey: false,
+    altKey: false,
+    shiftKey: false,
+    ...modifiers,
+  });
+
+  it("should copy on Enter while text is selected", () => {
+    expect(isEnterCopy(key("Enter"), true, true)).toBe(true);
+  });
+
+  it("should send Enter to the shell when disabled or nothing is selected", () => {
+    expect(isEnterCopy(key("Enter"), false, true)).toBe(false);
+    expect(isEnterCopy(key("Enter"), true, false)).toBe(false);
+  });
+
+  it("should not copy on other keys or Enter with modifiers", () => {
+    expect(isEnterCopy(key("a"), true, true)).toBe(false);
+    expect(isEnterCopy(key("Enter", { shiftKey: true }), true, true)).toBe(false);
+    expect(isEnterCopy(key("Enter", { ctrlKey: true }), true, true)).toBe(false);
+  });
+});
+
+describe("isPasteShortcut", () => {
+  const key = (
+    code: string,
+    modifiers: Partial<{ metaKey: boolean; ctrlKey: boolean; altKey: boolean; shiftKey: boolean }>
+  ) => ({
+    key: "",
+    code,
+    metaKey: false,
+    ctrlKey: false,
+    altKey: false,
+    shiftKey: false,
+    ...modifiers,
+  });
+
+  it("should match Cmd + V on macOS", () => {
+    expect(isPasteShortcut(key("KeyV", { metaKey: true }), true)).toBe(true);
+    expect(isPasteShortcut(key("KeyV", { ctrlKey: true }), true)).toBe(false);
+  });
+
+  it("should match Ctrl + Shift + V elsewhere and leave Ctrl + V to the shell", () => {
+    expect(isPasteShortcut(key("KeyV", { ctrlKey: true, shiftKey: true }), false)).toBe(true);
+    expect(isPasteShortcut(key("KeyV", { ctrlKey: true }), false)).toBe(false);
+    expect(isPasteShortcut(key("KeyC", { ctrlKey: true, shiftKey: true }), false)).toBe(false);
+  });
+});
+
+describe("promptJumpShortcut", () => {
+  const key = (
+    key: string,
+    modifiers: Partial<{ metaKey: boolean; ctrlKey: boolean; shiftKey: boolean }>
+  ) => ({ key, metaKey: false, ctrlKey: false, altKey: false, shiftKey: false, ...modifiers });
+
+  it("should match Mod + Shift + arrow keys", () => {
+    expect(promptJumpShortcut(key("ArrowUp", { metaKey: true, shiftKey: true }), true)).toBe(
+      "previous"
+    );
+    expect(promptJumpShortcut(key("ArrowDown", { ctrlKey: true, shiftKey: true }), false)).toBe(
+      "next"
+    );
+  });
+
+  it("should not match without Shift", () => {
+    expect(promptJumpShortcut(key("ArrowUp", { metaKey: true }), true)).toBeNull();
+  });
+});
+
+describe("isProjectSwitcherShortcut", () => {
+  const key = (code: string, modifiers: Partial<{ metaKey: boolean; ctrlKey: boolean }>) => ({
+    key: "",
+    code,
+    metaKey: false,
+    ctrlKey: false,
+    altKey: false,
+    shiftKey: true,
+    ...modifiers,
+  });
+
+  it("should match Mod + Shift + P", () => {
+    expect(isProjectSwitcherShortcut(key("KeyP", { metaKey: true }), true)).toBe(true);
+    expect(isProjectSwitcherShortcut(key("KeyP", { ctrlKey: true }), false)).toBe(true);
+    expect(isProjectSwitcherShortcut(key("KeyP", { ctrlKey: true }), true)).toBe(false);
+  });
+
+  it("should leave Ctrl + P to the shell", () => {
+    expect(
+      isProjectSwitcherShortcut({ ...key("KeyP", { ctrlKey: true }), shiftKey: false }, false)
+    ).toBe(false);
+  });
+});
+
+describe("stepFontSize", () => {
+  it("should step by one point", () => {
+    expect(stepFontSize(14, "increase", 14)).toBe(15);
+    expect(stepFontSize(14, "decrease", 14)).toBe(13);
+  });
+
+  it("should clamp to the allowed range", () => {
+    expect(stepFontSize(MAX_FONT_SIZE, "increase", 14)).toBe(MAX_FONT_SIZE);
+    expect(stepFontSize(MIN_FONT_SIZE, "decrease", 14)).toBe(MIN_FONT_SIZE);
+  });
+
+  it("should reset to the configured size", () => {
+    expect(stepFontSize(20, "reset", 16)).toBe(16);
+  });
+});
+
+describe("createResizeGate", () => {
+  it("should hold resizes until the PTY is spawned", () => {
+    const apply = vi.fn();
+    const gate = createResizeGate(apply);
+    gate.request({ cols: 100, rows: 30 });
+    gate.request({ cols: 120, rows: 40 });
+    expect(apply).not.toHaveBeenCalled();
+
+    gate.open({ cols: 80, rows: 24 });
+    expect(apply).toHaveBeenCalledTimes(1);
+    expect(apply).toHaveBeenCalledWith({ cols: 120, rows: 40 });
+  });
+
+  it("should skip the pending size if it matches the spawned size", () => {
+    const apply = vi.fn();
+    const gate = createResizeGate(apply);
+    gate.request({ cols: 80, rows: 24 });
+    gate.open({ cols: 80, rows: 24 });
+    expect(apply).not.toHaveBeenCalled();
+  });
+
+  it("should apply resizes immediately once open", () => {
+    const apply = vi.fn();
+    const gate = createResizeGate(apply);
+    gate.open({ cols: 80, rows: 24 });
+    gate.request({ cols: 90, rows: 30 });
+    expect(apply).toHaveBeenCalledWith({ cols: 90, rows: 30 });
+  });
+});
+
+describe("clampTerminalSize", () => {
+  const minimum = { cols: 20, rows: 5 };
+
+  it("should pass through sizes above the minimum", () => {
+    expect(clampTerminalSize({ cols: 80, rows: 24 }, minimum)).toEqual({
+      size: { cols: 80, rows: 24 },
+      tooSmall: false,
+    });
+  });
+
+  it("should clamp each dimension to the minimum", () => {
+    expect(clampTerminalSize({ cols: 1, rows: 1 }, minimum)).toEqual({
+      size: { cols: 20, rows: 5 },
+      tooSmall: true,
+    });
+    expect(clampTerminalSize({ cols: 100, rows: 3 }, minimum)).toEqual({
+      size: { cols: 100, rows: 5 },
+      tooSmall: true,
+    });
+  });
+
+  it("should read the minimum from the config", () => {
+    expect(minTerminalSize({})).toEqual({ cols: 20, rows: 5 });
+    expect(minTerminalSize({ min_cols: 40, min_rows: 10 })).toEqual({ cols: 40, rows: 10 });
+  });
+});
+
+describe("parseOsc7Cwd", () => {
+  it("should extract the path from a file URL", () => {
+    expect(parseOsc7Cwd("file://host/home/user/docs")).toBe("/home/user/docs");
+    expect(parseOsc7Cwd("file:///home/user/my%20docs")).toBe("/home/user/my docs");
+  });
+
+  it("should ignore other URLs and malformed data", () => {
+    expect(parseOsc7Cwd("https://example.com/")).toBeNull();
+    expect(parseOsc7Cwd("not a url")).toBeNull();
+  });
+});
+
+describe("duplicateCwd", () => {
+  it("should use the directory reported by the shell", () => {
+    expect(duplicateCwd("/project/docs/api", "/project")).toBe("/project/docs/api");
+  });
+
+  it("should fall back to the project path when the cwd is unknown", () => {
+    expect(duplicateCwd(null, "/project")).toBe("/project");
+    expect(duplicateCwd(undefined, "/project")).toBe("/project");
+  });
+});
+
+describe("terminalReloadAction", () => {
+  it("should recreate the terminal unless preserve_scrollback_on_reload is set", () => {
+    expect(terminalReloadAction(undefined, "/project", "/project")).toBe("recreate");
+    expect(terminalReloadAction(false, "/project", "/project")).toBe("recreate");
+  });
+
+  it("should reuse the session when the shell is already in the project", () => {
+    expect(terminalReloadAction(true, "/project", "/project")).toBe("reuse");
+    expect(terminalReloadAction(true, "/project/", "/project")).toBe("reuse");
+  });
+
+  it("should reprint the scrollback when the directory changes", () => {
+    expect(terminalReloadAction(true, "/old", "/project")).toBe("reprint");
+    expect(terminalReloadAction(true, "/project/docs", "/project")).toBe("reprint");
+    expect(terminalReloadAction(true, undefined, "/project")).toBe("reprint");
+  });
+});
+
+describe("formatPreservedScrollback", () => {
+  it("should wrap the previous output in a dimmed banner", () => {
+    expect(formatPreservedScrollback("$ make html  \nbuild succeeded.\n\n\n")).toBe(
+      "\x1b[2m--- Previous session ---\x1b[0m\r\n" +
+        "$ make html\r\n" +
+        "build succeeded.\r\n" +
+        "\x1b[2m--- End of previous session ---\x1b[0m\r\n"
+    );
+  });
+
+  it("should print nothing for an empty scrollback", () => {
+    expect(formatPreservedScrollback("  \n\n")).toBe("");
+  });
+});
+
+describe("titleReportReply", () => {
+  it("should report the tracked title for CSI 21 t", () => {
+    expect(titleReportReply([21], "nvim docs/index.rst")).toBe("\x1b]lnvim docs/index.rst\x1b\\");
+  });
+
+  it("should report an empty title when none has been set", () => {
+    expect(titleReportReply([21], "")).toBe("\x1b]l\x1b\\");
+  });
+
+  it("should strip control characters so the reply cannot inject input", () => {
+    expect(titleReportReply([21], "evil\x1b\\\rrm -rf ~\n")).toBe("\x1b]levil\\rm -rf ~\x1b\\");
+  });
+
+  it("should ignore other window operations", () => {
+    expect(titleReportReply([22, 0], "nvim")).toBeNull();
+    expect(titleReportReply([18], "nvim")).toBeNull();
+  });
+});
+
+describe("rerunCommandData", () => {
+  it("should send the command read from the semantic marks", () => {
+    expect(rerunCommandData("make html")).toBe("make html\r");
+  });
+
+  it("should fall back to history up and Enter without a known command", () => {
+    expect(rerunCommandData(null)).toBe("\x1b[A\r");
+    expect(rerunCommandData(null, { applicationCursor: true })).toBe("\x1bOA\r");
+  });
+
+  it("should replace half-typed input with the history entry", () => {
+    expect(rerunCommandData("make html", { pendingInput: true })).toBe("\x1b[A\r");
+    const options = { pendingInput: true, historyFallback: false };
+    expect(rerunCommandData("make html", options)).toBeNull();
+  });
+
+  it("should send nothing when the fallback is disabled", () => {
+    expect(rerunCommandData(null, { historyFallback: false })).toBeNull();
+    expect(rerunCommandData("make html", { historyFallback: false })).toBe("make html\r");
+  });
+});
+
+describe("markedCommand", () => {
+  it("should trim the command typed after the prompt", () => {
+    expect(markedCommand("make html   \n")).toBe("make html");
+  });
+
+  it("should not use a multi-line command with continuation prompts", () => {
+    expect(markedCommand("for f in *.rst; do\n> echo $f\n> done")).toBeNull();
+  });
+});
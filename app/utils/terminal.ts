@@ -0,0 +1,518 @@
+import type { CSSProperties } from "react";
+import type { ITerminalOptions, ITheme } from "@xterm/xterm";
+import type {
+  ColorScheme,
+  ScrollOnOutput,
+  TerminalConfig,
+  TerminalExitAction,
+} from "../types/config";
+
+// デフォルトフォント設定
+export const DEFAULT_FONT_FAMILY = 'Menlo, Monaco, "Courier New", monospace';
+export const DEFAULT_FONT_SIZE = 14;
+// スクロールバックの行数（上限はメモリ使用量を抑えるため）
+export const DEFAULT_SCROLLBACK_LINES = 10000;
+export const MAX_SCROLLBACK_LINES = 100000;
+// ダブルクリックで単語を選択する時の区切り文字（xterm.jsのデフォルトと同じ）
+export const DEFAULT_WORD_SEPARATORS = " ()[]{}',\"`";
+// タブストップの間隔（一般的な端末と同じ8桁ごと）
+export const DEFAULT_TAB_WIDTH = 8;
+export const MAX_TAB_WIDTH = 32;
+// 文字が読めなくなるほど透けさせない
+export const MIN_BACKGROUND_OPACITY = 0.5;
+
+// カーソルと下地の相対輝度の差がこれ未満なら見分けにくいとみなす
+export const MIN_CURSOR_LUMINANCE_DELTA = 0.3;
+
+// ショートカットで変更できるフォントサイズの範囲
+export const MIN_FONT_SIZE = 6;
+export const MAX_FONT_SIZE = 72;
+
+/** ターミナル設定をxterm.jsのオプションに変換（テーマは別途解決） */
+export function buildTerminalOptions(config: TerminalConfig): ITerminalOptions {
+  return {
+    cursorBlink: true,
+    fontSize: config.font_size ?? DEFAULT_FONT_SIZE,
+    fontFamily: config.font_family ?? DEFAULT_FONT_FAMILY,
+    scrollback: clampScrollbackLines(config.scrollback_lines),
+    // タブ（\t）はxterm.jsがタブストップまでカーソルを進めて揃える
+    tabStopWidth: clampTabWidth(config.tab_width),
+    // "bold is bright": 太字の通常ANSI色(0-7)を明るい色(8-15)で描画
+    drawBoldTextInBrightColors: config.bold_is_bright ?? true,
+    fontWeightBold: (config.bold_font_weight ?? true) ? "bold" : "normal",
+    wordSeparator: config.word_separators ?? DEFAULT_WORD_SEPARATORS,
+    // 背景を透過させるときはセルの既定背景を描かない（色付きのセルだけ背景を描く）
+    allowTransparency: hasTranslucentBackground(config),
+    // Alt（macOSではOption）を押しながらのドラッグで矩形選択する（macOSはこの指定が必要）
+    macOptionClickForcesSelection: true,
+  };
+}
+
+/** terminal.scrollback_lines を0〜上限の範囲に収める（未指定はデフォルト） */
+export function clampScrollbackLines(lines: number | undefined): number {
+  if (lines === undefined || !Number.isFinite(lines)) return DEFAULT_SCROLLBACK_LINES;
+  return Math.min(Math.max(Math.floor(lines), 0), MAX_SCROLLBACK_LINES);
+}
+
+/** terminal.tab_width を1〜上限の範囲に収める（未指定はデフォルト） */
+export function clampTabWidth(width: number | undefined): number {
+  if (width === undefined || !Number.isFinite(width)) return DEFAULT_TAB_WIDTH;
+  return Math.min(Math.max(Math.floor(width), 1), MAX_TAB_WIDTH);
+}
+
+/**
+ * OSのLight/Darkテーマに合わせてカラースキームを選ぶ
+ * theme_file_light / theme_file_dark（color_scheme_light / dark）を優先し、無ければcolor_scheme
+ */
+export function selectColorScheme(
+  config: TerminalConfig,
+  systemTheme: "light" | "dark"
+): ColorScheme | undefined {
+  const variant = systemTheme === "dark" ? config.color_scheme_dark : config.color_scheme_light;
+  return variant ?? config.color_scheme;
+}
+
+/**
+ * terminal.cursor_color / cursor_text_color でテーマのカーソル色を上書き
+ * 優先順位は 設定 > テーマ > xterm.jsのデフォルト
+ * smart_cursor_contrast が有効なら、見分けにくい組み合わせを黒か白に補正する
+ */
+export function applyCursorColors(theme: ITheme, config: TerminalConfig): ITheme {
+  const cursor = config.cursor_color ?? theme.cursor;
+  const cursorAccent = config.cursor_text_color ?? theme.cursorAccent;
+  if (!config.smart_cursor_contrast) return { ...theme, cursor, cursorAccent };
+
+  // 未指定の色はxterm.jsのデフォルト（背景は黒、カーソルは白、カーソル上の文字は背景色）
+  const background = theme.background ?? "#000000";
+  const safeCursor = ensureContrast(background, cursor ?? "#ffffff");
+  return {
+    ...theme,
+    cursor: safeCursor,
+    cursorAccent: ensureContrast(safeCursor, cursorAccent ?? background),
+  };
+}
+
+/** #rrggbb（#rgb）を[r, g, b]にする（解釈できない色はnull） */
+export function parseHexColor(color: string): [number, number, number] | null {
+  const hex = /^#([0-9a-f]{3}|[0-9a-f]{6})$/i.exec(color)?.[1];
+  if (!hex) return null;
+  const full = hex.length === 3 ? [...hex].map((c) => c + c).join("") : hex;
+  const [r, g, b] = [0, 2, 4].map((i) => parseInt(full.slice(i, i + 2), 16));
+  return [r, g, b];
+}
+
+/** WCAGの相対輝度（0: 黒 〜 1: 白） */
+export function relativeLuminance([r, g, b]: [number, number, number]): number {
+  const linear = (channel: number) => {
+    const c = channel / 255;
+    return c <= 0.03928 ? c / 12.92 : ((c + 0.055) / 1.055) ** 2.4;
+  };
+  return 0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b);
+}
+
+/**
+ * desiredがbaseと見分けにくければ、baseに対して目立つ黒か白を返す
+ * どちらかが解釈できない色ならdesiredをそのまま返す
+ */
+export function ensureContrast(base: string, desired: string): string {
+  const baseRgb = parseHexColor(base);
+  const desiredRgb = parseHexColor(desired);
+  if (!baseRgb || !desiredRgb) return desired;
+
+  const baseLuminance = relativeLuminance(baseRgb);
+  if (Math.abs(baseLuminance - relativeLuminance(desiredRgb)) >= MIN_CURSOR_LUMINANCE_DELTA) {
+    return desired;
+  }
+  return baseLuminance > 0.5 ? "#000000" : "#ffffff";
+}
+
+/**
+ * ターミナル外枠のスタイル
+ * 余白はxterm.jsを開く要素（FitAddonが寸法を測る親要素）ではなく外枠に付ける。
+ * これにより行列数の計算やマウス座標・カーソル位置が余白とずれない
+ */
+export function buildContainerStyle(
+  config: TerminalConfig,
+  background: string,
+  imageUrl?: string
+): CSSProperties {
+  const style: CSSProperties = {
+    padding: config.padding ?? 0,
+    backgroundColor: background,
+  };
+  const opacity = clampBackgroundOpacity(config.background_opacity);
+  if (imageUrl) {
+    // テーマの背景色を不透明度付きで画像の上に重ねる
+    const overlay = withAlpha(background, opacity);
+    style.backgroundImage = `linear-gradient(${overlay}, ${overlay}), url("${imageUrl}")`;
+    style.backgroundSize = "cover";
+    style.backgroundPosition = "center";
+  } else if (opacity < 1) {
+    style.backgroundColor = withAlpha(background, opacity);
+  }
+  return style;
+}
+
+/** terminal.background_opacity を下限〜1の範囲に収める（未指定は不透明） */
+export function clampBackgroundOpacity(opacity: number | undefined): number {
+  if (opacity === undefined || !Number.isFinite(opacity)) return 1;
+  return Math.min(Math.max(opacity, MIN_BACKGROUND_OPACITY), 1);
+}
+
+/** 背景画像か1未満の不透明度が設定されているか */
+export function hasTranslucentBackground(config: TerminalConfig): boolean {
+  return !!config.background_image || clampBackgroundOpacity(config.background_opacity) < 1;
+}
+
+/** #rrggbb（#rgb）に不透明度を付けたrgba()にする（解釈できない色はそのまま返す） */
+export function withAlpha(color: string, alpha: number): string {
+  const rgb = parseHexColor(color);
+  if (!rgb) return color;
+  const [r, g, b] = rgb;
+  return `rgba(${r}, ${g}, ${b}, ${alpha})`;
+}
+
+/**
+ * ビューポートが最下部より上にスクロールされているか
+ * viewportY: 表示中の先頭行、baseY: 最下部表示時の先頭行（xterm.jsのバッファ座標）
+ */
+export function isScrolledUp(viewportY: number, baseY: number): boolean {
+  return viewportY < baseY;
+}
+
+/**
+ * 出力を書き込んだ後のスクロール先（terminal.scroll_on_output）
+ * viewportY・baseYは書き込む前の値。"bottom"なら最下部、数値ならその行、nullならxterm.jsに任せる
+ * （xterm.jsは最下部を表示中なら出力に追従し、スクロールバックを見ている間は動かさない）
+ */
+export function outputScrollTarget(
+  mode: ScrollOnOutput | undefined,
+  viewportY: number,
+  baseY: number
+): "bottom" | number | null {
+  switch (mode ?? "if_at_bottom") {
+    case "always":
+      return isScrolledUp(viewportY, baseY) ? "bottom" : null;
+    case "never":
+      return isScrolledUp(viewportY, baseY) ? null : viewportY;
+    case "if_at_bottom":
+      return null;
+  }
+}
+
+/**
+ * 書き込んだ後に適用するスクロール先
+ * 書き込みで代替スクリーン（?1049h/l）が切り替わった場合、書き込む前の位置は別のバッファのものなので使わない
+ * （vimなどを終了した時に、通常のスクロールバックの先頭へ飛ばずに元の位置へ戻る）
+ */
+export function scrollTargetAfterWrite(
+  target: "bottom" | number | null,
+  bufferBefore: "normal" | "alternate",
+  bufferAfter: "normal" | "alternate"
+): "bottom" | number | null {
+  return bufferBefore === bufferAfter ? target : null;
+}
+
+/** POSIXシェル向けに引数をシングルクォートで囲む */
+export function shellQuote(arg: string): string {
+  return `'${arg.replace(/'/g, `'\\''`)}'`;
+}
+
+/** editor.line_format未設定時の行番号付きの引数（vi、Emacs、nanoなど多くのエディタが対応） */
+export const DEFAULT_EDITOR_LINE_FORMAT = "+{line} {file}";
+
+/**
+ * エディタでファイルを開くためにPTYへ送るコマンド行
+ * 行番号を指定するとlineFormat（editor.line_format）の{line}と{file}を置き換えた引数にする。
+ * lineFormatが空なら行番号は付けない（`code`なら "-g {file}:{line}"）
+ */
+export function buildEditorCommand(
+  editor: string,
+  path: string,
+  line?: number,
+  lineFormat = DEFAULT_EDITOR_LINE_FORMAT
+): string {
+  const file = shellQuote(path);
+  if (!line || !lineFormat) return `${editor} ${file}\r`;
+  const args = lineFormat.replace(/\{(line|file)\}/g, (_, key) =>
+    key === "line" ? String(line) : file
+  );
+  return `${editor} ${lineFormat.includes("{file}") ? args : `${args} ${file}`}\r`;
+}
+
+/** シェル終了時の各動作のハンドラ */
+export type ExitActionHandlers = Record<TerminalExitAction, () => void>;
+
+/** terminal.on_exitに応じてシェル終了時の動作を実行（未設定ならidle） */
+export function dispatchExitAction(
+  action: TerminalExitAction | undefined,
+  handlers: ExitActionHandlers
+): void {
+  handlers[action ?? "idle"]();
+}
+
+/** フォントサイズ変更ショートカットの操作 */
+export type FontSizeAction = "increase" | "decrease" | "reset";
+
+interface ShortcutKeyEvent {
+  key: string;
+  metaKey: boolean;
+  ctrlKey: boolean;
+  altKey: boolean;
+  shiftKey?: boolean;
+}
+
+/** ショートカットの修飾キー（macOSはCmd、それ以外はCtrl）だけが押されているか */
+function hasShortcutModifier(event: ShortcutKeyEvent, isMac: boolean): boolean {
+  return isMac ? event.metaKey && !event.ctrlKey : event.ctrlKey && !event.metaKey;
+}
+
+/**
+ * フォントサイズ変更ショートカット（macOSはCmd、それ以外はCtrl + "+" / "-" / "0"）を判定
+ * 他のOSでCmdを見ないのは、Ctrl+- などをシェルに渡すキーと区別するため
+ */
+export function fontSizeShortcut(event: ShortcutKeyEvent, isMac: boolean): FontSizeAction | null {
+  if (!hasShortcutModifier(event, isMac) || event.altKey) return null;
+  switch (event.key) {
+    case "+":
+    case "=":
+      return "increase";
+    case "-":
+      return "decrease";
+    case "0":
+      return "reset";
+    default:
+      return null;
+  }
+}
+
+/**
+ * 色付きコピーのショートカット（Mod + Alt + C）を判定
+ * macOSではAltで入力文字が変わるため、keyではなく物理キーのcodeで判定する
+ */
+export function isAnsiCopyShortcut(
+  event: ShortcutKeyEvent & { code: string },
+  isMac: boolean
+): boolean {
+  return hasShortcutModifier(event, isMac) && event.altKey && event.code === "KeyC";
+}
+
+interface RerunOptions {
+  /** コマンドが分からない時にUp + Enterを送るか（terminal.rerun_history_fallback） */
+  historyFallback?: boolean;
+  /** DECCKMが有効か（有効ならシェルはUpをESC O Aとして受け取る） */
+  applicationCursor?: boolean;
+  /** プロンプトに入力途中の文字があるか（コマンドを送ると後ろに付け足されてしまう） */
+  pendingInput?: boolean;
+}
+
+/**
+ * 直前のコマンドを再実行するためにPTYへ送るデータ（送るものが無ければnull）
+ * セマンティックマーク（OSC 133）で読み取ったコマンドがあればそれを送る。
+ * 無い場合や入力途中の文字がある場合は、Up（入力中の行を履歴の1つ前で置き換える）+ Enterを送る
+ */
+export function rerunCommandData(
+  lastCommand: string | null,
+  { historyFallback = true, applicationCursor = false, pendingInput = false }: RerunOptions = {}
+): string | null {
+  if (lastCommand && !pendingInput) return `${lastCommand}\r`;
+  if (!historyFallback) return null;
+  return applicationCursor ? "\x1bOA\r" : "\x1b[A\r";
+}
+
+/**
+ * セマンティックマーク（OSC 133のB〜C）の間に入力されたコマンド
+ * 複数行のコマンドは継続行のプロンプト（PS2）も含んでしまうのでnull（履歴から再実行する）
+ */
+export function markedCommand(text: string): string | null {
+  const command = text.trim();
+  return command.includes("\n") ? null : command;
+}
+
+/**
+ * 選択中のEnterをコピーとして扱うか（terminal.enter_copies_selection）
+ * 修飾キー付きのEnterはシェルへ送る
+ */
+export function isEnterCopy(
+  event: ShortcutKeyEvent,
+  enabled: boolean,
+  hasSelection: boolean
+): boolean {
+  return (
+    enabled &&
+    hasSelection &&
+    event.key === "Enter" &&
+    !event.metaKey &&
+    !event.ctrlKey &&
+    !event.altKey &&
+    !event.shiftKey
+  );
+}
+
+/**
+ * 貼り付けショートカット（macOSはCmd + V、それ以外はCtrl + Shift + V）を判定
+ * Ctrl + V は他のOSではシェルに渡す（readlineのquoted-insertなど）
+ */
+export function isPasteShortcut(
+  event: ShortcutKeyEvent & { code: string },
+  isMac: boolean
+): boolean {
+  if (!hasShortcutModifier(event, isMac) || event.altKey || event.code !== "KeyV") return false;
+  return isMac ? !event.shiftKey : !!event.shiftKey;
+}
+
+/** 前後のプロンプトへの移動ショートカット（Mod + Shift + ↑ / ↓）を判定 */
+export function promptJumpShortcut(
+  event: ShortcutKeyEvent,
+  isMac: boolean
+): "previous" | "next" | null {
+  if (!hasShortcutModifier(event, isMac) || !event.shiftKey || event.altKey) return null;
+  switch (event.key) {
+    case "ArrowUp":
+      return "previous";
+    case "ArrowDown":
+      return "next";
+    default:
+      return null;
+  }
+}
+
+/** プロジェクト切り替えのショートカット（Mod + Shift + P）を判定 */
+export function isProjectSwitcherShortcut(
+  event: ShortcutKeyEvent & { code: string },
+  isMac: boolean
+): boolean {
+  return (
+    hasShortcutModifier(event, isMac) && !!event.shiftKey && !event.altKey && event.code === "KeyP"
+  );
+}
+
+/** ショートカット操作後のフォントサイズ（範囲内に収める） */
+export function stepFontSize(current: number, action: FontSizeAction, configured: number): number {
+  if (action === "reset") return configured;
+  const next = current + (action === "increase" ? 1 : -1);
+  return Math.min(MAX_FONT_SIZE, Math.max(MIN_FONT_SIZE, next));
+}
+
+/** ターミナルの行列数 */
+export interface TerminalSize {
+  cols: number;
+  rows: number;
+}
+
+/** PTY起動前のリサイズを保留し、起動後にまとめて適用する */
+export interface ResizeGate {
+  /** リサイズを要求（PTY起動前は最新のサイズだけを保持） */
+  request: (size: TerminalSize) => void;
+  /** PTY起動完了。保留中のサイズが起動時のサイズと異なれば即座に適用 */
+  open: (spawnedSize: TerminalSize) => void;
+}
+
+export function createResizeGate(apply: (size: TerminalSize) => void): ResizeGate {
+  let ready = false;
+  let pending: TerminalSize | null = null;
+  return {
+    request: (size) => {
+      if (ready) {
+        apply(size);
+      } else {
+        pending = size;
+      }
+    },
+    open: (spawnedSize) => {
+      ready = true;
+      if (pending && (pending.cols !== spawnedSize.cols || pending.rows !== spawnedSize.rows)) {
+        apply(pending);
+      }
+      pending = null;
+    },
+  };
+}
+
+// PTYに伝える最小サイズのデフォルト
+export const DEFAULT_MIN_COLS = 20;
+export const DEFAULT_MIN_ROWS = 5;
+
+/** terminal.min_cols / min_rows から最小サイズを決める */
+export function minTerminalSize(config: TerminalConfig): TerminalSize {
+  return {
+    cols: config.min_cols ?? DEFAULT_MIN_COLS,
+    rows: config.min_rows ?? DEFAULT_MIN_ROWS,
+  };
+}
+
+/**
+ * 表示領域から計算した行列数を最小サイズ以上に収める
+ * 全画面プログラムに1x1のような極端なサイズを渡さないため。tooSmallは表示領域が最小サイズを下回っているか
+ */
+export function clampTerminalSize(
+  size: TerminalSize,
+  minimum: TerminalSize
+): { size: TerminalSize; tooSmall: boolean } {
+  const tooSmall = size.cols < minimum.cols || size.rows < minimum.rows;
+  return {
+    size: { cols: Math.max(size.cols, minimum.cols), rows: Math.max(size.rows, minimum.rows) },
+    tooSmall,
+  };
+}
+
+/**
+ * OSC 7（シェルが通知する作業ディレクトリ）の file:// URLからパスを取り出す
+ * 例: file://host/home/user/my%20docs -> /home/user/my docs
+ */
+export function parseOsc7Cwd(data: string): string | null {
+  try {
+    const url = new URL(data);
+    if (url.protocol !== "file:") return null;
+    return decodeURIComponent(url.pathname) || null;
+  } catch {
+    return null;
+  }
+}
+
+/** 複製するターミナルの作業ディレクトリ（OSC 7で未通知ならプロジェクトのパス） */
+export function duplicateCwd(trackedCwd: string | null | undefined, projectPath: string): string {
+  return trackedCwd ?? projectPath;
+}
+
+/** プロジェクトを開き直した時のターミナルの扱い */
+export type TerminalReloadAction = "reuse" | "reprint" | "recreate";
+
+/**
+ * プロジェクトを開き直した時にターミナルをどう扱うか（terminal.preserve_scrollback_on_reload）
+ * シェルが既に新しいプロジェクトにいればセッションをそのまま使う（実行中のジョブも止めない）。
+ * それ以外は新しいセッションを起動し、前のスクロールバックを先頭に表示する
+ */
+export function terminalReloadAction(
+  preserve: boolean | undefined,
+  shellCwd: string | undefined,
+  projectPath: string
+): TerminalReloadAction {
+  if (!preserve) return "recreate";
+  const trimSlash = (path: string) => path.replace(/(?<=.)\/+$/, "");
+  if (shellCwd && trimSlash(shellCwd) === trimSlash(projectPath)) return "reuse";
+  return "reprint";
+}
+
+/** 前のセッションのスクロールバックを、新しいセッションの先頭に表示する形に整える */
+export function formatPreservedScrollback(text: string): string {
+  const lines = text.split("\n").map((line) => line.trimEnd());
+  while (lines.length > 0 && lines[lines.length - 1] === "") lines.pop();
+  if (lines.length === 0) return "";
+  return [
+    "\x1b[2m--- Previous session ---\x1b[0m",
+    ...lines,
+    "\x1b[2m--- End of previous session ---\x1b[0m",
+    "",
+  ].join("\r\n");
+}
+
+/**
+ * ウィンドウタイトルの問い合わせ（CSI 21 t）への応答。対象外のウィンドウ操作はnull
+ * 応答はシェルの入力として読まれるため、タイトル中の制御文字は取り除く
+ */
+export function titleReportReply(params: (number | number[])[], title: string): string | null {
+  if (params[0] !== 21) return null;
+  // eslint-disable-next-line no-control-regex
+  return `\x1b]l${title.replace(/[\x00-\x1f\x7f-\x9f]/g, "")}\x1b\\`;
+}
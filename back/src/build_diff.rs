@@ -0,0 +1,197 @@
+//! ビルド出力の比較（一時ディレクトリに再ビルドして現在の出力と比べる）
+
+use crate::sphinx::{build_once, resolve_build_path, SphinxEnv};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 変更の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// 変更されたファイル（ビルドディレクトリからの相対パス）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedFile {
+    pub path: String,
+    pub change: ChangeKind,
+}
+
+/// ディレクトリ以下のファイルを相対パス順に列挙
+/// ドットで始まるエントリ（.buildinfo, .doctreesなど）はビルドごとに変わるため除外
+fn collect_files(root: &Path) -> BTreeMap<String, PathBuf> {
+    fn walk(dir: &Path, prefix: &str, files: &mut BTreeMap<String, PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let relative = format!("{}{}", prefix, name);
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, &format!("{}/", relative), files);
+            } else {
+                files.insert(relative, path);
+            }
+        }
+    }
+
+    let mut files = BTreeMap::new();
+    walk(root, "", &mut files);
+    files
+}
+
+/// 2つのファイルの内容が同じか（更新日時は見ない）
+fn same_content(a: &Path, b: &Path) -> bool {
+    let (Ok(meta_a), Ok(meta_b)) = (std::fs::metadata(a), std::fs::metadata(b)) else {
+        return false;
+    };
+    if meta_a.len() != meta_b.len() {
+        return false;
+    }
+    match (std::fs::read(a), std::fs::read(b)) {
+        (Ok(content_a), Ok(content_b)) => content_a == content_b,
+        _ => false,
+    }
+}
+
+/// ビルドディレクトリaからbへの変更を列挙（パス順）
+pub fn diff_build_dirs(a: &Path, b: &Path) -> Vec<ChangedFile> {
+    let files_a = collect_files(a);
+    let files_b = collect_files(b);
+
+    let mut changes: Vec<ChangedFile> = Vec::new();
+    for (path, file_a) in &files_a {
+        let change = match files_b.get(path) {
+            None => ChangeKind::Removed,
+            Some(file_b) if !same_content(file_a, file_b) => ChangeKind::Modified,
+            Some(_) => continue,
+        };
+        changes.push(ChangedFile {
+            path: path.clone(),
+            change,
+        });
+    }
+    for path in files_b.keys().filter(|path| !files_a.contains_key(*path)) {
+        changes.push(ChangedFile {
+            path: path.clone(),
+            change: ChangeKind::Added,
+        });
+    }
+    changes.sort_by(|x, y| x.path.cmp(&y.path));
+    changes
+}
+
+/// 一時ディレクトリにビルドし、現在のビルド出力との差分を返す
+pub fn build_and_diff(
+    project_path: &str,
+    source_dir: &str,
+    build_dir: &str,
+    python_path: &str,
+    env: &SphinxEnv,
+) -> Result<Vec<ChangedFile>, String> {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let temp_dir =
+        std::env::temp_dir().join(format!("khafre-build-{}-{}", std::process::id(), nonce));
+
+    let result = build_once(project_path, source_dir, &temp_dir, python_path, env)
+        .map(|()| diff_build_dirs(&resolve_build_path(project_path, build_dir), &temp_dir));
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixture;
+
+    fn changed(path: &str, change: ChangeKind) -> ChangedFile {
+        ChangedFile {
+            path: path.to_string(),
+            change,
+        }
+    }
+
+    #[test]
+    fn test_diff_build_dirs() {
+        let a = fixture(
+            "diff-a",
+            &[
+                ("index.html", "<h1>Home</h1>"),
+                ("guide/install.html", "<p>pip install</p>"),
+                ("guide/old.html", "<p>old</p>"),
+                ("_static/basic.css", "body {}"),
+            ],
+        );
+        let b = fixture(
+            "diff-b",
+            &[
+                ("index.html", "<h1>Home</h1>"),
+                ("guide/install.html", "<p>uv pip install</p>"),
+                ("guide/new.html", "<p>new</p>"),
+                ("_static/basic.css", "body {}"),
+            ],
+        );
+
+        assert_eq!(
+            diff_build_dirs(&a, &b),
+            vec![
+                changed("guide/install.html", ChangeKind::Modified),
+                changed("guide/new.html", ChangeKind::Added),
+                changed("guide/old.html", ChangeKind::Removed),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&a);
+        let _ = std::fs::remove_dir_all(&b);
+    }
+
+    #[test]
+    fn test_diff_ignores_build_metadata() {
+        let a = fixture(
+            "diff-meta-a",
+            &[
+                ("index.html", "same"),
+                (".buildinfo", "config: 1"),
+                (".doctrees/index.doctree", "1"),
+            ],
+        );
+        let b = fixture(
+            "diff-meta-b",
+            &[
+                ("index.html", "same"),
+                (".buildinfo", "config: 2"),
+                (".doctrees/index.doctree", "2"),
+            ],
+        );
+
+        assert!(diff_build_dirs(&a, &b).is_empty());
+
+        let _ = std::fs::remove_dir_all(&a);
+        let _ = std::fs::remove_dir_all(&b);
+    }
+
+    #[test]
+    fn test_diff_against_missing_build_dir() {
+        let b = fixture("diff-fresh", &[("index.html", "<h1>Home</h1>")]);
+        let missing = b.join("missing");
+
+        assert_eq!(
+            diff_build_dirs(&missing, &b),
+            vec![changed("index.html", ChangeKind::Added)]
+        );
+
+        let _ = std::fs::remove_dir_all(&b);
+    }
+}
@@ -4,12 +4,14 @@
 //! - Alacritty (TOML)
 //! - Windows Terminal (JSON)
 //! - iTerm2 (.itermcolors plist)
+//! - Kitty (.conf)
+//! - Xresources (.Xresources / .Xdefaults)
 
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 /// xterm.js ITheme互換のカラースキーム
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ColorScheme {
     #[serde(default)]
     pub background: Option<String>,
@@ -57,28 +59,299 @@ pub struct ColorScheme {
     pub bright_cyan: Option<String>,
     #[serde(default)]
     pub bright_white: Option<String>,
+    // Extended 256-color palette (16-255)
+    #[serde(default)]
+    pub extended_ansi: Option<Vec<String>>,
 }
 
 /// テーマファイルを読み込み、フォーマットを拡張子から自動検出
+///
+/// 拡張子の無いファイルは、先頭がXresourcesの書式ならXresourcesとして読む
 pub fn load_theme_file(path: &Path) -> Result<ColorScheme, String> {
     let content =
         std::fs::read_to_string(path).map_err(|e| format!("テーマファイル読み込み失敗: {}", e))?;
 
+    // .Xresourcesのようなドットファイルはファイル名を拡張子とみなす
     let extension = path
         .extension()
+        .or_else(|| {
+            path.file_name()
+                .filter(|name| name.to_string_lossy().starts_with('.'))
+        })
         .and_then(|e| e.to_str())
         .unwrap_or("")
+        .trim_start_matches('.')
         .to_lowercase();
 
-    match extension.as_str() {
+    let scheme = match extension.as_str() {
         "toml" => parse_alacritty_toml(&content),
         "json" => parse_windows_terminal_json(&content),
         "itermcolors" => parse_iterm2_plist(&content),
+        "conf" => parse_kitty_conf(&content),
+        "xresources" | "xdefaults" => parse_xresources(&content),
+        "" if looks_like_xresources(&content) => parse_xresources(&content),
         _ => Err(format!(
-            "未対応のテーマファイル形式: .{} (対応: .toml, .json, .itermcolors)",
+            "未対応のテーマファイル形式: .{} (対応: .toml, .json, .itermcolors, .conf, .Xresources)",
             extension
         )),
+    }?;
+
+    Ok(scheme.normalized())
+}
+
+/// 256色パレットの色数
+pub const PALETTE_SIZE: usize = 256;
+
+/// 256色パレットファイルを読み込む
+///
+/// 1行に1色（空行は無視）か、色の文字列のJSON配列で0〜255番の色を並べる
+pub fn load_palette_file(path: &Path) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("パレットファイル読み込み失敗: {}", e))?;
+    parse_palette(&content)
+}
+
+/// パレットをパースし、全ての色を#rrggbb形式にする
+fn parse_palette(content: &str) -> Result<Vec<String>, String> {
+    let colors: Vec<String> = if content.trim_start().starts_with('[') {
+        serde_json::from_str(content).map_err(|e| format!("パレットのJSONパース失敗: {}", e))?
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    };
+    if colors.len() != PALETTE_SIZE {
+        return Err(format!(
+            "パレットの色数が{}ではありません: {}",
+            PALETTE_SIZE,
+            colors.len()
+        ));
+    }
+
+    colors
+        .iter()
+        .enumerate()
+        .map(|(index, color)| {
+            let hex = normalize_hex_color(color);
+            let digits = hex.strip_prefix('#').unwrap_or("");
+            if digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                Ok(hex)
+            } else {
+                Err(format!("パレットの{}番の色が不正: {}", index, color))
+            }
+        })
+        .collect()
+}
+
+/// ANSI 0〜15番の色に対応するフィールド名
+const ANSI_FIELDS: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright_black",
+    "bright_red",
+    "bright_green",
+    "bright_yellow",
+    "bright_blue",
+    "bright_magenta",
+    "bright_cyan",
+    "bright_white",
+];
+
+impl ColorScheme {
+    /// フィールド名を指定して色を設定（未知のフィールド名は無視してfalseを返す）
+    fn set_color(&mut self, field: &str, value: String) -> bool {
+        match self.color_mut(field) {
+            Some(slot) => {
+                *slot = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// フィールド名に対応する色（未知のフィールド名はNone）
+    fn color_mut(&mut self, field: &str) -> Option<&mut Option<String>> {
+        let slot = match field {
+            "background" => &mut self.background,
+            "foreground" => &mut self.foreground,
+            "cursor" => &mut self.cursor,
+            "cursor_accent" => &mut self.cursor_accent,
+            "selection_background" => &mut self.selection_background,
+            "selection_foreground" => &mut self.selection_foreground,
+            "black" => &mut self.black,
+            "red" => &mut self.red,
+            "green" => &mut self.green,
+            "yellow" => &mut self.yellow,
+            "blue" => &mut self.blue,
+            "magenta" => &mut self.magenta,
+            "cyan" => &mut self.cyan,
+            "white" => &mut self.white,
+            "bright_black" => &mut self.bright_black,
+            "bright_red" => &mut self.bright_red,
+            "bright_green" => &mut self.bright_green,
+            "bright_yellow" => &mut self.bright_yellow,
+            "bright_blue" => &mut self.bright_blue,
+            "bright_magenta" => &mut self.bright_magenta,
+            "bright_cyan" => &mut self.bright_cyan,
+            "bright_white" => &mut self.bright_white,
+            _ => return None,
+        };
+        Some(slot)
+    }
+
+    /// 256色パレットを適用
+    /// 0〜15番はテーマで未設定のANSI色だけを埋め、16〜255番は拡張パレットとして使う
+    pub fn with_palette(mut self, palette: Vec<String>) -> Self {
+        for (field, color) in ANSI_FIELDS.iter().zip(&palette) {
+            if let Some(slot) = self.color_mut(field) {
+                slot.get_or_insert_with(|| color.clone());
+            }
+        }
+        self.extended_ansi = Some(palette.into_iter().skip(ANSI_FIELDS.len()).collect());
+        self
+    }
+
+    /// 全ての色をxterm.jsが解釈できる形式に揃える
+    pub fn normalized(mut self) -> Self {
+        for color in [
+            &mut self.background,
+            &mut self.foreground,
+            &mut self.cursor,
+            &mut self.cursor_accent,
+            &mut self.selection_background,
+            &mut self.selection_foreground,
+            &mut self.black,
+            &mut self.red,
+            &mut self.green,
+            &mut self.yellow,
+            &mut self.blue,
+            &mut self.magenta,
+            &mut self.cyan,
+            &mut self.white,
+            &mut self.bright_black,
+            &mut self.bright_red,
+            &mut self.bright_green,
+            &mut self.bright_yellow,
+            &mut self.bright_blue,
+            &mut self.bright_magenta,
+            &mut self.bright_cyan,
+            &mut self.bright_white,
+        ] {
+            if let Some(value) = color.as_mut() {
+                *value = normalize_hex_color(value);
+            }
+        }
+        for value in self.extended_ansi.iter_mut().flatten() {
+            *value = normalize_hex_color(value);
+        }
+        self
+    }
+
+    /// parse_alacritty_tomlで読み込めるAlacritty TOML形式に書き出す（未設定の色は省く）
+    pub fn to_alacritty_toml(&self) -> String {
+        let normal = [
+            &self.black,
+            &self.red,
+            &self.green,
+            &self.yellow,
+            &self.blue,
+            &self.magenta,
+            &self.cyan,
+            &self.white,
+        ];
+        let bright = [
+            &self.bright_black,
+            &self.bright_red,
+            &self.bright_green,
+            &self.bright_yellow,
+            &self.bright_blue,
+            &self.bright_magenta,
+            &self.bright_cyan,
+            &self.bright_white,
+        ];
+        // normal/brightのキー名はANSI 0〜7番のフィールド名と同じ
+        fn ansi(colors: [&Option<String>; 8]) -> Vec<(&'static str, &Option<String>)> {
+            ANSI_FIELDS[..8].iter().copied().zip(colors).collect()
+        }
+        let sections = [
+            (
+                "primary",
+                vec![
+                    ("background", &self.background),
+                    ("foreground", &self.foreground),
+                ],
+            ),
+            (
+                "cursor",
+                vec![("cursor", &self.cursor), ("text", &self.cursor_accent)],
+            ),
+            (
+                "selection",
+                vec![
+                    ("background", &self.selection_background),
+                    ("text", &self.selection_foreground),
+                ],
+            ),
+            ("normal", ansi(normal)),
+            ("bright", ansi(bright)),
+        ];
+
+        let mut out = String::new();
+        for (section, colors) in sections {
+            let set: Vec<_> = colors
+                .into_iter()
+                .filter_map(|(key, value)| value.as_ref().map(|v| (key, v)))
+                .collect();
+            if set.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("[colors.{}]\n", section));
+            for (key, value) in set {
+                let quoted = toml::Value::String(value.clone()).to_string();
+                out.push_str(&format!("{} = {}\n", key, quoted));
+            }
+        }
+        out
+    }
+}
+
+/// 16進カラーを#rrggbb形式にする
+///
+/// `#rgb`（短縮形）と`0xRRGGBB`（Alacrittyの旧形式）を展開し、
+/// それ以外（#rrggbbやCSSの色名など）はそのまま返す
+pub fn normalize_hex_color(value: &str) -> String {
+    let value = value.trim();
+    let is_hex = |digits: &str| digits.chars().all(|c| c.is_ascii_hexdigit());
+
+    if let Some(digits) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        if digits.len() == 6 && is_hex(digits) {
+            return format!("#{}", digits);
+        }
+    } else if let Some(digits) = value.strip_prefix('#') {
+        if digits.len() == 3 && is_hex(digits) {
+            return digits.chars().fold(String::from("#"), |mut hex, c| {
+                hex.push(c);
+                hex.push(c);
+                hex
+            });
+        }
     }
+    value.to_string()
 }
 
 /// Alacritty TOML形式をパース
@@ -160,6 +433,7 @@ fn parse_alacritty_toml(content: &str) -> Result<ColorScheme, String> {
         bright_magenta: bright.magenta,
         bright_cyan: bright.cyan,
         bright_white: bright.white,
+        extended_ansi: None,
     })
 }
 
@@ -216,6 +490,7 @@ fn parse_windows_terminal_json(content: &str) -> Result<ColorScheme, String> {
         bright_magenta: theme.bright_purple,
         bright_cyan: theme.bright_cyan,
         bright_white: theme.bright_white,
+        extended_ansi: None,
     })
 }
 
@@ -303,40 +578,128 @@ fn parse_iterm2_plist(content: &str) -> Result<ColorScheme, String> {
 
     for (iterm_key, field) in &color_map {
         if let Some(hex) = extract_color(content, iterm_key) {
-            match *field {
-                "background" => scheme.background = Some(hex),
-                "foreground" => scheme.foreground = Some(hex),
-                "cursor" => scheme.cursor = Some(hex),
-                "cursor_accent" => scheme.cursor_accent = Some(hex),
-                "selection_background" => scheme.selection_background = Some(hex),
-                "selection_foreground" => scheme.selection_foreground = Some(hex),
-                "black" => scheme.black = Some(hex),
-                "red" => scheme.red = Some(hex),
-                "green" => scheme.green = Some(hex),
-                "yellow" => scheme.yellow = Some(hex),
-                "blue" => scheme.blue = Some(hex),
-                "magenta" => scheme.magenta = Some(hex),
-                "cyan" => scheme.cyan = Some(hex),
-                "white" => scheme.white = Some(hex),
-                "bright_black" => scheme.bright_black = Some(hex),
-                "bright_red" => scheme.bright_red = Some(hex),
-                "bright_green" => scheme.bright_green = Some(hex),
-                "bright_yellow" => scheme.bright_yellow = Some(hex),
-                "bright_blue" => scheme.bright_blue = Some(hex),
-                "bright_magenta" => scheme.bright_magenta = Some(hex),
-                "bright_cyan" => scheme.bright_cyan = Some(hex),
-                "bright_white" => scheme.bright_white = Some(hex),
-                _ => {}
+            scheme.set_color(field, hex);
+        }
+    }
+
+    Ok(scheme)
+}
+
+/// Kitty の `キー 値` 形式（.conf）をパース
+///
+/// `#`で始まる行はコメント。キーと値の間はスペースでもタブでもよい。
+/// 色以外の設定や、`cursor_text_color background`のような色でない値は無視する
+fn parse_kitty_conf(content: &str) -> Result<ColorScheme, String> {
+    let mut scheme = ColorScheme::default();
+    let mut found = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if !value.starts_with('#') {
+            continue;
+        }
+
+        let field = match key {
+            "cursor_text_color" => "cursor_accent",
+            _ => match key
+                .strip_prefix("color")
+                .and_then(|n| n.parse::<usize>().ok())
+            {
+                Some(index) if index < ANSI_FIELDS.len() => ANSI_FIELDS[index],
+                Some(_) => continue,
+                None => key,
+            },
+        };
+        found |= scheme.set_color(field, value.to_string());
+    }
+
+    if !found {
+        return Err("Kitty テーマのパース失敗: 色の設定が見つかりません".to_string());
+    }
+    Ok(scheme)
+}
+
+/// Xresourcesのリソース名（`*.color0`、`URxvt.cursorColor`など）をフィールド名に変換
+fn xresources_field(resource: &str) -> Option<&'static str> {
+    // `*`や`.`で区切られた最後の要素が色の名前
+    let name = resource.rsplit(['.', '*']).next()?;
+    match name {
+        "background" => Some("background"),
+        "foreground" => Some("foreground"),
+        "cursorColor" => Some("cursor"),
+        _ => {
+            let index: usize = name.strip_prefix("color")?.parse().ok()?;
+            ANSI_FIELDS.get(index).copied()
+        }
+    }
+}
+
+/// 拡張子の無いファイルがXresourcesらしいか（コメント以外の先頭数行で判定）
+fn looks_like_xresources(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('!'))
+        .take(5)
+        .any(|line| {
+            line.split_once(':')
+                .is_some_and(|(resource, _)| xresources_field(resource.trim()).is_some())
+        })
+}
+
+/// Xresources形式（`*.background: #1e1e1e`）をパース
+///
+/// `!`で始まる行はコメント。`#define 名前 値`で定義した名前は値に展開する
+fn parse_xresources(content: &str) -> Result<ColorScheme, String> {
+    use std::collections::HashMap;
+
+    let mut defines: HashMap<&str, &str> = HashMap::new();
+    let mut scheme = ColorScheme::default();
+    let mut found = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('!') {
+            continue;
+        }
+        if let Some(define) = line.strip_prefix("#define") {
+            let mut parts = define.split_whitespace();
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                defines.insert(name, value);
             }
+            continue;
         }
+        let Some((resource, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(field) = xresources_field(resource.trim()) else {
+            continue;
+        };
+        let value = value.trim();
+        let value = defines.get(value).copied().unwrap_or(value);
+        if value.is_empty() {
+            continue;
+        }
+        found |= scheme.set_color(field, value.to_string());
     }
 
+    if !found {
+        return Err("Xresources のパース失敗: 色の設定が見つかりません".to_string());
+    }
     Ok(scheme)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{test_dir, write_file};
 
     #[test]
     fn test_parse_alacritty_toml() {
@@ -373,6 +736,52 @@ white = "#ffffff"
         assert_eq!(scheme.bright_white, Some("#ffffff".to_string()));
     }
 
+    #[test]
+    fn test_alacritty_toml_round_trip() {
+        let toml = r##"
+[colors.primary]
+background = "#1e1e1e"
+foreground = "#d4d4d4"
+
+[colors.cursor]
+cursor = "#ffffff"
+text = "#000000"
+
+[colors.selection]
+background = "#264f78"
+
+[colors.normal]
+black = "#000000"
+red = "#cc0000"
+white = "#cccccc"
+
+[colors.bright]
+black = "#555555"
+magenta = "#ff55ff"
+white = "#ffffff"
+"##;
+
+        let scheme = parse_alacritty_toml(toml).unwrap();
+        let exported = scheme.to_alacritty_toml();
+        assert_eq!(parse_alacritty_toml(&exported).unwrap(), scheme);
+        assert!(exported.starts_with("[colors.primary]\nbackground = \"#1e1e1e\"\n"));
+        // 未設定の色は書き出さない
+        assert!(!exported.contains("selection_foreground"));
+        assert!(!exported.contains("green"));
+    }
+
+    #[test]
+    fn test_export_other_formats_to_alacritty() {
+        let json =
+            r##"{ "background": "#1E1E1E", "purple": "#CC00CC", "brightPurple": "#FF55FF" }"##;
+        let scheme = parse_windows_terminal_json(json).unwrap();
+        let exported = scheme.to_alacritty_toml();
+        assert_eq!(parse_alacritty_toml(&exported).unwrap(), scheme);
+        assert!(exported.contains("[colors.normal]\nmagenta = \"#CC00CC\""));
+        assert!(exported.contains("[colors.bright]\nmagenta = \"#FF55FF\""));
+        assert_eq!(ColorScheme::default().to_alacritty_toml(), "");
+    }
+
     #[test]
     fn test_parse_windows_terminal_json() {
         let json = r##"
@@ -450,6 +859,176 @@ white = "#ffffff"
         assert_eq!(scheme.black, Some("#000000".to_string()));
     }
 
+    #[test]
+    fn test_parse_kitty_conf() {
+        let conf = "# vim:ft=kitty
+## name: Example
+# color0 #ffffff
+
+background #1e1e1e
+foreground\t#d4d4d4
+cursor                #ffffff
+cursor_text_color     background
+selection_background  #264f78
+color0   #000000
+color5   #cc00cc
+color15\t\t#ffffff
+color200 #123456
+url_color #0087bd
+";
+
+        let scheme = parse_kitty_conf(conf).unwrap();
+        assert_eq!(scheme.background, Some("#1e1e1e".to_string()));
+        assert_eq!(scheme.foreground, Some("#d4d4d4".to_string()));
+        assert_eq!(scheme.cursor, Some("#ffffff".to_string()));
+        assert_eq!(scheme.cursor_accent, None);
+        assert_eq!(scheme.selection_background, Some("#264f78".to_string()));
+        // コメント行の色は使わない
+        assert_eq!(scheme.black, Some("#000000".to_string()));
+        assert_eq!(scheme.magenta, Some("#cc00cc".to_string()));
+        assert_eq!(scheme.bright_white, Some("#ffffff".to_string()));
+    }
+
+    #[test]
+    fn test_parse_kitty_conf_without_colors() {
+        assert!(parse_kitty_conf("font_size 12\n# background #000000\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_xresources() {
+        let xresources = "! Example palette
+#define base00 #1d1f21
+#define base05 #c5c8c6
+
+*.background: base00
+*.foreground:   base05
+URxvt.cursorColor: #ffffff
+*.color0:  #000000
+*color5:\t#cc00cc
+XTerm*color15: #ffffff
+*.color16: #123456
+URxvt.font: xft:Monospace:size=10
+";
+
+        let scheme = parse_xresources(xresources).unwrap();
+        assert_eq!(scheme.background, Some("#1d1f21".to_string()));
+        assert_eq!(scheme.foreground, Some("#c5c8c6".to_string()));
+        assert_eq!(scheme.cursor, Some("#ffffff".to_string()));
+        assert_eq!(scheme.black, Some("#000000".to_string()));
+        assert_eq!(scheme.magenta, Some("#cc00cc".to_string()));
+        assert_eq!(scheme.bright_white, Some("#ffffff".to_string()));
+    }
+
+    #[test]
+    fn test_looks_like_xresources() {
+        assert!(looks_like_xresources("! colors\n*.background: #000000\n"));
+        assert!(!looks_like_xresources(
+            "background #000000\ncolor0 #000000\n"
+        ));
+        assert!(!looks_like_xresources(
+            "[colors.primary]\nbackground = \"#000000\"\n"
+        ));
+    }
+
+    #[test]
+    fn test_load_xresources_by_name() {
+        let dir = test_dir("theme-xres");
+        let content = "*.background: #282828\n*.color1: #cc241d\n";
+        for name in [".Xresources", "gruvbox.xresources", "gruvbox"] {
+            let path = dir.join(name);
+            std::fs::write(&path, content).unwrap();
+            let scheme = load_theme_file(&path).unwrap();
+            assert_eq!(scheme.background, Some("#282828".to_string()), "{}", name);
+            assert_eq!(scheme.red, Some("#cc241d".to_string()), "{}", name);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_normalize_hex_color() {
+        assert_eq!(normalize_hex_color("#1e1e1e"), "#1e1e1e");
+        assert_eq!(normalize_hex_color("#1E1E1E"), "#1E1E1E");
+        assert_eq!(normalize_hex_color("#abc"), "#aabbcc");
+        assert_eq!(normalize_hex_color("0x1e1e1e"), "#1e1e1e");
+        assert_eq!(normalize_hex_color("0XFF8000"), "#FF8000");
+        assert_eq!(normalize_hex_color(" #fff "), "#ffffff");
+        // 解釈できないものはそのまま
+        assert_eq!(normalize_hex_color("0x12345"), "0x12345");
+        assert_eq!(normalize_hex_color("#ggg"), "#ggg");
+        assert_eq!(normalize_hex_color("red"), "red");
+    }
+
+    #[test]
+    fn test_load_alacritty_legacy_hex() {
+        let dir = test_dir("theme-hex");
+        let path = dir.join("legacy.toml");
+        write_file(
+            &path,
+            r##"
+[colors.primary]
+background = "0x282828"
+foreground = "#fff"
+"##,
+        );
+
+        let scheme = load_theme_file(&path).unwrap();
+        assert_eq!(scheme.background, Some("#282828".to_string()));
+        assert_eq!(scheme.foreground, Some("#ffffff".to_string()));
+        assert_eq!(scheme.black, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// index番目を#iiiiiiにしたパレット
+    fn palette_lines() -> Vec<String> {
+        (0..PALETTE_SIZE)
+            .map(|index| format!("#{:02x}{:02x}{:02x}", index, index, index))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        let lines = palette_lines().join("\n");
+        let palette = parse_palette(&format!("{}\n\n", lines)).unwrap();
+        assert_eq!(palette.len(), PALETTE_SIZE);
+        assert_eq!(palette[16], "#101010");
+        assert_eq!(palette[255], "#ffffff");
+
+        // JSON配列でも書ける（短縮形や0xも#rrggbbにする）
+        let mut colors = palette_lines();
+        colors[0] = "#fff".to_string();
+        colors[1] = "0x123456".to_string();
+        let palette = parse_palette(&serde_json::to_string(&colors).unwrap()).unwrap();
+        assert_eq!(palette[0], "#ffffff");
+        assert_eq!(palette[1], "#123456");
+
+        // 色数の不足や不正な色はエラー
+        assert!(parse_palette("#000000\n#ffffff").is_err());
+        colors[200] = "red".to_string();
+        let error = parse_palette(&serde_json::to_string(&colors).unwrap()).unwrap_err();
+        assert!(error.contains("200"));
+    }
+
+    #[test]
+    fn test_with_palette() {
+        let scheme = ColorScheme {
+            red: Some("#cc0000".to_string()),
+            ..Default::default()
+        }
+        .with_palette(palette_lines());
+
+        // 16〜255番は拡張パレットになる
+        let extended = scheme.extended_ansi.as_ref().unwrap();
+        assert_eq!(extended.len(), PALETTE_SIZE - 16);
+        assert_eq!(extended[0], "#101010");
+        assert_eq!(extended[196 - 16], "#c4c4c4");
+
+        // 0〜15番はテーマの色を優先し、未設定の色だけを埋める
+        assert_eq!(scheme.red, Some("#cc0000".to_string()));
+        assert_eq!(scheme.black, Some("#000000".to_string()));
+        assert_eq!(scheme.bright_white, Some("#0f0f0f".to_string()));
+        assert_eq!(scheme.background, None);
+    }
+
     #[test]
     fn test_rgb_float_to_hex() {
         fn rgb_float_to_hex(r: f64, g: f64, b: f64) -> String {
@@ -1,6 +1,9 @@
-use crate::color_scheme::{load_theme_file, ColorScheme};
+use crate::color_scheme::{load_palette_file, load_theme_file, ColorScheme};
+use crate::sphinx::{DEFAULT_ERROR_PATTERNS, DEFAULT_SUCCESS_PATTERNS};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// プロジェクト設定全体
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -13,6 +16,14 @@ pub struct Config {
     pub editor: EditorConfig,
     #[serde(default)]
     pub terminal: TerminalConfig,
+    #[serde(default)]
+    pub preview: PreviewConfig,
+    #[serde(default)]
+    pub log: LogConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub control: ControlConfig,
 }
 
 /// Sphinx関連設定
@@ -27,6 +38,30 @@ pub struct SphinxConfig {
     /// sphinx-autobuild への追加引数
     #[serde(default)]
     pub extra_args: Vec<String>,
+    /// プレビューを操作しない状態がこの秒数続いたらsphinx-autobuildを停止 (0 = 停止しない)
+    #[serde(default)]
+    pub idle_timeout_secs: u64,
+    /// sphinx-autobuildが異常終了した時に自動で再起動する回数（ビルド成功でリセット）
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    /// stderrにこの文字列を含む行があればビルド完了とみなす
+    #[serde(default = "default_success_patterns")]
+    pub success_patterns: Vec<String>,
+    /// stderrにこの文字列を含む行があればエラーとして通知する
+    #[serde(default = "default_error_patterns")]
+    pub error_patterns: Vec<String>,
+    /// 起動中にプレビューのURLへ接続を確認する間隔（秒、0 = 確認しない）
+    #[serde(default = "default_health_check_interval_secs")]
+    pub health_check_interval_secs: u64,
+    /// 接続の確認がこの回数続けて失敗したら停止したものとみなす
+    #[serde(default = "default_health_check_failures")]
+    pub health_check_failures: u32,
+    /// sphinx-autobuildに渡す環境変数
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Khafreの環境変数を引き継ぐか（falseならenvとロケールの設定のみで起動する）
+    #[serde(default = "default_true")]
+    pub inherit_env: bool,
 }
 
 /// sphinx-autobuildサーバー設定
@@ -41,6 +76,23 @@ pub struct ServerConfig {
 pub struct PythonConfig {
     #[serde(default = "default_interpreter")]
     pub interpreter: String,
+    #[serde(default)]
+    pub runner: PythonRunner,
+}
+
+/// インタプリタの解決方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PythonRunner {
+    /// .python-version（pyenv）や.tool-versions（asdf）があればそのバージョンのPythonを使う
+    #[default]
+    Auto,
+    /// pyenvで解決する
+    Pyenv,
+    /// asdfで解決する
+    Asdf,
+    /// interpreterをそのまま使う
+    Direct,
 }
 
 /// エディタ設定
@@ -48,6 +100,9 @@ pub struct PythonConfig {
 pub struct EditorConfig {
     #[serde(default = "default_editor")]
     pub command: String,
+    /// 行番号を指定して開く時の引数（{line}と{file}を置き換える、None = "+{line} {file}"）
+    #[serde(default)]
+    pub line_format: Option<String>,
 }
 
 /// ターミナル設定
@@ -62,12 +117,226 @@ pub struct TerminalConfig {
     /// フォントサイズ
     #[serde(default)]
     pub font_size: Option<u16>,
-    /// テーマファイルパス（Alacritty/WindowsTerminal/iTerm2形式）
+    /// テーマファイルパス（Alacritty/WindowsTerminal/iTerm2/Kitty/Xresources形式）
     #[serde(default)]
     pub theme_file: Option<String>,
+    /// 256色パレットファイルパス（16〜255番の色を上書き）
+    #[serde(default)]
+    pub palette_file: Option<String>,
     /// インラインカラースキーム（theme_fileより優先）
     #[serde(default)]
     pub color_scheme: Option<ColorScheme>,
+    /// OSがライトテーマの時に使うテーマファイル（color_scheme / theme_fileより優先）
+    #[serde(default)]
+    pub theme_file_light: Option<String>,
+    /// OSがダークテーマの時に使うテーマファイル（color_scheme / theme_fileより優先）
+    #[serde(default)]
+    pub theme_file_dark: Option<String>,
+    /// ライトテーマ用のカラースキーム（theme_file_lightより優先）
+    #[serde(default)]
+    pub color_scheme_light: Option<ColorScheme>,
+    /// ダークテーマ用のカラースキーム（theme_file_darkより優先）
+    #[serde(default)]
+    pub color_scheme_dark: Option<ColorScheme>,
+    /// 太字の通常ANSI色を明るい色で描画するか (None = true)
+    #[serde(default)]
+    pub bold_is_bright: Option<bool>,
+    /// 太字を太いフォントウェイトで描画するか (None = true)
+    #[serde(default)]
+    pub bold_font_weight: Option<bool>,
+    /// ターミナル周囲の余白（px） (None = 0)
+    #[serde(default)]
+    pub padding: Option<u16>,
+    /// シェル終了時の動作 (None = idle)
+    #[serde(default)]
+    pub on_exit: Option<TerminalExitAction>,
+    /// PTYに伝える最小の列数 (None = 20)
+    #[serde(default)]
+    pub min_cols: Option<u16>,
+    /// PTYに伝える最小の行数 (None = 5)
+    #[serde(default)]
+    pub min_rows: Option<u16>,
+    /// カーソル色（テーマのcursorより優先）
+    #[serde(default)]
+    pub cursor_color: Option<String>,
+    /// カーソル上の文字色（テーマのcursor_accentより優先）
+    #[serde(default)]
+    pub cursor_text_color: Option<String>,
+    /// プロンプト行を判定する正規表現（前後のプロンプトへの移動に使用）
+    #[serde(default)]
+    pub prompt_pattern: Option<String>,
+    /// ダブルクリックで単語を選択する時の区切り文字
+    #[serde(default)]
+    pub word_separators: Option<String>,
+    /// スクロールバックの最大行数
+    #[serde(default)]
+    pub scrollback_lines: Option<u32>,
+    /// 出力があった時にスクロールするか（デフォルト: 最下部を表示中の場合のみ）
+    #[serde(default)]
+    pub scroll_on_output: Option<ScrollOnOutput>,
+    /// 描画方式（大きなグリッドではWebGLの方が速い）
+    #[serde(default)]
+    pub renderer: Option<TerminalRenderer>,
+    /// タブストップの間隔（桁数、デフォルト: 8）
+    #[serde(default)]
+    pub tab_width: Option<u16>,
+    /// 背景色の不透明度（0.5〜1.0、背景画像を透かす）
+    #[serde(default)]
+    pub background_opacity: Option<f32>,
+    /// ターミナルの背景画像（theme_fileと同じ規則でパスを解決）
+    #[serde(default)]
+    pub background_image: Option<String>,
+    /// カーソルが背景や下の文字と見分けにくい色なら自動で補正するか
+    #[serde(default)]
+    pub smart_cursor_contrast: Option<bool>,
+    /// ベル（BEL）を受け取ったときにターミナルを一瞬光らせるか（デフォルト: true）
+    #[serde(default)]
+    pub visual_bell: Option<bool>,
+    /// プロジェクトを開き直した時にターミナルのセッションか、少なくともスクロールバックを残すか（デフォルト: false）
+    #[serde(default)]
+    pub preserve_scrollback_on_reload: Option<bool>,
+    /// 選択中のEnterをPTYへ送らず、選択範囲のコピーにするか（デフォルト: false）
+    #[serde(default)]
+    pub enter_copies_selection: Option<bool>,
+    /// 直前のコマンドが分からない時に、Up + Enterで履歴から再実行するか（デフォルト: true）
+    #[serde(default)]
+    pub rerun_history_fallback: Option<bool>,
+    /// コピー時のテキスト変換
+    #[serde(default)]
+    pub copy: Option<CopyConfig>,
+}
+
+/// コピー時のテキスト変換
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyConfig {
+    /// 各行末尾の空白を取り除くか (None = true)
+    #[serde(default)]
+    pub trim_trailing: Option<bool>,
+    /// 各行の先頭から取り除くプロンプトの正規表現
+    #[serde(default)]
+    pub strip_prompt: Option<String>,
+    /// 折り返して表示している行を1行に連結するか (None = true)
+    #[serde(default)]
+    pub join_wrapped: Option<bool>,
+}
+
+/// シェル終了時の動作
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalExitAction {
+    /// セッション終了を表示して待機
+    #[default]
+    Idle,
+    /// シェルを再起動
+    Respawn,
+    /// アプリを終了
+    Close,
+}
+
+/// 出力があった時のスクロール
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollOnOutput {
+    /// 常に最下部にスクロール
+    Always,
+    /// 最下部を表示中なら追従し、スクロールバックを見ている間は動かさない
+    #[default]
+    IfAtBottom,
+    /// 表示位置を動かさない
+    Never,
+}
+
+/// ターミナルの描画方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalRenderer {
+    /// xterm.jsの標準の描画（行ごとのDOM要素）
+    #[default]
+    Dom,
+    /// WebGLで描画（使えない場合はDomに戻す）
+    Webgl,
+}
+
+/// プレビュー設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewConfig {
+    /// プレビューとsphinx-autobuildを使うか（falseならターミナルのみ）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// プレビューの表示方法
+    #[serde(default)]
+    pub mode: PreviewMode,
+    /// 初回のビルド完了時に外部ブラウザを開くか（embeddedモードでも有効）
+    #[serde(default)]
+    pub open_browser_on_ready: bool,
+    /// 表示中ページの見出し一覧をプレビュー横に表示するか
+    #[serde(default)]
+    pub outline: bool,
+}
+
+/// プレビューの表示方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewMode {
+    /// アプリ内のiframeで表示
+    #[default]
+    Embedded,
+    /// 外部ブラウザで表示（iframeは描画しない）
+    External,
+}
+
+/// ログ設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// アプリ内ログビューアに記録する最低レベル
+    #[serde(default)]
+    pub level: LogLevel,
+}
+
+/// ログレベル
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+/// 画面表示の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiConfig {
+    /// ヘッダーのステータスバーの表示量
+    #[serde(default)]
+    pub status_bar: StatusBarMode,
+    /// プレビューのペインの割合（%）。スプリッターを動かすと保存される (None = 50)
+    #[serde(default)]
+    pub split_ratio: Option<f64>,
+}
+
+/// ステータスバーの表示量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusBarMode {
+    /// プロジェクトパス、Sphinxの状態、操作ボタンを全て表示
+    #[default]
+    Full,
+    /// Sphinxの状態のみ表示
+    Minimal,
+    /// ヘッダーごと表示しない
+    Hidden,
+}
+
+/// 制御ソケット設定（起動時にバックエンドのみが参照）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlConfig {
+    /// 制御ソケットを有効にするか
+    #[serde(default)]
+    pub enabled: bool,
+    /// ソケットファイルのパス（~や環境変数を展開する。None = $XDG_RUNTIME_DIR/khafre.sock）
+    #[serde(default)]
+    pub socket_path: Option<String>,
 }
 
 // デフォルト値関数
@@ -87,6 +356,30 @@ fn default_editor() -> String {
     "nvim".to_string()
 }
 
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_health_check_interval_secs() -> u64 {
+    10
+}
+
+fn default_health_check_failures() -> u32 {
+    3
+}
+
+fn default_success_patterns() -> Vec<String> {
+    DEFAULT_SUCCESS_PATTERNS.map(String::from).to_vec()
+}
+
+fn default_error_patterns() -> Vec<String> {
+    DEFAULT_ERROR_PATTERNS.map(String::from).to_vec()
+}
+
+fn default_true() -> bool {
+    true
+}
+
 impl Default for SphinxConfig {
     fn default() -> Self {
         Self {
@@ -94,6 +387,14 @@ impl Default for SphinxConfig {
             build_dir: default_build_dir(),
             server: ServerConfig::default(),
             extra_args: Vec::new(),
+            idle_timeout_secs: 0,
+            max_restarts: default_max_restarts(),
+            success_patterns: default_success_patterns(),
+            error_patterns: default_error_patterns(),
+            health_check_interval_secs: default_health_check_interval_secs(),
+            health_check_failures: default_health_check_failures(),
+            env: BTreeMap::new(),
+            inherit_env: true,
         }
     }
 }
@@ -102,6 +403,7 @@ impl Default for PythonConfig {
     fn default() -> Self {
         Self {
             interpreter: default_interpreter(),
+            runner: PythonRunner::default(),
         }
     }
 }
@@ -110,15 +412,256 @@ impl Default for EditorConfig {
     fn default() -> Self {
         Self {
             command: default_editor(),
+            line_format: None,
+        }
+    }
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            mode: PreviewMode::default(),
+            open_browser_on_ready: false,
+            outline: false,
+        }
+    }
+}
+
+/// パス中の `~` と環境変数（`$VAR` / `${VAR}`）を展開
+/// 未定義の環境変数はそのまま残す
+pub fn expand_path(path: &str) -> PathBuf {
+    let expanded = expand_env_vars(path);
+    if expanded == "~" {
+        return dirs::home_dir().unwrap_or_else(|| PathBuf::from(expanded));
+    }
+    if let Some(rest) = expanded.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(expanded)
+}
+
+fn expand_env_vars(path: &str) -> String {
+    let mut result = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => result.push_str(&value),
+            _ => result.push_str(&rest[start..start + 1 + consumed]),
+        }
+        rest = &after[consumed..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// パスを展開し、相対パスの場合はbaseを基準に解決
+pub fn resolve_path(base: &Path, path: &str) -> PathBuf {
+    let expanded = expand_path(path);
+    if expanded.is_relative() {
+        base.join(expanded)
+    } else {
+        expanded
+    }
+}
+
+/// 仮想環境のPythonの場所（プロジェクトルートからの相対パス、優先順）
+#[cfg(not(windows))]
+const VENV_INTERPRETERS: [&str; 2] = [".venv/bin/python", "venv/bin/python"];
+#[cfg(windows)]
+const VENV_INTERPRETERS: [&str; 2] = [".venv/Scripts/python.exe", "venv/Scripts/python.exe"];
+
+/// PATHから探すPython
+#[cfg(not(windows))]
+const PATH_INTERPRETER: &str = "python3";
+#[cfg(windows)]
+const PATH_INTERPRETER: &str = "python.exe";
+
+/// PATH（区切られたディレクトリの一覧）から実行ファイルを探す
+fn find_in_path(name: &str, path_var: &std::ffi::OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// プロジェクトのPythonインタプリタを検出
+/// よくある仮想環境の場所を探し、無ければPATHのpython3の絶対パスを返す
+pub fn detect_python_interpreter(project_path: &Path) -> Option<String> {
+    VENV_INTERPRETERS
+        .iter()
+        .find(|venv| project_path.join(venv).is_file())
+        .map(|venv| venv.to_string())
+        .or_else(|| {
+            let path_var = std::env::var_os("PATH")?;
+            find_in_path(PATH_INTERPRETER, &path_var).map(|path| path.to_string_lossy().to_string())
+        })
+}
+
+/// conf.pyを探す深さ（プロジェクトルートは0）
+const SOURCE_DIR_SEARCH_DEPTH: usize = 3;
+/// conf.pyを探さないディレクトリ（ドットや_で始まるものに加えて）
+const SOURCE_DIR_SKIP: [&str; 3] = ["node_modules", "venv", "build"];
+
+/// dirの直下のディレクトリ（プロジェクトルートからの相対パス、名前順）
+fn source_dir_children(project_path: &Path, dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(project_path.join(dir)) else {
+        return Vec::new();
+    };
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with(['.', '_']) && !SOURCE_DIR_SKIP.contains(&name.as_str()))
+        .map(|name| dir.join(name))
+        .collect();
+    children.sort();
+    children
+}
+
+/// プロジェクト内のconf.pyを探し、sphinx.source_dirに使うディレクトリを返す（浅い場所を優先）
+pub fn detect_source_dir(project_path: &Path) -> Option<String> {
+    let mut level = vec![PathBuf::new()];
+    for _ in 0..=SOURCE_DIR_SEARCH_DEPTH {
+        if let Some(found) = level
+            .iter()
+            .find(|dir| project_path.join(dir).join("conf.py").is_file())
+        {
+            if found.as_os_str().is_empty() {
+                return Some(".".to_string());
+            }
+            return Some(found.to_string_lossy().replace('\\', "/"));
+        }
+        level = level
+            .iter()
+            .flat_map(|dir| source_dir_children(project_path, dir))
+            .collect();
+    }
+    None
+}
+
+/// sphinx.source_dirにconf.pyが無い時の確認結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingSourceDir {
+    /// プロジェクト内で見つけたconf.pyのディレクトリ
+    pub suggestion: Option<String>,
+}
+
+/// sphinx.source_dirにconf.pyがあるか確認し、無ければ代わりのディレクトリを探す
+pub fn check_source_dir(project_path: &Path, source_dir: &str) -> Option<MissingSourceDir> {
+    if project_path.join(source_dir).join("conf.py").is_file() {
+        return None;
+    }
+    Some(MissingSourceDir {
+        suggestion: detect_source_dir(project_path),
+    })
+}
+
+/// theme_fileのパスを解決
+/// 相対パスはプロジェクトルートを優先し、そこに無ければ設定ディレクトリを基準にする
+pub fn resolve_theme_path(
+    theme_file: &str,
+    project_path: Option<&Path>,
+    config_dir: Option<&Path>,
+) -> PathBuf {
+    let expanded = expand_path(theme_file);
+    if !expanded.is_relative() {
+        return expanded;
+    }
+    if let Some(project) = project_path {
+        let path = project.join(&expanded);
+        if path.exists() || config_dir.is_none() {
+            return path;
         }
     }
+    match config_dir {
+        Some(dir) => dir.join(expanded),
+        None => expanded,
+    }
+}
+
+/// terminal.background_imageとして読み込める拡張子
+const BACKGROUND_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+
+/// ターミナルの背景画像を読み込む
+/// パスはフロントエンドから渡されるので、画像以外のファイルは読まない
+pub fn read_background_image(path: &Path) -> Result<Vec<u8>, String> {
+    let is_image = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            BACKGROUND_IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+        });
+    if !is_image {
+        return Err(format!(
+            "背景画像には{}のファイルを指定してください: {}",
+            BACKGROUND_IMAGE_EXTENSIONS.join("/"),
+            path.display()
+        ));
+    }
+    std::fs::read(path).map_err(|e| format!("背景画像の読み込みに失敗: {} ({})", e, path.display()))
+}
+
+/// ライト/ダークテーマ用のテーマファイルを読み込む（color_scheme_light / darkが未設定の場合のみ）
+fn resolve_theme_variant(
+    scheme: &mut Option<ColorScheme>,
+    theme_file: Option<&str>,
+    project_path: Option<&Path>,
+    config_dir: Option<&Path>,
+) {
+    let Some(theme_file) = theme_file.filter(|_| scheme.is_none()) else {
+        return;
+    };
+    match load_theme_file(&resolve_theme_path(theme_file, project_path, config_dir)) {
+        Ok(loaded) => *scheme = Some(loaded),
+        Err(e) => eprintln!("テーマファイル読み込みエラー: {}", e),
+    }
+}
+
+/// ライト/ダークテーマ用のカラースキームにもパレットを適用
+fn apply_palette_to_variants(
+    light: &mut Option<ColorScheme>,
+    dark: &mut Option<ColorScheme>,
+    palette: &[String],
+) {
+    for scheme in [light, dark] {
+        *scheme = scheme.take().map(|s| s.with_palette(palette.to_vec()));
+    }
 }
 
 impl TerminalConfig {
     /// theme_fileからカラースキームを解決
     /// color_schemeが設定済みの場合はそのまま、
     /// theme_fileが設定されている場合はファイルを読み込んでcolor_schemeに変換
-    pub fn resolve_color_scheme(&mut self, base_path: Option<&std::path::Path>) {
+    pub fn resolve_color_scheme(&mut self, project_path: Option<&Path>, config_dir: Option<&Path>) {
+        // ライト/ダークテーマ用のテーマファイル（OSのテーマに合わせてフロントエンドで選ぶ）
+        resolve_theme_variant(
+            &mut self.color_scheme_light,
+            self.theme_file_light.as_deref(),
+            project_path,
+            config_dir,
+        );
+        resolve_theme_variant(
+            &mut self.color_scheme_dark,
+            self.theme_file_dark.as_deref(),
+            project_path,
+            config_dir,
+        );
+
         // color_schemeが既に設定されている場合はそのまま
         if self.color_scheme.is_some() {
             return;
@@ -126,11 +669,7 @@ impl TerminalConfig {
 
         // theme_fileが設定されている場合はファイルを読み込む
         if let Some(ref theme_file) = self.theme_file {
-            let theme_path = if let Some(base) = base_path {
-                base.join(theme_file)
-            } else {
-                PathBuf::from(theme_file)
-            };
+            let theme_path = resolve_theme_path(theme_file, project_path, config_dir);
 
             match load_theme_file(&theme_path) {
                 Ok(scheme) => {
@@ -142,6 +681,40 @@ impl TerminalConfig {
             }
         }
     }
+
+    /// palette_fileを読み込んでカラースキームに適用（resolve_color_schemeの後に呼ぶ）
+    /// 相対パスはtheme_fileと同じ規則で解決
+    pub fn resolve_palette(&mut self, project_path: Option<&Path>, config_dir: Option<&Path>) {
+        if let Some(ref palette_file) = self.palette_file {
+            let palette_path = resolve_theme_path(palette_file, project_path, config_dir);
+            match load_palette_file(&palette_path) {
+                Ok(palette) => {
+                    apply_palette_to_variants(
+                        &mut self.color_scheme_light,
+                        &mut self.color_scheme_dark,
+                        &palette,
+                    );
+                    let scheme = self.color_scheme.take().unwrap_or_default();
+                    self.color_scheme = Some(scheme.with_palette(palette));
+                }
+                Err(e) => {
+                    eprintln!("パレットファイル読み込みエラー: {}", e);
+                }
+            }
+        }
+    }
+
+    /// background_imageの相対パスをtheme_fileと同じ規則で絶対パスに解決
+    pub fn resolve_background_image(
+        &mut self,
+        project_path: Option<&Path>,
+        config_dir: Option<&Path>,
+    ) {
+        if let Some(ref image) = self.background_image {
+            let path = resolve_theme_path(image, project_path, config_dir);
+            self.background_image = Some(path.to_string_lossy().into_owned());
+        }
+    }
 }
 
 impl Config {
@@ -162,15 +735,159 @@ impl Config {
 
     /// 設定ファイルのパスを取得
     /// XDG_CONFIG_HOME/khafre/config.toml または ~/.config/khafre/config.toml
-    fn config_path() -> PathBuf {
+    pub fn config_path() -> PathBuf {
         let config_dir = std::env::var("XDG_CONFIG_HOME")
             .map(PathBuf::from)
             .unwrap_or_else(|_| dirs::home_dir().unwrap_or_default().join(".config"));
 
         config_dir.join("khafre").join("config.toml")
     }
+
+    /// ui.split_ratioを設定ファイルに保存（他の設定やコメントはそのまま残す）
+    /// 解決済みの設定を書き戻すと、color_schemeの展開や絶対パスがファイルに残るので、値だけを書き換える
+    pub fn save_split_ratio(ratio: f64) -> Result<(), String> {
+        update_config_file(
+            &Self::config_path(),
+            "ui",
+            "split_ratio",
+            &toml::Value::Float(ratio),
+        )
+    }
+
+    /// 設定ファイルの場所と作成済みか（初回起動の案内用）
+    pub fn user_config_file() -> UserConfigFile {
+        UserConfigFile::at(&Self::config_path())
+    }
+
+    /// 初回起動の案内を終えたことを設定ファイルの隣に記録
+    pub fn complete_onboarding() -> Result<(), String> {
+        mark_onboarded(&Self::config_path())
+    }
+}
+
+/// 初回起動の案内を終えた印のファイル（設定ファイルと同じディレクトリに置く）
+const ONBOARDED_FILE: &str = ".onboarded";
+
+/// config_pathの隣に案内を終えた印を作る（ディレクトリが無ければ作る）
+fn mark_onboarded(config_path: &Path) -> Result<(), String> {
+    let marker = config_path.with_file_name(ONBOARDED_FILE);
+    if let Some(dir) = marker.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("設定ディレクトリの作成に失敗: {}", e))?;
+    }
+    std::fs::write(&marker, "").map_err(|e| format!("初回起動の記録に失敗: {}", e))
+}
+
+/// 設定ファイルの[table]のkeyを書き換える（ファイルが無ければ作る）
+/// 設定として読めなくなる値（型の違いなど）は書き込まない
+fn update_config_file(
+    path: &Path,
+    table: &str,
+    key: &str,
+    value: &toml::Value,
+) -> Result<(), String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("設定ファイルの読み込みに失敗: {}", e)),
+    };
+    let updated = set_toml_value(&content, table, key, value)?;
+    toml::from_str::<Config>(&updated)
+        .map_err(|e| format!("設定ファイルの更新に失敗: {}.{}: {}", table, key, e))?;
+    write_config_file(path, &updated)
+}
+
+/// 設定ファイルを書き込む（ディレクトリが無ければ作る）
+/// 途中で落ちても元のファイルが壊れないよう、同じディレクトリの一時ファイルに書いてから置き換える
+fn write_config_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("設定ディレクトリの作成に失敗: {}", e))?;
+    }
+    let tmp_path = path.with_extension("toml.tmp");
+    std::fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            format!("設定ファイルの保存に失敗: {}", e)
+        })
+}
+
+/// TOMLの[table]にあるkeyの値を書き換えたテキストを返す（無ければ追加する）
+/// 他の行はコメントも含めてそのまま残す
+fn set_toml_value(
+    content: &str,
+    table: &str,
+    key: &str,
+    value: &toml::Value,
+) -> Result<String, String> {
+    let header = format!("[{}]", table);
+    let assignment = format!("{} = {}", key, value);
+    // 行末のコメントを除いた中身
+    let code = |line: &str| line.split('#').next().unwrap_or("").trim().to_string();
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    match lines.iter().position(|line| code(line) == header) {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| code(line).starts_with('['))
+                .map_or(lines.len(), |offset| start + 1 + offset);
+            let existing = (start + 1..end).find(|&i| {
+                code(&lines[i])
+                    .split_once('=')
+                    .is_some_and(|(name, _)| name.trim() == key)
+            });
+            match existing {
+                Some(i) => lines[i] = assignment,
+                None => lines.insert(start + 1, assignment),
+            }
+        }
+        None => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(header);
+            lines.push(assignment);
+        }
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+
+    // 書き換えた結果が読めて、値が反映されていることを確認
+    let parsed: toml::Table = updated
+        .parse()
+        .map_err(|e| format!("設定ファイルの更新に失敗: {}", e))?;
+    if parsed.get(table).and_then(|t| t.get(key)) != Some(value) {
+        return Err(format!("設定ファイルの更新に失敗: {}.{}", table, key));
+    }
+    Ok(updated)
+}
+
+/// ユーザー設定ファイルの状態
+#[derive(Debug, Clone, Serialize)]
+pub struct UserConfigFile {
+    pub path: String,
+    pub exists: bool,
+    /// 初回起動の案内を終えたか
+    pub onboarded: bool,
+}
+
+impl UserConfigFile {
+    fn at(config_path: &Path) -> Self {
+        Self {
+            path: config_path.to_string_lossy().into_owned(),
+            exists: config_path.exists(),
+            onboarded: config_path.with_file_name(ONBOARDED_FILE).exists(),
+        }
+    }
 }
 
+/// プロジェクトごとの設定ファイル（プロジェクトルートに置き、グローバル設定を上書きする）
+pub const PROJECT_CONFIG_FILE: &str = ".khafre.toml";
+
 /// ローカル開発用設定
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DevConfig {
@@ -196,6 +913,12 @@ pub struct ConfigOverride {
     pub editor: Option<EditorConfigOverride>,
     #[serde(default)]
     pub terminal: Option<TerminalConfigOverride>,
+    #[serde(default)]
+    pub preview: Option<PreviewConfigOverride>,
+    #[serde(default)]
+    pub log: Option<LogConfigOverride>,
+    #[serde(default)]
+    pub ui: Option<UiConfigOverride>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -208,6 +931,22 @@ pub struct SphinxConfigOverride {
     pub server: Option<ServerConfigOverride>,
     #[serde(default)]
     pub extra_args: Option<Vec<String>>,
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+    #[serde(default)]
+    pub success_patterns: Option<Vec<String>>,
+    #[serde(default)]
+    pub error_patterns: Option<Vec<String>>,
+    #[serde(default)]
+    pub health_check_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub health_check_failures: Option<u32>,
+    #[serde(default)]
+    pub env: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub inherit_env: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -220,12 +959,16 @@ pub struct ServerConfigOverride {
 pub struct PythonConfigOverride {
     #[serde(default)]
     pub interpreter: Option<String>,
+    #[serde(default)]
+    pub runner: Option<PythonRunner>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct EditorConfigOverride {
     #[serde(default)]
     pub command: Option<String>,
+    #[serde(default)]
+    pub line_format: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -239,14 +982,108 @@ pub struct TerminalConfigOverride {
     #[serde(default)]
     pub theme_file: Option<String>,
     #[serde(default)]
+    pub palette_file: Option<String>,
+    #[serde(default)]
     pub color_scheme: Option<ColorScheme>,
+    #[serde(default)]
+    pub theme_file_light: Option<String>,
+    #[serde(default)]
+    pub theme_file_dark: Option<String>,
+    #[serde(default)]
+    pub color_scheme_light: Option<ColorScheme>,
+    #[serde(default)]
+    pub color_scheme_dark: Option<ColorScheme>,
+    #[serde(default)]
+    pub bold_is_bright: Option<bool>,
+    #[serde(default)]
+    pub bold_font_weight: Option<bool>,
+    #[serde(default)]
+    pub padding: Option<u16>,
+    #[serde(default)]
+    pub on_exit: Option<TerminalExitAction>,
+    #[serde(default)]
+    pub min_cols: Option<u16>,
+    #[serde(default)]
+    pub min_rows: Option<u16>,
+    #[serde(default)]
+    pub cursor_color: Option<String>,
+    #[serde(default)]
+    pub cursor_text_color: Option<String>,
+    #[serde(default)]
+    pub prompt_pattern: Option<String>,
+    #[serde(default)]
+    pub word_separators: Option<String>,
+    #[serde(default)]
+    pub scrollback_lines: Option<u32>,
+    #[serde(default)]
+    pub scroll_on_output: Option<ScrollOnOutput>,
+    #[serde(default)]
+    pub renderer: Option<TerminalRenderer>,
+    #[serde(default)]
+    pub tab_width: Option<u16>,
+    #[serde(default)]
+    pub background_opacity: Option<f32>,
+    #[serde(default)]
+    pub background_image: Option<String>,
+    #[serde(default)]
+    pub smart_cursor_contrast: Option<bool>,
+    #[serde(default)]
+    pub visual_bell: Option<bool>,
+    #[serde(default)]
+    pub preserve_scrollback_on_reload: Option<bool>,
+    #[serde(default)]
+    pub enter_copies_selection: Option<bool>,
+    #[serde(default)]
+    pub rerun_history_fallback: Option<bool>,
+    #[serde(default)]
+    pub copy: Option<CopyConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PreviewConfigOverride {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub mode: Option<PreviewMode>,
+    #[serde(default)]
+    pub open_browser_on_ready: Option<bool>,
+    #[serde(default)]
+    pub outline: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LogConfigOverride {
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UiConfigOverride {
+    #[serde(default)]
+    pub status_bar: Option<StatusBarMode>,
+    #[serde(default)]
+    pub split_ratio: Option<f64>,
 }
 
 impl TerminalConfigOverride {
     /// theme_fileからカラースキームを解決
     /// color_schemeが設定済みの場合はそのまま、
     /// theme_fileが設定されている場合はファイルを読み込んでcolor_schemeに変換
-    pub fn resolve_color_scheme(&mut self) {
+    pub fn resolve_color_scheme(&mut self, project_path: Option<&Path>) {
+        // ライト/ダークテーマ用のテーマファイル（相対パスはDevConfigのproject_path基準）
+        resolve_theme_variant(
+            &mut self.color_scheme_light,
+            self.theme_file_light.as_deref(),
+            project_path,
+            None,
+        );
+        resolve_theme_variant(
+            &mut self.color_scheme_dark,
+            self.theme_file_dark.as_deref(),
+            project_path,
+            None,
+        );
+
         // color_schemeが既に設定されている場合はそのまま
         if self.color_scheme.is_some() {
             return;
@@ -254,8 +1091,8 @@ impl TerminalConfigOverride {
 
         // theme_fileが設定されている場合はファイルを読み込む
         if let Some(ref theme_file) = self.theme_file {
-            // DevConfigのtheme_fileは絶対パスを想定
-            let theme_path = PathBuf::from(theme_file);
+            // 相対パスはDevConfigのproject_pathを基準にする
+            let theme_path = resolve_theme_path(theme_file, project_path, None);
 
             match load_theme_file(&theme_path) {
                 Ok(scheme) => {
@@ -267,16 +1104,73 @@ impl TerminalConfigOverride {
             }
         }
     }
-}
 
-fn default_auto_start_sphinx() -> bool {
-    true
-}
-
-impl DevConfig {
-    /// アプリのルートから.khafre.dev.jsonを読み込む
-    /// カレントディレクトリと親ディレクトリを順に探索
-    pub fn load() -> Option<Self> {
+    /// palette_fileを読み込んでカラースキームに適用（相対パスはDevConfigのproject_path基準）
+    pub fn resolve_palette(&mut self, project_path: Option<&Path>) {
+        if let Some(ref palette_file) = self.palette_file {
+            let palette_path = resolve_theme_path(palette_file, project_path, None);
+            match load_palette_file(&palette_path) {
+                Ok(palette) => {
+                    apply_palette_to_variants(
+                        &mut self.color_scheme_light,
+                        &mut self.color_scheme_dark,
+                        &palette,
+                    );
+                    let scheme = self.color_scheme.take().unwrap_or_default();
+                    self.color_scheme = Some(scheme.with_palette(palette));
+                }
+                Err(e) => {
+                    eprintln!("パレットファイル読み込みエラー: {}", e);
+                }
+            }
+        }
+    }
+
+    /// background_imageの相対パスをDevConfigのproject_path基準で解決
+    pub fn resolve_background_image(&mut self, project_path: Option<&Path>) {
+        if let Some(ref image) = self.background_image {
+            let path = resolve_theme_path(image, project_path, None);
+            self.background_image = Some(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+fn default_auto_start_sphinx() -> bool {
+    true
+}
+
+impl ConfigOverride {
+    /// プロジェクトの.khafre.tomlを読み込む（無ければNone）
+    pub fn load_project(project_path: &Path) -> Result<Option<Self>, String> {
+        let path = project_path.join(PROJECT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}の読み込みに失敗: {}", PROJECT_CONFIG_FILE, e))?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("{}のパースに失敗: {}", PROJECT_CONFIG_FILE, e))
+    }
+
+    /// sphinx.source_dirをプロジェクトの.khafre.tomlに保存（他の設定やコメントはそのまま残す）
+    pub fn save_project_source_dir(project_path: &Path, source_dir: &str) -> Result<(), String> {
+        let path = project_path.join(PROJECT_CONFIG_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("{}の読み込みに失敗: {}", PROJECT_CONFIG_FILE, e)),
+        };
+        let value = toml::Value::String(source_dir.to_string());
+        let updated = set_toml_value(&content, "sphinx", "source_dir", &value)?;
+        write_config_file(&path, &updated)
+    }
+}
+
+impl DevConfig {
+    /// アプリのルートから.khafre.dev.jsonを読み込む
+    /// カレントディレクトリと親ディレクトリを順に探索
+    pub fn load() -> Option<Self> {
         let current_dir = std::env::current_dir().ok()?;
 
         // カレントディレクトリと親ディレクトリを順に探索
@@ -300,6 +1194,7 @@ impl DevConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{fixture, test_dir, write_file};
 
     #[test]
     fn test_default_config() {
@@ -307,9 +1202,204 @@ mod tests {
         assert_eq!(config.sphinx.source_dir, "docs");
         assert_eq!(config.sphinx.build_dir, "_build/html");
         assert_eq!(config.sphinx.server.port, 0);
+        assert_eq!(config.sphinx.idle_timeout_secs, 0);
+        assert_eq!(config.sphinx.max_restarts, 3);
+        assert_eq!(
+            config.sphinx.success_patterns,
+            vec!["build succeeded", "waiting for changes"]
+        );
+        assert_eq!(config.sphinx.error_patterns, vec!["ERROR", "error:"]);
+        assert_eq!(config.sphinx.health_check_interval_secs, 10);
+        assert_eq!(config.sphinx.health_check_failures, 3);
+        assert!(config.sphinx.env.is_empty());
+        assert!(config.sphinx.inherit_env);
         assert_eq!(config.python.interpreter, "python");
         assert_eq!(config.editor.command, "nvim");
         assert!(config.terminal.shell.is_none());
+        assert!(config.preview.enabled);
+        assert_eq!(config.preview.mode, PreviewMode::Embedded);
+        assert!(!config.preview.open_browser_on_ready);
+        assert!(!config.preview.outline);
+        assert_eq!(config.log.level, LogLevel::Info);
+        assert_eq!(config.ui.status_bar, StatusBarMode::Full);
+    }
+
+    #[test]
+    fn test_parse_preview_mode() {
+        let toml_str = r#"
+            [preview]
+            mode = "external"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.preview.mode, PreviewMode::External);
+    }
+
+    #[test]
+    fn test_parse_preview_outline() {
+        let toml_str = r#"
+            [preview]
+            outline = true
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.preview.outline);
+    }
+
+    #[test]
+    fn test_parse_open_browser_on_ready() {
+        let toml_str = r#"
+            [preview]
+            open_browser_on_ready = true
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.preview.open_browser_on_ready);
+        assert_eq!(config.preview.mode, PreviewMode::Embedded);
+    }
+
+    #[test]
+    fn test_parse_preview_disabled() {
+        // modeだけ指定した場合は有効のまま
+        let config: Config = toml::from_str("[preview]\nmode = \"external\"").unwrap();
+        assert!(config.preview.enabled);
+
+        let toml_str = r#"
+            [preview]
+            enabled = false
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(!config.preview.enabled);
+    }
+
+    #[test]
+    fn test_parse_log_level() {
+        let toml_str = r#"
+            [log]
+            level = "debug"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.log.level, LogLevel::Debug);
+    }
+
+    #[test]
+    fn test_parse_status_bar() {
+        let config: Config = toml::from_str("[ui]\nstatus_bar = \"minimal\"").unwrap();
+        assert_eq!(config.ui.status_bar, StatusBarMode::Minimal);
+
+        let config: Config = toml::from_str("[ui]\nstatus_bar = \"hidden\"").unwrap();
+        assert_eq!(config.ui.status_bar, StatusBarMode::Hidden);
+
+        assert!(toml::from_str::<Config>("[ui]\nstatus_bar = \"compact\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_split_ratio() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.ui.split_ratio, None);
+
+        let config: Config = toml::from_str("[ui]\nsplit_ratio = 62.5").unwrap();
+        assert_eq!(config.ui.split_ratio, Some(62.5));
+    }
+
+    #[test]
+    fn test_set_toml_value() {
+        let value = toml::Value::Float(62.5);
+        let set = |content: &str| set_toml_value(content, "ui", "split_ratio", &value).unwrap();
+
+        // 既存の値を書き換え、コメントと他の設定は残す
+        assert_eq!(
+            set("# my config\n[ui]\nstatus_bar = \"minimal\"\nsplit_ratio = 40.0 # old\n\n[sphinx]\n"),
+            "# my config\n[ui]\nstatus_bar = \"minimal\"\nsplit_ratio = 62.5\n\n[sphinx]\n"
+        );
+
+        // テーブルはあるがキーが無い
+        assert_eq!(
+            set("[ui] # display\nstatus_bar = \"full\"\n"),
+            "[ui] # display\nsplit_ratio = 62.5\nstatus_bar = \"full\"\n"
+        );
+
+        // テーブルが無い
+        assert_eq!(
+            set("[sphinx]\nsource_dir = \"docs\""),
+            "[sphinx]\nsource_dir = \"docs\"\n\n[ui]\nsplit_ratio = 62.5\n"
+        );
+        assert_eq!(set(""), "[ui]\nsplit_ratio = 62.5\n");
+
+        // コメントアウトされた行は書き換えない
+        assert_eq!(
+            set("[ui]\n# split_ratio = 30.0\n"),
+            "[ui]\nsplit_ratio = 62.5\n# split_ratio = 30.0\n"
+        );
+    }
+
+    #[test]
+    fn test_set_toml_value_rejects_broken_result() {
+        // 書き換え後に読めない設定ファイルは保存しない
+        let value = toml::Value::Float(62.5);
+        assert!(set_toml_value("[ui\nfoo = 1\n", "ui", "split_ratio", &value).is_err());
+    }
+
+    #[test]
+    fn test_write_config_file() {
+        let dir = test_dir("save");
+        let path = dir.join("khafre/config.toml");
+
+        // ディレクトリが無くても作って書き込む
+        let mut config = Config::default();
+        config.editor.command = "vim".to_string();
+        config.ui.split_ratio = Some(40.0);
+        config
+            .sphinx
+            .env
+            .insert("SPHINXOPTS".to_string(), "-W".to_string());
+        write_config_file(&path, &toml::to_string_pretty(&config).unwrap()).unwrap();
+
+        // 書き戻した設定を読み込める
+        let saved: Config = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.editor.command, "vim");
+        assert_eq!(saved.ui.split_ratio, Some(40.0));
+        assert_eq!(saved.sphinx.build_dir, "_build/html");
+        assert_eq!(saved.sphinx.env, config.sphinx.env);
+
+        // 既存のファイルを置き換え、一時ファイルは残さない
+        write_config_file(&path, "[editor]\ncommand = \"nvim\"\n").unwrap();
+        let saved: Config = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.editor.command, "nvim");
+        assert!(!path.with_extension("toml.tmp").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_config_file() {
+        let dir = test_dir("update");
+        let path = dir.join("khafre/config.toml");
+
+        // ファイルが無ければ作る
+        let font_size = toml::Value::Integer(16);
+        update_config_file(&path, "terminal", "font_size", &font_size).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[terminal]\nfont_size = 16\n"
+        );
+
+        // 書き換えたキー以外（コメントや相対パス）はそのまま残す
+        let content =
+            "# my config\n[terminal]\ntheme_file = \"themes/dracula.toml\"\nfont_size = 16\n";
+        std::fs::write(&path, content).unwrap();
+        let font_size = toml::Value::Integer(18);
+        update_config_file(&path, "terminal", "font_size", &font_size).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            content.replace("font_size = 16", "font_size = 18")
+        );
+
+        // 設定として読めない値は書き込まない
+        let invalid = toml::Value::String("large".to_string());
+        assert!(update_config_file(&path, "terminal", "font_size", &invalid).is_err());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("font_size = 18"));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
@@ -331,10 +1421,21 @@ mod tests {
             [sphinx]
             source_dir = "docs/source"
             build_dir = "docs/_build"
+            idle_timeout_secs = 600
+            max_restarts = 5
+            success_patterns = ["[custom] done"]
+            error_patterns = ["FAILED", "Traceback"]
+            health_check_interval_secs = 0
+            health_check_failures = 5
+            inherit_env = false
 
             [sphinx.server]
             port = 8080
 
+            [sphinx.env]
+            SPHINXOPTS = "-W"
+            PYTHONPATH = "src"
+
             [python]
             interpreter = ".venv/bin/python"
 
@@ -348,6 +1449,15 @@ mod tests {
         assert_eq!(config.sphinx.source_dir, "docs/source");
         assert_eq!(config.sphinx.build_dir, "docs/_build");
         assert_eq!(config.sphinx.server.port, 8080);
+        assert_eq!(config.sphinx.idle_timeout_secs, 600);
+        assert_eq!(config.sphinx.max_restarts, 5);
+        assert_eq!(config.sphinx.success_patterns, vec!["[custom] done"]);
+        assert_eq!(config.sphinx.error_patterns, vec!["FAILED", "Traceback"]);
+        assert_eq!(config.sphinx.health_check_interval_secs, 0);
+        assert_eq!(config.sphinx.health_check_failures, 5);
+        assert_eq!(config.sphinx.env["SPHINXOPTS"], "-W");
+        assert_eq!(config.sphinx.env["PYTHONPATH"], "src");
+        assert!(!config.sphinx.inherit_env);
         assert_eq!(config.python.interpreter, ".venv/bin/python");
         assert_eq!(config.editor.command, "vim");
         assert_eq!(
@@ -365,6 +1475,26 @@ mod tests {
         std::env::remove_var("XDG_CONFIG_HOME");
     }
 
+    #[test]
+    fn test_user_config_file_and_onboarding() {
+        let dir = test_dir("onboarding");
+        let path = dir.join("khafre/config.toml");
+
+        // 初回起動の案内は設定ファイルが無いことで判定する
+        let file = UserConfigFile::at(&path);
+        assert_eq!(file.path, path.to_string_lossy());
+        assert!(!file.exists);
+        assert!(!file.onboarded);
+
+        // 案内を終えたことは設定ファイルの隣に残す（設定ファイルは作らない）
+        mark_onboarded(&path).unwrap();
+        let file = UserConfigFile::at(&path);
+        assert!(!file.exists);
+        assert!(file.onboarded);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_dev_config_parse_camel_case() {
         // ユーザーが使用するキャメルケースのJSONをパースできるか確認
@@ -416,6 +1546,198 @@ mod tests {
         assert_eq!(config.terminal.font_size, Some(16));
     }
 
+    #[test]
+    fn test_parse_terminal_bold_config() {
+        let toml_str = r#"
+            [terminal]
+            bold_is_bright = false
+            bold_font_weight = true
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.bold_is_bright, Some(false));
+        assert_eq!(config.terminal.bold_font_weight, Some(true));
+    }
+
+    #[test]
+    fn test_control_config() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(!config.control.enabled);
+        assert_eq!(config.control.socket_path, None);
+
+        let toml_str = r#"
+            [control]
+            enabled = true
+            socket_path = "/tmp/khafre-test.sock"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.control.enabled);
+        assert_eq!(
+            config.control.socket_path,
+            Some("/tmp/khafre-test.sock".to_string())
+        );
+    }
+
+    #[test]
+    fn test_terminal_on_exit() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.terminal.on_exit, None);
+
+        let toml_str = r#"
+            [terminal]
+            on_exit = "respawn"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.on_exit, Some(TerminalExitAction::Respawn));
+
+        let toml_str = r#"
+            [terminal]
+            on_exit = "restart"
+        "#;
+        assert!(toml::from_str::<Config>(toml_str).is_err());
+    }
+
+    #[test]
+    fn test_terminal_scroll_on_output() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.terminal.scroll_on_output, None);
+        assert_eq!(ScrollOnOutput::default(), ScrollOnOutput::IfAtBottom);
+
+        let toml_str = r#"
+            [terminal]
+            scroll_on_output = "if_at_bottom"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.terminal.scroll_on_output,
+            Some(ScrollOnOutput::IfAtBottom)
+        );
+
+        let toml_str = r#"
+            [terminal]
+            scroll_on_output = "sometimes"
+        "#;
+        assert!(toml::from_str::<Config>(toml_str).is_err());
+    }
+
+    #[test]
+    fn test_terminal_renderer() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.terminal.renderer, None);
+        assert_eq!(TerminalRenderer::default(), TerminalRenderer::Dom);
+
+        let toml_str = r#"
+            [terminal]
+            renderer = "webgl"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.renderer, Some(TerminalRenderer::Webgl));
+
+        let toml_str = r#"
+            [terminal]
+            renderer = "canvas"
+        "#;
+        assert!(toml::from_str::<Config>(toml_str).is_err());
+    }
+
+    #[test]
+    fn test_python_runner() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.python.runner, PythonRunner::Auto);
+
+        let toml_str = r#"
+            [python]
+            runner = "direct"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.python.runner, PythonRunner::Direct);
+        assert_eq!(config.python.interpreter, "python");
+    }
+
+    #[test]
+    fn test_terminal_copy_config() {
+        let toml_str = r#"
+            [terminal.copy]
+            trim_trailing = false
+            strip_prompt = "^\\$ "
+            join_wrapped = false
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let copy = config.terminal.copy.unwrap();
+        assert_eq!(copy.trim_trailing, Some(false));
+        assert_eq!(copy.strip_prompt, Some("^\\$ ".to_string()));
+        assert_eq!(copy.join_wrapped, Some(false));
+    }
+
+    #[test]
+    fn test_terminal_min_size() {
+        let toml_str = r#"
+            [terminal]
+            min_cols = 40
+            min_rows = 10
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.min_cols, Some(40));
+        assert_eq!(config.terminal.min_rows, Some(10));
+    }
+
+    #[test]
+    fn test_terminal_cursor_colors() {
+        let toml_str = r##"
+            [terminal]
+            cursor_color = "#ffcc00"
+            cursor_text_color = "#000000"
+        "##;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.cursor_color, Some("#ffcc00".to_string()));
+        assert_eq!(
+            config.terminal.cursor_text_color,
+            Some("#000000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_terminal_prompt_pattern() {
+        let toml_str = r#"
+            [terminal]
+            prompt_pattern = "^λ "
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.prompt_pattern, Some("^λ ".to_string()));
+    }
+
+    #[test]
+    fn test_terminal_word_separators() {
+        let toml_str = r#"
+            [terminal]
+            word_separators = " ()[]{}'\"`"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.terminal.word_separators,
+            Some(" ()[]{}'\"`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_terminal_scrollback_lines() {
+        let toml_str = r#"
+            [terminal]
+            scrollback_lines = 50000
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.scrollback_lines, Some(50000));
+    }
+
+    #[test]
+    fn test_terminal_padding() {
+        let toml_str = r#"
+            [terminal]
+            padding = 6
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.padding, Some(6));
+    }
+
     #[test]
     fn test_parse_terminal_font_config_json() {
         // JSONでフォント設定がパースできるか確認
@@ -438,4 +1760,277 @@ mod tests {
         assert_eq!(terminal.font_family, Some("Fira Code".to_string()));
         assert_eq!(terminal.font_size, Some(18));
     }
+
+    fn project_with_theme(name: &str) -> PathBuf {
+        fixture(&format!("theme-{}", name), &[(".khafre/theme.toml", "")])
+    }
+
+    #[test]
+    fn test_resolve_theme_path_relative_to_project() {
+        let project = project_with_theme("relative");
+        let config_dir = Path::new("/config/khafre");
+        assert_eq!(
+            resolve_theme_path(".khafre/theme.toml", Some(&project), Some(config_dir)),
+            project.join(".khafre/theme.toml")
+        );
+        // プロジェクトに無いテーマは設定ディレクトリを基準にする
+        assert_eq!(
+            resolve_theme_path("themes/gruvbox.toml", Some(&project), Some(config_dir)),
+            config_dir.join("themes/gruvbox.toml")
+        );
+        // 設定ディレクトリが無ければ存在しなくてもプロジェクト基準
+        assert_eq!(
+            resolve_theme_path("themes/gruvbox.toml", Some(&project), None),
+            project.join("themes/gruvbox.toml")
+        );
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn test_resolve_theme_path_absolute() {
+        let project = project_with_theme("absolute");
+        assert_eq!(
+            resolve_theme_path("/etc/khafre/theme.toml", Some(&project), None),
+            PathBuf::from("/etc/khafre/theme.toml")
+        );
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn test_resolve_background_image() {
+        let project = project_with_theme("background");
+        let config_dir = Path::new("/config/khafre");
+        let mut config = TerminalConfig {
+            background_image: Some("images/bg.png".to_string()),
+            ..Default::default()
+        };
+        config.resolve_background_image(Some(&project), Some(config_dir));
+        let expected = config_dir.join("images/bg.png");
+        assert_eq!(
+            config.background_image,
+            Some(expected.to_string_lossy().into_owned())
+        );
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn test_read_background_image() {
+        let dir = fixture(
+            "background-image",
+            &[("bg.PNG", "png"), ("config.toml", "[python]")],
+        );
+        assert_eq!(read_background_image(&dir.join("bg.PNG")).unwrap(), b"png");
+        // 画像以外のファイルは中身を返さない
+        assert!(read_background_image(&dir.join("config.toml")).is_err());
+        assert!(read_background_image(&dir.join("missing.png")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_light_and_dark_theme_files() {
+        let project = project_with_theme("light-dark");
+        std::fs::write(
+            project.join(".khafre/light.toml"),
+            "[colors.primary]\nbackground = \"#ffffff\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.join(".khafre/dark.toml"),
+            "[colors.primary]\nbackground = \"#000000\"\n",
+        )
+        .unwrap();
+
+        let mut config = TerminalConfig {
+            theme_file_light: Some(".khafre/light.toml".to_string()),
+            theme_file_dark: Some(".khafre/dark.toml".to_string()),
+            ..Default::default()
+        };
+        config.resolve_color_scheme(Some(&project), None);
+        let background = |scheme: &Option<ColorScheme>| scheme.as_ref()?.background.clone();
+        assert_eq!(
+            background(&config.color_scheme_light),
+            Some("#ffffff".into())
+        );
+        assert_eq!(
+            background(&config.color_scheme_dark),
+            Some("#000000".into())
+        );
+        // theme_fileが無ければ共通のカラースキームは未設定のまま
+        assert!(config.color_scheme.is_none());
+
+        // インラインのカラースキームはテーマファイルより優先
+        let inline = ColorScheme {
+            background: Some("#fdf6e3".to_string()),
+            ..Default::default()
+        };
+        let mut config = TerminalConfig {
+            theme_file_light: Some(".khafre/light.toml".to_string()),
+            color_scheme_light: Some(inline.clone()),
+            ..Default::default()
+        };
+        config.resolve_color_scheme(Some(&project), None);
+        assert_eq!(config.color_scheme_light, Some(inline));
+        assert!(config.color_scheme_dark.is_none());
+
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn test_terminal_background() {
+        let toml_str = r#"
+            [terminal]
+            background_opacity = 0.8
+            background_image = "/images/bg.png"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.terminal.background_opacity, Some(0.8));
+        assert_eq!(
+            config.terminal.background_image,
+            Some("/images/bg.png".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_theme_path_home() {
+        let home = dirs::home_dir().unwrap();
+        let project = Path::new("/project");
+        assert_eq!(
+            resolve_theme_path("~/themes/nord.toml", Some(project), None),
+            home.join("themes/nord.toml")
+        );
+    }
+
+    #[test]
+    fn test_expand_path_env_vars() {
+        std::env::set_var("KHAFRE_TEST_THEME_DIR", "/shared/themes");
+        assert_eq!(
+            expand_path("$KHAFRE_TEST_THEME_DIR/nord.toml"),
+            PathBuf::from("/shared/themes/nord.toml")
+        );
+        assert_eq!(
+            expand_path("${KHAFRE_TEST_THEME_DIR}/nord.toml"),
+            PathBuf::from("/shared/themes/nord.toml")
+        );
+        // 未定義の変数や単独の$はそのまま
+        assert_eq!(
+            expand_path("$KHAFRE_TEST_UNDEFINED/a$/b"),
+            PathBuf::from("$KHAFRE_TEST_UNDEFINED/a$/b")
+        );
+        assert_eq!(
+            expand_path("${unterminated"),
+            PathBuf::from("${unterminated")
+        );
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let base = Path::new("/project");
+        assert_eq!(
+            resolve_path(base, ".venv/bin/python"),
+            base.join(".venv/bin/python")
+        );
+        assert_eq!(
+            resolve_path(base, "/usr/bin/python3"),
+            PathBuf::from("/usr/bin/python3")
+        );
+    }
+
+    #[test]
+    fn test_detect_python_interpreter() {
+        let dir = test_dir("venv");
+        let create = |path: &str| write_file(&dir.join(path), "");
+
+        create(VENV_INTERPRETERS[1]);
+        assert_eq!(
+            detect_python_interpreter(&dir),
+            Some(VENV_INTERPRETERS[1].to_string())
+        );
+        // .venvを優先する
+        create(VENV_INTERPRETERS[0]);
+        assert_eq!(
+            detect_python_interpreter(&dir),
+            Some(VENV_INTERPRETERS[0].to_string())
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_detect_source_dir() {
+        let dir = test_dir("source");
+        let create = |path: &str| write_file(&dir.join(path), "");
+        assert_eq!(detect_source_dir(&dir), None);
+
+        // ビルド出力や仮想環境の中は探さない
+        create("_build/html/conf.py");
+        create(".venv/lib/conf.py");
+        assert_eq!(detect_source_dir(&dir), None);
+
+        create("doc/source/conf.py");
+        assert_eq!(detect_source_dir(&dir), Some("doc/source".to_string()));
+        // 浅い場所を優先する
+        create("manual/conf.py");
+        assert_eq!(detect_source_dir(&dir), Some("manual".to_string()));
+        create("conf.py");
+        assert_eq!(detect_source_dir(&dir), Some(".".to_string()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_check_source_dir() {
+        let dir = fixture("check-source", &[("doc/conf.py", "")]);
+
+        assert_eq!(check_source_dir(&dir, "doc"), None);
+        assert_eq!(
+            check_source_dir(&dir, "docs"),
+            Some(MissingSourceDir {
+                suggestion: Some("doc".to_string())
+            })
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_project_config() {
+        let dir = test_dir("project-config");
+        assert!(ConfigOverride::load_project(&dir).unwrap().is_none());
+
+        // 既存の設定とコメントを残してsource_dirを書き込む
+        std::fs::write(
+            dir.join(PROJECT_CONFIG_FILE),
+            "# docs layout\n[sphinx]\nbuild_dir = \"build/html\"\n",
+        )
+        .unwrap();
+        ConfigOverride::save_project_source_dir(&dir, "doc/source").unwrap();
+        let content = std::fs::read_to_string(dir.join(PROJECT_CONFIG_FILE)).unwrap();
+        assert!(content.starts_with("# docs layout\n"));
+
+        let config = ConfigOverride::load_project(&dir).unwrap().unwrap();
+        let sphinx = config.sphinx.unwrap();
+        assert_eq!(sphinx.source_dir, Some("doc/source".to_string()));
+        assert_eq!(sphinx.build_dir, Some("build/html".to_string()));
+
+        // 読めない内容はエラーにする
+        std::fs::write(dir.join(PROJECT_CONFIG_FILE), "[sphinx\n").unwrap();
+        assert!(ConfigOverride::load_project(&dir).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_in_path() {
+        let dir = fixture("path", &[("bin/python3", "")]);
+        let bin = dir.join("bin");
+
+        let path_var = std::env::join_paths([dir.join("missing"), bin.clone()]).unwrap();
+        assert_eq!(
+            find_in_path("python3", &path_var),
+            Some(bin.join("python3"))
+        );
+        assert_eq!(find_in_path("python2", &path_var), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,100 @@
+//! 設定ファイルの変更監視
+//!
+//! 更新日時とサイズを定期的に確認し、変化が落ち着いたら通知する。
+//! エディタの保存途中（書きかけのファイル）を読まないよう、同じ値を2回続けて観測してから確定する。
+
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// 変更の確認間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// ファイルの状態（存在しなければNone）
+type FileStamp = Option<(SystemTime, u64)>;
+
+fn file_stamp(path: &Path) -> FileStamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// 観測値の変化を、落ち着いてから1回だけ報告する
+pub struct ChangeDetector<T> {
+    current: T,
+    pending: Option<T>,
+}
+
+impl<T: PartialEq> ChangeDetector<T> {
+    pub fn new(initial: T) -> Self {
+        Self {
+            current: initial,
+            pending: None,
+        }
+    }
+
+    /// 新しい観測値を渡す（変化した値を続けて2回観測したらtrue）
+    pub fn observe(&mut self, value: T) -> bool {
+        if value == self.current {
+            self.pending = None;
+            return false;
+        }
+        if self.pending.as_ref() == Some(&value) {
+            self.current = value;
+            self.pending = None;
+            return true;
+        }
+        self.pending = Some(value);
+        false
+    }
+}
+
+/// 設定ファイルを監視するスレッドを起動（アプリの終了まで動き続ける）
+pub fn watch<F>(path: PathBuf, on_change: F) -> thread::JoinHandle<()>
+where
+    F: Fn() + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut detector = ChangeDetector::new(file_stamp(&path));
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if detector.observe(file_stamp(&path)) {
+                on_change();
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_detector_waits_until_stable() {
+        let mut detector = ChangeDetector::new(1);
+        assert!(!detector.observe(1));
+
+        // 書き込み途中で値が変わり続ける間は報告しない
+        assert!(!detector.observe(2));
+        assert!(!detector.observe(3));
+        assert!(detector.observe(3));
+
+        // 確定した後は同じ値で再度報告しない
+        assert!(!detector.observe(3));
+        assert!(!detector.observe(3));
+    }
+
+    #[test]
+    fn test_change_detector_ignores_reverted_change() {
+        let mut detector = ChangeDetector::new(1);
+        assert!(!detector.observe(2));
+        assert!(!detector.observe(1));
+        assert!(!detector.observe(1));
+    }
+
+    #[test]
+    fn test_change_detector_reports_deletion() {
+        let mut detector = ChangeDetector::new(Some(1));
+        assert!(!detector.observe(None));
+        assert!(detector.observe(None));
+    }
+}
@@ -0,0 +1,379 @@
+//! 外部ツールからKhafreを操作するためのローカル制御ソケット
+//!
+//! プロトコル: 1行1JSONのコマンドを受け取り、1行1JSONのレスポンスを返す
+//!
+//! ```text
+//! {"command": "open_project", "path": "/path/to/project"}
+//! {"command": "start_preview"}
+//! {"command": "stop_preview"}
+//! {"command": "reload"}
+//! {"command": "navigate", "page": "guide/install.html"}
+//! {"command": "set_split_ratio", "ratio": 0.6}
+//! {"command": "set_split_orientation", "orientation": "vertical"}
+//! ```
+//!
+//! レスポンスは `{"ok": true}` または `{"ok": false, "error": "..."}`。
+//! コマンドはフロントエンドに転送した時点で成功とする（実行結果は待たない）
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+/// プレビューとターミナルの並べ方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitOrientation {
+    /// 左右に並べる
+    Horizontal,
+    /// 上下に並べる
+    Vertical,
+}
+
+/// 制御コマンド
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// プロジェクトを開く
+    OpenProject { path: String },
+    /// sphinx-autobuildを起動
+    StartPreview,
+    /// sphinx-autobuildを停止
+    StopPreview,
+    /// プレビューを再読み込み
+    Reload,
+    /// プレビューで指定ページを開く（サーバールートからの相対パス）
+    Navigate { page: String },
+    /// プレビュー側のペインの割合（0〜1、範囲外はフロントエンドで丸める）
+    SetSplitRatio { ratio: f64 },
+    /// ペインの並べ方を変更
+    SetSplitOrientation { orientation: SplitOrientation },
+}
+
+/// 制御コマンドへのレスポンス
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    fn ok() -> Self {
+        Self {
+            ok: true,
+            error: None,
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            ok: false,
+            error: Some(message),
+        }
+    }
+}
+
+/// 受け取ったコマンドの転送先
+pub type CommandSink = Arc<dyn Fn(ControlCommand) -> Result<(), String> + Send + Sync>;
+
+/// コマンドを "control_command" イベントとしてフロントエンドに転送するシンク
+pub fn app_command_sink(app_handle: AppHandle) -> CommandSink {
+    Arc::new(move |command| {
+        app_handle
+            .emit("control_command", command)
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// 制御ソケットのデフォルトパス
+/// $XDG_RUNTIME_DIR/khafre.sock（無い環境では一時ディレクトリ）
+pub fn default_socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("khafre.sock")
+}
+
+/// 1行分のリクエストを処理してレスポンスを返す
+pub fn handle_line(line: &str, sink: &CommandSink) -> ControlResponse {
+    let command: ControlCommand = match serde_json::from_str(line) {
+        Ok(command) => command,
+        Err(e) => return ControlResponse::error(format!("invalid command: {}", e)),
+    };
+    match sink(command) {
+        Ok(()) => ControlResponse::ok(),
+        Err(e) => ControlResponse::error(e),
+    }
+}
+
+/// 制御ソケットで待ち受けを開始
+/// 前回の異常終了で残ったソケットファイルは削除し、使用中の場合やソケット以外のファイルがある場合はエラーを返す
+#[cfg(unix)]
+pub fn serve(
+    path: &std::path::Path,
+    sink: CommandSink,
+) -> Result<std::thread::JoinHandle<()>, String> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        // 設定の書き間違いで普通のファイルを消さない
+        if !metadata.file_type().is_socket() {
+            return Err(format!(
+                "制御ソケットのパスにソケット以外のファイルがあります: {}",
+                path.display()
+            ));
+        }
+        if UnixStream::connect(path).is_ok() {
+            return Err(format!(
+                "制御ソケットは既に使用されています: {}",
+                path.display()
+            ));
+        }
+        std::fs::remove_file(path).map_err(|e| format!("古い制御ソケットの削除に失敗: {}", e))?;
+    }
+
+    // 自分以外のユーザーからは接続させない
+    // 権限を絞る前に接続されないよう、自分だけが入れるディレクトリで作ってから置き場所へ移す
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("制御ソケットのパスが不正です: {}", path.display()))?;
+    let private_dir = path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&private_dir);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .map_err(|e| format!("制御ソケットの作成に失敗: {}", e))?;
+    let staging = private_dir.join("control.sock");
+    let listener = UnixListener::bind(&staging)
+        .map_err(|e| format!("制御ソケットの作成に失敗: {}", e))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staging, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("制御ソケットの権限設定に失敗: {}", e))?;
+            std::fs::rename(&staging, path)
+                .map_err(|e| format!("制御ソケットの作成に失敗: {}", e))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&private_dir);
+    let listener = listener?;
+
+    let handle = std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let sink = sink.clone();
+            std::thread::spawn(move || handle_client(stream, &sink));
+        }
+    });
+    Ok(handle)
+}
+
+/// 制御ソケットは現状Unix系のみ対応
+#[cfg(not(unix))]
+pub fn serve(
+    _path: &std::path::Path,
+    _sink: CommandSink,
+) -> Result<std::thread::JoinHandle<()>, String> {
+    Err("制御ソケットはこのプラットフォームでは未対応です".to_string())
+}
+
+/// 接続ごとにリクエストを1行ずつ処理
+#[cfg(unix)]
+fn handle_client(stream: std::os::unix::net::UnixStream, sink: &CommandSink) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = handle_line(&line, sink);
+        let Ok(mut json) = serde_json::to_string(&response) else {
+            break;
+        };
+        json.push('\n');
+        if writer.write_all(json.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recording_sink() -> (CommandSink, Arc<Mutex<Vec<ControlCommand>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let recorder = received.clone();
+        let sink: CommandSink = Arc::new(move |command| {
+            recorder.lock().unwrap().push(command);
+            Ok(())
+        });
+        (sink, received)
+    }
+
+    #[test]
+    fn test_command_deserialization() {
+        let cases = [
+            (
+                r#"{"command": "open_project", "path": "/docs"}"#,
+                ControlCommand::OpenProject {
+                    path: "/docs".to_string(),
+                },
+            ),
+            (
+                r#"{"command": "start_preview"}"#,
+                ControlCommand::StartPreview,
+            ),
+            (
+                r#"{"command": "stop_preview"}"#,
+                ControlCommand::StopPreview,
+            ),
+            (r#"{"command": "reload"}"#, ControlCommand::Reload),
+            (
+                r#"{"command": "navigate", "page": "guide/install.html"}"#,
+                ControlCommand::Navigate {
+                    page: "guide/install.html".to_string(),
+                },
+            ),
+            (
+                r#"{"command": "set_split_ratio", "ratio": 0.6}"#,
+                ControlCommand::SetSplitRatio { ratio: 0.6 },
+            ),
+            (
+                r#"{"command": "set_split_orientation", "orientation": "vertical"}"#,
+                ControlCommand::SetSplitOrientation {
+                    orientation: SplitOrientation::Vertical,
+                },
+            ),
+        ];
+        for (json, expected) in cases {
+            let command: ControlCommand = serde_json::from_str(json).unwrap();
+            assert_eq!(command, expected);
+        }
+    }
+
+    #[test]
+    fn test_command_serialization_round_trip() {
+        let command = ControlCommand::Navigate {
+            page: "index.html".to_string(),
+        };
+        let json = serde_json::to_string(&command).unwrap();
+        assert_eq!(json, r#"{"command":"navigate","page":"index.html"}"#);
+        assert_eq!(
+            serde_json::from_str::<ControlCommand>(&json).unwrap(),
+            command
+        );
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        assert_eq!(
+            serde_json::to_string(&ControlResponse::ok()).unwrap(),
+            r#"{"ok":true}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ControlResponse::error("boom".to_string())).unwrap(),
+            r#"{"ok":false,"error":"boom"}"#
+        );
+    }
+
+    #[test]
+    fn test_handle_line_dispatches_command() {
+        let (sink, received) = recording_sink();
+        let response = handle_line(r#"{"command": "reload"}"#, &sink);
+        assert_eq!(response, ControlResponse::ok());
+        assert_eq!(*received.lock().unwrap(), vec![ControlCommand::Reload]);
+    }
+
+    #[test]
+    fn test_handle_line_rejects_invalid_commands() {
+        let (sink, received) = recording_sink();
+        for line in [
+            "not json",
+            r#"{"command": "format_disk"}"#,
+            r#"{"command": "open_project"}"#,
+            r#"{"command": "set_split_orientation", "orientation": "diagonal"}"#,
+        ] {
+            let response = handle_line(line, &sink);
+            assert!(!response.ok, "{} should be rejected", line);
+            assert!(response.error.is_some());
+        }
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_handle_line_reports_sink_errors() {
+        let sink: CommandSink = Arc::new(|_| Err("window closed".to_string()));
+        let response = handle_line(r#"{"command": "stop_preview"}"#, &sink);
+        assert_eq!(
+            response,
+            ControlResponse::error("window closed".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_serve_round_trip() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let dir = crate::test_util::test_dir("control");
+        let path = dir.join("control.sock");
+        // 前回の異常終了で残ったソケットファイルは置き換えられる
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let (sink, received) = recording_sink();
+        serve(&path, sink).unwrap();
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream
+            .write_all(b"{\"command\": \"start_preview\"}\n")
+            .unwrap();
+        let mut response = String::new();
+        BufReader::new(&stream).read_line(&mut response).unwrap();
+
+        assert_eq!(response, "{\"ok\":true}\n");
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![ControlCommand::StartPreview]
+        );
+
+        // 自分だけが接続でき、作成に使ったディレクトリは残さない
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        let private_dir = path.with_file_name(format!(".{}.{}", file_name, std::process::id()));
+        assert!(!private_dir.exists());
+
+        // 使用中のソケットには二重に待ち受けできない
+        let (sink, _) = recording_sink();
+        assert!(serve(&path, sink).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_serve_keeps_files_that_are_not_sockets() {
+        let dir = crate::test_util::test_dir("control-file");
+        let path = dir.join("notes.txt");
+        std::fs::write(&path, "notes").unwrap();
+
+        let (sink, _) = recording_sink();
+        assert!(serve(&path, sink).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,217 @@
+//! Sphinxビルドエラーを報告するためのMarkdownテンプレート
+
+use crate::sphinx::resolve_python_path;
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// 報告に含める情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueReport {
+    /// エラーメッセージ
+    pub error: String,
+    /// 実行したコマンド（Python実行ファイルと引数）
+    pub argv: Vec<String>,
+    /// `python --version` の出力
+    pub python_version: String,
+    /// OSとアーキテクチャ
+    pub os: String,
+    /// Khafreのバージョン
+    pub app_version: String,
+}
+
+impl IssueReport {
+    /// 実行環境の情報を集めて報告を作成
+    pub fn collect(
+        error: String,
+        project_path: &str,
+        python_path: &str,
+        args: Vec<OsString>,
+    ) -> Self {
+        let python = resolve_python_path(project_path, python_path)
+            .map(PathBuf::into_os_string)
+            .unwrap_or_else(|_| python_path.into());
+        let python_version = python_version(&python);
+
+        // 報告用の文字列なので、UTF-8でない部分は置き換えて表示
+        let argv = std::iter::once(python)
+            .chain(args)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+
+        Self {
+            error,
+            argv,
+            python_version,
+            os: format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// `python --version` の結果（取得できない場合は理由）
+fn python_version(python: impl AsRef<OsStr>) -> String {
+    match Command::new(python).arg("--version").output() {
+        // Python 2系はstderrに出力する
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let version = if stdout.trim().is_empty() {
+                stderr.trim()
+            } else {
+                stdout.trim()
+            };
+            if version.is_empty() {
+                "unknown".to_string()
+            } else {
+                version.to_string()
+            }
+        }
+        Err(e) => format!("unknown ({})", e),
+    }
+}
+
+/// シェルにそのまま貼り付けられるよう必要に応じて引数をクォート
+fn quote_arg(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// 内容に含まれるバッククォートの連続より長いコードフェンス
+fn code_fence(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// Issueにそのまま貼り付けられるMarkdownを作成
+pub fn format_issue_report(report: &IssueReport) -> String {
+    let error = report.error.trim();
+    let command = report
+        .argv
+        .iter()
+        .map(|arg| quote_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let error_fence = code_fence(error);
+    let command_fence = code_fence(&command);
+
+    format!(
+        "### Sphinx build error\n\
+         \n\
+         {error_fence}\n\
+         {error}\n\
+         {error_fence}\n\
+         \n\
+         **Command**\n\
+         \n\
+         {command_fence}sh\n\
+         {command}\n\
+         {command_fence}\n\
+         \n\
+         **Environment**\n\
+         \n\
+         - Python: {python}\n\
+         - OS: {os}\n\
+         - Khafre: {app}\n",
+        python = report.python_version,
+        os = report.os,
+        app = report.app_version,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> IssueReport {
+        IssueReport {
+            error: "ERROR: Unknown directive type \"autoclass\".\n".to_string(),
+            argv: vec![
+                "/project/.venv/bin/python".to_string(),
+                "-m".to_string(),
+                "sphinx_autobuild".to_string(),
+                "/project/docs".to_string(),
+                "/project/_build/html".to_string(),
+            ],
+            python_version: "Python 3.12.1".to_string(),
+            os: "macos (aarch64)".to_string(),
+            app_version: "0.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_issue_report() {
+        let expected = "\
+### Sphinx build error
+
+```
+ERROR: Unknown directive type \"autoclass\".
+```
+
+**Command**
+
+```sh
+/project/.venv/bin/python -m sphinx_autobuild /project/docs /project/_build/html
+```
+
+**Environment**
+
+- Python: Python 3.12.1
+- OS: macos (aarch64)
+- Khafre: 0.1.0
+";
+        assert_eq!(format_issue_report(&sample_report()), expected);
+    }
+
+    #[test]
+    fn test_format_quotes_arguments_with_spaces() {
+        let report = IssueReport {
+            argv: vec!["python".to_string(), "/My Docs/it's".to_string()],
+            ..sample_report()
+        };
+        assert!(format_issue_report(&report).contains("python '/My Docs/it'\\''s'\n"));
+    }
+
+    #[test]
+    fn test_format_fences_errors_containing_backticks() {
+        let report = IssueReport {
+            error: "invalid literal: ```python".to_string(),
+            ..sample_report()
+        };
+        let formatted = format_issue_report(&report);
+        assert!(formatted.contains("````\ninvalid literal: ```python\n````\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_python_version() {
+        use crate::test_util::{test_dir, write_script};
+
+        let dir = test_dir("pyversion");
+        let script = |name: &str, body: &str| {
+            let path = dir.join(name);
+            write_script(&path, &format!("#!/bin/sh\n{}\n", body));
+            path.to_string_lossy().to_string()
+        };
+
+        assert_eq!(
+            python_version(script("python3", "echo 'Python 3.12.1'")),
+            "Python 3.12.1"
+        );
+        // Python 2系はstderrに出力する
+        assert_eq!(
+            python_version(script("python2", "echo 'Python 2.7.18' >&2")),
+            "Python 2.7.18"
+        );
+        assert!(python_version("/nonexistent/python").starts_with("unknown"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
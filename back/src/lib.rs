@@ -1,11 +1,37 @@
+mod build_diff;
 mod color_scheme;
 mod config;
+mod config_watcher;
+mod control;
+mod issue_report;
+mod outline;
+mod preview;
+mod python_env;
+mod recording;
 mod sphinx;
 mod terminal;
+#[cfg(test)]
+mod test_util;
 
-use config::{Config, DevConfig};
-use sphinx::{create_sphinx_manager, SharedSphinxManager};
-use tauri::State;
+use build_diff::{build_and_diff, ChangedFile};
+use color_scheme::ColorScheme;
+use config::{
+    check_source_dir, detect_python_interpreter, expand_path, Config, ConfigOverride, DevConfig,
+    MissingSourceDir, PythonRunner, UserConfigFile,
+};
+use control::{app_command_sink, default_socket_path};
+use issue_report::{format_issue_report, IssueReport};
+use outline::{read_outline, Heading};
+use preview::html_url_to_source;
+use python_env::resolve_interpreter;
+use sphinx::{
+    app_event_sink, build_argv, create_sphinx_manager, resolve_build_path, resolve_python_path,
+    LineClassifier, SharedSphinxManager, SphinxDiagnostic, SphinxEnv, SphinxEvent,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{Emitter, State};
 use tauri_plugin_opener::OpenerExt;
 use terminal::{create_terminal_manager, SharedTerminalManager};
 
@@ -21,7 +47,9 @@ fn spawn_terminal(
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     let mut inner = manager.lock().map_err(|e| e.to_string())?;
-    inner.spawn(session_id, cwd, shell, cols, rows, app_handle)
+    inner
+        .spawn(session_id, cwd, shell, cols, rows, app_handle)
+        .map_err(|e| e.to_string())
 }
 
 /// PTYにデータを書き込む
@@ -47,6 +75,37 @@ fn pty_resize(
     inner.resize(&session_id, cols, rows)
 }
 
+/// ターミナル出力の録画を開始
+#[tauri::command]
+fn start_recording(
+    session_id: String,
+    path: String,
+    manager: State<'_, SharedTerminalManager>,
+) -> Result<(), String> {
+    let mut inner = manager.lock().map_err(|e| e.to_string())?;
+    inner.start_recording(&session_id, Path::new(&path))
+}
+
+/// ターミナル出力の録画を終了
+#[tauri::command]
+fn stop_recording(
+    session_id: String,
+    manager: State<'_, SharedTerminalManager>,
+) -> Result<(), String> {
+    let mut inner = manager.lock().map_err(|e| e.to_string())?;
+    inner.stop_recording(&session_id)
+}
+
+/// シェルでコマンドを実行中か（ターミナルを起動し直してよいかの判断に使う）
+#[tauri::command]
+fn terminal_has_foreground_job(
+    session_id: String,
+    manager: State<'_, SharedTerminalManager>,
+) -> Result<bool, String> {
+    let inner = manager.lock().map_err(|e| e.to_string())?;
+    inner.has_foreground_job(&session_id)
+}
+
 /// PTYセッションを終了
 #[tauri::command]
 fn kill_terminal(
@@ -58,14 +117,23 @@ fn kill_terminal(
 }
 
 /// グローバル設定を読み込む
+/// theme_fileの相対パスはproject_pathが指定されていればそこを基準に解決
 #[tauri::command]
-fn load_config() -> Result<Config, String> {
+fn load_config(project_path: Option<String>) -> Result<Config, String> {
     let mut config = Config::load()?;
-    // テーマファイルがある場合は解決（設定ファイルの場所を基準に）
+    // テーマファイルがある場合は解決（プロジェクトルート、設定ファイルの場所の順に）
     let config_dir = dirs::config_dir()
         .unwrap_or_default()
         .join("khafre");
-    config.terminal.resolve_color_scheme(Some(&config_dir));
+    config
+        .terminal
+        .resolve_color_scheme(project_path.as_deref().map(Path::new), Some(&config_dir));
+    config
+        .terminal
+        .resolve_palette(project_path.as_deref().map(Path::new), Some(&config_dir));
+    config
+        .terminal
+        .resolve_background_image(project_path.as_deref().map(Path::new), Some(&config_dir));
     Ok(config)
 }
 
@@ -73,38 +141,178 @@ fn load_config() -> Result<Config, String> {
 #[tauri::command]
 fn load_dev_config() -> Option<DevConfig> {
     let mut config = DevConfig::load()?;
-    // テーマファイルがある場合は解決
+    // テーマファイルがある場合は解決（相対パスはproject_pathを基準に）
+    let project_path = config.project_path.clone();
     if let Some(ref mut terminal) = config.config.as_mut().and_then(|c| c.terminal.as_mut()) {
-        terminal.resolve_color_scheme();
+        terminal.resolve_color_scheme(project_path.as_deref().map(Path::new));
+        terminal.resolve_palette(project_path.as_deref().map(Path::new));
+        terminal.resolve_background_image(project_path.as_deref().map(Path::new));
     }
     Some(config)
 }
 
+/// プロジェクトの.khafre.tomlを読み込む（テーマファイルなどの相対パスはプロジェクト基準）
+#[tauri::command]
+fn load_project_config(project_path: String) -> Result<Option<ConfigOverride>, String> {
+    let mut config = ConfigOverride::load_project(Path::new(&project_path))?;
+    if let Some(terminal) = config.as_mut().and_then(|c| c.terminal.as_mut()) {
+        terminal.resolve_color_scheme(Some(Path::new(&project_path)));
+        terminal.resolve_palette(Some(Path::new(&project_path)));
+        terminal.resolve_background_image(Some(Path::new(&project_path)));
+    }
+    Ok(config)
+}
+
+/// sphinx.source_dirにconf.pyがあるか確認（無ければプロジェクト内のconf.pyの場所を提案）
+#[tauri::command]
+fn check_sphinx_source_dir(project_path: String, source_dir: String) -> Option<MissingSourceDir> {
+    check_source_dir(Path::new(&project_path), &source_dir)
+}
+
+/// sphinx.source_dirをプロジェクトの.khafre.tomlに保存
+#[tauri::command]
+fn save_project_source_dir(project_path: String, source_dir: String) -> Result<(), String> {
+    ConfigOverride::save_project_source_dir(Path::new(&project_path), &source_dir)
+}
+
+/// カラースキームをAlacritty TOML形式で書き出す
+#[tauri::command]
+fn export_alacritty_theme(scheme: ColorScheme, path: String) -> Result<(), String> {
+    std::fs::write(&path, scheme.to_alacritty_toml())
+        .map_err(|e| format!("テーマの書き出しに失敗: {} ({})", e, path))
+}
+
+/// スプリッターで変更した比率（%）を設定ファイルに保存
+#[tauri::command]
+fn save_split_ratio(ratio: f64) -> Result<(), String> {
+    Config::save_split_ratio(ratio)
+}
+
+/// ユーザー設定ファイルの場所と作成済みかを取得
+#[tauri::command]
+fn user_config_file() -> UserConfigFile {
+    Config::user_config_file()
+}
+
+/// 初回起動の案内を終えたことを記録（WebViewのデータを消しても再表示しない）
+#[tauri::command]
+fn complete_onboarding() -> Result<(), String> {
+    Config::complete_onboarding()
+}
+
+/// ターミナルの背景画像を読み込む（パスはload_configで解決済み）
+#[tauri::command]
+fn read_background_image(path: String) -> Result<tauri::ipc::Response, String> {
+    config::read_background_image(Path::new(&path)).map(tauri::ipc::Response::new)
+}
+
+/// Sphinxの実行に使うインタプリタ
+struct SphinxInterpreter {
+    path: String,
+    /// 設定したインタプリタが見つからず自動検出したものを使う場合の、元の設定
+    fallback_from: Option<String>,
+    /// pyenv / asdfで解決できなかった理由
+    warning: Option<String>,
+}
+
+/// python.interpreterをpyenv / asdfで解決し、見つからなければ仮想環境やPATHのpython3を探して使う
+/// 起動・差分ビルド・報告用の情報で同じインタプリタになるよう、どれもここで解決する
+fn sphinx_interpreter(
+    project_path: &str,
+    python_path: &str,
+    python_runner: Option<PythonRunner>,
+) -> Result<SphinxInterpreter, String> {
+    let resolved = resolve_interpreter(
+        Path::new(project_path),
+        python_path,
+        python_runner.unwrap_or_default(),
+    );
+    let python_path = resolved.interpreter;
+    let error = match resolve_python_path(project_path, &python_path) {
+        Ok(path) if path.exists() => {
+            return Ok(SphinxInterpreter {
+                path: python_path,
+                fallback_from: None,
+                warning: resolved.warning,
+            })
+        }
+        Ok(path) => format!("Pythonインタプリタが見つかりません: {}", path.display()),
+        Err(e) => e,
+    };
+    let detected = detect_python_interpreter(Path::new(project_path)).ok_or(error)?;
+    Ok(SphinxInterpreter {
+        path: detected,
+        fallback_from: Some(python_path),
+        warning: resolved.warning,
+    })
+}
+
 /// sphinx-autobuildを起動
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
-fn start_sphinx(
+async fn start_sphinx(
     session_id: String,
     project_path: String,
     source_dir: String,
     build_dir: String,
     python_path: String,
+    python_runner: Option<PythonRunner>,
     port: u16,
     extra_args: Vec<String>,
+    env: Option<BTreeMap<String, String>>,
+    inherit_env: Option<bool>,
+    success_patterns: Vec<String>,
+    error_patterns: Vec<String>,
     manager: State<'_, SharedSphinxManager>,
     app_handle: tauri::AppHandle,
 ) -> Result<u16, String> {
-    let mut inner = manager.lock().map_err(|e| e.to_string())?;
-    inner.start(
-        session_id,
-        project_path,
-        source_dir,
-        build_dir,
-        python_path,
-        port,
-        extra_args,
-        app_handle,
-    )
+    let manager = Arc::clone(&manager);
+    // Pythonの起動を待つので、UIスレッドを塞がないよう別スレッドで実行
+    tauri::async_runtime::spawn_blocking(move || {
+        // 再起動でも同じインタプリタを使うよう、起動時に解決したパスを渡す
+        let interpreter = sphinx_interpreter(&project_path, &python_path, python_runner)?;
+        let python_path = interpreter.path;
+        let sink = app_event_sink(app_handle);
+        if let Some(message) = interpreter.warning {
+            sink(SphinxEvent::Warning {
+                session_id: session_id.clone(),
+                message,
+            });
+        }
+        if let Some(configured) = interpreter.fallback_from {
+            sink(SphinxEvent::InterpreterFallback {
+                session_id: session_id.clone(),
+                configured,
+                actual: python_path.clone(),
+            });
+        }
+        let env = SphinxEnv {
+            vars: env.unwrap_or_default(),
+            inherit: inherit_env.unwrap_or(true),
+        };
+        // 確認の間に他のセッションの操作を待たせないよう、マネージャのロックを取る前に確認する
+        let checks = manager
+            .lock()
+            .map_err(|e| e.to_string())?
+            .autobuild_checks();
+        checks.ensure(&resolve_python_path(&project_path, &python_path)?, &env)?;
+
+        let mut inner = manager.lock().map_err(|e| e.to_string())?;
+        inner.start(
+            session_id,
+            project_path,
+            source_dir,
+            build_dir,
+            python_path,
+            port,
+            extra_args,
+            env,
+            LineClassifier::new(success_patterns, error_patterns),
+            sink,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// sphinx-autobuildを停止
@@ -114,6 +322,16 @@ fn stop_sphinx(session_id: String, manager: State<'_, SharedSphinxManager>) -> R
     inner.stop(&session_id)
 }
 
+/// 同じ設定のままsphinx-autobuildを起動し直す
+#[tauri::command]
+fn restart_sphinx(
+    session_id: String,
+    manager: State<'_, SharedSphinxManager>,
+) -> Result<u16, String> {
+    let mut inner = manager.lock().map_err(|e| e.to_string())?;
+    inner.restart(&session_id)
+}
+
 /// sphinxのポートを取得
 #[tauri::command]
 fn get_sphinx_port(
@@ -124,6 +342,26 @@ fn get_sphinx_port(
     Ok(inner.get_port(&session_id))
 }
 
+/// sphinx-autobuildの直近の出力を取得
+#[tauri::command]
+fn get_sphinx_log(
+    session_id: String,
+    manager: State<'_, SharedSphinxManager>,
+) -> Result<Vec<String>, String> {
+    let inner = manager.lock().map_err(|e| e.to_string())?;
+    Ok(inner.get_log(&session_id))
+}
+
+/// 直近のビルドの警告・エラーを取得
+#[tauri::command]
+fn get_sphinx_diagnostics(
+    session_id: String,
+    manager: State<'_, SharedSphinxManager>,
+) -> Result<Vec<SphinxDiagnostic>, String> {
+    let inner = manager.lock().map_err(|e| e.to_string())?;
+    Ok(inner.get_diagnostics(&session_id))
+}
+
 /// ブラウザでURLを開く
 #[tauri::command]
 fn open_in_browser(url: String, app_handle: tauri::AppHandle) -> Result<(), String> {
@@ -133,6 +371,134 @@ fn open_in_browser(url: String, app_handle: tauri::AppHandle) -> Result<(), Stri
         .map_err(|e| e.to_string())
 }
 
+/// ビルド出力ディレクトリをファイルマネージャで開く
+#[tauri::command]
+fn reveal_build_dir(
+    project_path: String,
+    build_dir: String,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let path = resolve_build_path(&project_path, &build_dir);
+    if !path.is_dir() {
+        return Err(format!(
+            "ビルド出力ディレクトリがまだありません: {}",
+            path.display()
+        ));
+    }
+    app_handle
+        .opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// プロジェクトのディレクトリをファイルマネージャで開く
+#[tauri::command]
+fn reveal_project_dir(project_path: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_handle
+        .opener()
+        .open_path(&project_path, None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// Sphinxのエラーを報告用のMarkdownに整形
+#[tauri::command]
+async fn format_sphinx_issue(
+    error: String,
+    project_path: String,
+    source_dir: String,
+    build_dir: String,
+    python_path: String,
+    python_runner: Option<PythonRunner>,
+    port: u16,
+    extra_args: Vec<String>,
+) -> Result<String, String> {
+    // python --version の実行を待つため別スレッドで実行
+    tauri::async_runtime::spawn_blocking(move || {
+        // 見つからない場合は、起動時と同じく自動検出したインタプリタの情報を載せる
+        let python_path = sphinx_interpreter(&project_path, &python_path, python_runner)
+            .map_or(python_path, |interpreter| interpreter.path);
+        let args = build_argv(&project_path, &source_dir, &build_dir, port, extra_args);
+        let report = IssueReport::collect(error, &project_path, &python_path, args);
+        format_issue_report(&report)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 一時ディレクトリにビルドし、現在のビルド出力から変わるファイルを取得
+#[tauri::command]
+async fn diff_build(
+    project_path: String,
+    source_dir: String,
+    build_dir: String,
+    python_path: String,
+    python_runner: Option<PythonRunner>,
+    env: Option<BTreeMap<String, String>>,
+    inherit_env: Option<bool>,
+) -> Result<Vec<ChangedFile>, String> {
+    // ビルド完了まで待つため、UIスレッドを塞がないよう別スレッドで実行
+    tauri::async_runtime::spawn_blocking(move || {
+        let python_path = sphinx_interpreter(&project_path, &python_path, python_runner)?.path;
+        let env = SphinxEnv {
+            vars: env.unwrap_or_default(),
+            inherit: inherit_env.unwrap_or(true),
+        };
+        build_and_diff(&project_path, &source_dir, &build_dir, &python_path, &env)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// プレビュー中のページに対応するソースファイルのパスを取得
+#[tauri::command]
+fn resolve_preview_source(
+    url: String,
+    base_url: String,
+    project_path: String,
+    source_dir: String,
+) -> Option<String> {
+    let source_path = Path::new(&project_path).join(&source_dir);
+    html_url_to_source(&url, &base_url, &source_path).map(|p| p.to_string_lossy().to_string())
+}
+
+/// プレビュー中のページのソースから見出し一覧を取得
+#[tauri::command]
+fn preview_outline(source_path: String) -> Result<Vec<Heading>, String> {
+    read_outline(Path::new(&source_path))
+}
+
+/// アプリを終了（terminal.on_exit = "close" 用）
+#[tauri::command]
+fn exit_app(app_handle: tauri::AppHandle) {
+    app_handle.exit(0);
+}
+
+/// 設定で有効な場合は制御ソケットを起動
+fn start_control_server(app_handle: &tauri::AppHandle) {
+    let Ok(config) = Config::load() else {
+        return;
+    };
+    if !config.control.enabled {
+        return;
+    }
+    let path = config
+        .control
+        .socket_path
+        .map(|path| expand_path(&path))
+        .unwrap_or_else(default_socket_path);
+    if let Err(e) = control::serve(&path, app_command_sink(app_handle.clone())) {
+        eprintln!("制御ソケットの起動に失敗: {}", e);
+    }
+}
+
+/// 設定ファイルが書き換えられたらフロントエンドに通知（設定を読み直して反映する）
+fn start_config_watcher(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    config_watcher::watch(Config::config_path(), move || {
+        let _ = app_handle.emit("config_changed", ());
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let terminal_manager = create_terminal_manager();
@@ -143,17 +509,43 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .manage(terminal_manager)
         .manage(sphinx_manager)
+        .setup(|app| {
+            start_control_server(app.handle());
+            start_config_watcher(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             spawn_terminal,
             pty_write,
             pty_resize,
+            terminal_has_foreground_job,
             kill_terminal,
+            start_recording,
+            stop_recording,
             load_config,
             load_dev_config,
+            user_config_file,
+            complete_onboarding,
+            save_split_ratio,
+            load_project_config,
+            check_sphinx_source_dir,
+            save_project_source_dir,
+            export_alacritty_theme,
+            read_background_image,
             start_sphinx,
             stop_sphinx,
+            restart_sphinx,
             get_sphinx_port,
+            get_sphinx_log,
+            get_sphinx_diagnostics,
             open_in_browser,
+            reveal_build_dir,
+            reveal_project_dir,
+            diff_build,
+            format_sphinx_issue,
+            resolve_preview_source,
+            preview_outline,
+            exit_app,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,397 @@
+//! ドキュメントソースの見出し一覧（プレビューのアウトライン表示用）
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 見出しの書式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// reStructuredText（下線・上線で見出しを表す）
+    Rst,
+    /// Markdown（`#` の数でレベルを表す）
+    Markdown,
+}
+
+impl SourceKind {
+    /// 拡張子から書式を判定
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rst" | "txt" => Some(Self::Rst),
+            "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// 見出し
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heading {
+    /// 1始まりの階層
+    pub level: usize,
+    pub title: String,
+    /// Sphinxが生成するセクションのid
+    pub anchor: String,
+}
+
+/// rstで見出しの装飾に使える記号
+const RST_ADORNMENTS: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// 小文字をNFKDで分解してASCII以外を捨てた時に残る文字列（docutilsの置き換え表を含む）
+/// Latin-1 Supplement〜Latin Extended-BとLatin Extended Additionalの範囲
+const ASCII_FOLDING: &[(&str, &str)] = &[
+    ("1", "¹"),
+    ("2", "²"),
+    ("3", "³"),
+    ("a", "ªàáâãäåāăąǎǟǡǻȁȃȧḁẚạảấầẩẫậắằẳẵặ"),
+    ("ae", "æ"),
+    ("b", "ƀƃḃḅḇ"),
+    ("c", "çćĉċčƈȼḉ"),
+    ("d", "ďđƌḋḍḏḑḓ"),
+    ("db", "ȸ"),
+    ("dz", "ǆǳ"),
+    ("e", "èéêëēĕėęěȅȇȩɇḕḗḙḛḝẹẻẽếềểễệ"),
+    ("f", "ƒḟ"),
+    ("g", "ĝğġģǥǧǵḡ"),
+    ("h", "ĥħȟḣḥḧḩḫẖ"),
+    ("i", "ìíîïĩīĭįıǐȉȋḭḯỉị"),
+    ("ij", "ĳ"),
+    ("j", "ĵǰȷɉ"),
+    ("k", "ķƙǩḱḳḵ"),
+    ("l", "ĺļľŀłƚȴḷḹḻḽ"),
+    ("lj", "ǉ"),
+    ("m", "ḿṁṃ"),
+    ("n", "ñńņňŉƞǹȵṅṇṉṋ"),
+    ("nj", "ǌ"),
+    ("o", "ºòóôõöøōŏőơǒǫǭȍȏȫȭȯȱṍṏṑṓọỏốồổỗộớờởỡợ"),
+    ("oe", "œ"),
+    ("p", "ƥṕṗ"),
+    ("q", "ɋ"),
+    ("qp", "ȹ"),
+    ("r", "ŕŗřȑȓɍṙṛṝṟ"),
+    ("s", "śŝşšſșȿṡṣṥṧṩẛ"),
+    ("sz", "ß"),
+    ("t", "ţťŧƫƭțȶṫṭṯṱẗ"),
+    ("u", "ùúûüũūŭůűųưǔǖǘǚǜȕȗṳṵṷṹṻụủứừửữự"),
+    ("v", "ṽṿ"),
+    ("w", "ŵẁẃẅẇẉẘ"),
+    ("x", "ẋẍ"),
+    ("y", "ýÿŷƴȳɏẏẙỳỵỷỹ"),
+    ("z", "źżžƶȥɀẑẓẕ"),
+];
+
+/// make_id用に1文字をASCIIに寄せる（ASCIIにならない文字はNone = 捨てる）
+fn fold_to_ascii(c: char) -> Option<String> {
+    if c.is_ascii() {
+        return Some(c.to_string());
+    }
+    // 全角英数字・記号（NFKDで半角になる）
+    if let Some(half) = ('\u{FF01}'..='\u{FF5E}')
+        .contains(&c)
+        .then(|| char::from_u32(c as u32 - 0xFEE0))
+        .flatten()
+    {
+        return Some(half.to_string());
+    }
+    if c == '\u{3000}' {
+        return Some(" ".to_string());
+    }
+    ASCII_FOLDING
+        .iter()
+        .find(|(_, chars)| chars.contains(c))
+        .map(|(ascii, _)| ascii.to_string())
+}
+
+/// docutilsの make_id: 小文字化してASCIIに寄せ（寄せられない文字は捨てる）、
+/// 英数字以外の連続を "-" にまとめて、先頭の "-" と数字、末尾の "-" を除く
+fn make_id(title: &str) -> String {
+    let folded: String = title
+        .to_lowercase()
+        .chars()
+        .filter_map(fold_to_ascii)
+        .collect();
+    let mut id = String::new();
+    for c in folded.chars() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            id.push(c);
+        } else if !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    id.trim_start_matches(|c: char| c == '-' || c.is_ascii_digit())
+        .trim_end_matches('-')
+        .to_string()
+}
+
+/// 文書内のセクションのid（docutilsの set_id 相当）
+/// make_idが空になる見出し（日本語だけの見出しなど）や、既に使われたidは "id1", "id2", ... になる
+/// 連番は脚注など見出し以外の要素とも共有されるので、それらが先にある文書ではずれることがある
+#[derive(Default)]
+struct SectionIds {
+    used: HashSet<String>,
+    counter: usize,
+}
+
+impl SectionIds {
+    fn assign(&mut self, title: &str) -> String {
+        let mut id = make_id(title);
+        while id.is_empty() || self.used.contains(&id) {
+            self.counter += 1;
+            id = format!("id{}", self.counter);
+        }
+        self.used.insert(id.clone());
+        id
+    }
+}
+
+/// 1種類の記号だけで構成された装飾行なら、その記号を返す
+fn adornment_char(line: &str) -> Option<char> {
+    let line = line.trim_end();
+    let first = line.chars().next()?;
+    (line.chars().count() >= 2
+        && RST_ADORNMENTS.contains(first)
+        && line.chars().all(|c| c == first))
+    .then_some(first)
+}
+
+fn rst_headings(source: &str) -> Vec<Heading> {
+    let lines: Vec<&str> = source.lines().collect();
+    // 見出しのレベルは装飾（記号と上線の有無）が初めて現れた順に決まる
+    let mut styles: Vec<(char, bool)> = Vec::new();
+    let mut headings = Vec::new();
+    let mut ids = SectionIds::default();
+    let mut i = 0;
+
+    while i + 1 < lines.len() {
+        let (title, style, consumed) = match (
+            adornment_char(lines[i]),
+            adornment_char(lines[i + 1]),
+            lines.get(i + 2).and_then(|line| adornment_char(line)),
+        ) {
+            // 上線付き: ===== / Title / =====
+            (Some(over), None, Some(under)) if over == under => {
+                (lines[i + 1].trim(), (over, true), 3)
+            }
+            // 下線のみ: Title / =====
+            (None, Some(under), _) => {
+                let title = lines[i].trim_end();
+                let long_enough = lines[i + 1].trim_end().chars().count() >= title.chars().count();
+                if title.starts_with(char::is_whitespace) || !long_enough {
+                    i += 1;
+                    continue;
+                }
+                (title, (under, false), 2)
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        if title.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        let level = match styles.iter().position(|s| *s == style) {
+            Some(index) => index + 1,
+            None => {
+                styles.push(style);
+                styles.len()
+            }
+        };
+        headings.push(Heading {
+            level,
+            title: title.to_string(),
+            anchor: ids.assign(title),
+        });
+        i += consumed;
+    }
+    headings
+}
+
+fn markdown_headings(source: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut ids = SectionIds::default();
+    let mut fence: Option<&str> = None;
+
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        // コードブロック内の # は見出しではない
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = match fence {
+                Some(open) if open == marker => None,
+                None => Some(marker),
+                other => other,
+            };
+            continue;
+        }
+        if fence.is_some() {
+            continue;
+        }
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let rest = &trimmed[level..];
+        if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+            continue;
+        }
+        let title = rest.trim().trim_end_matches('#').trim_end();
+        if title.is_empty() {
+            continue;
+        }
+        headings.push(Heading {
+            level,
+            title: title.to_string(),
+            anchor: ids.assign(title),
+        });
+    }
+    headings
+}
+
+/// ソースから見出しを取り出す
+pub fn extract_headings(source: &str, kind: SourceKind) -> Vec<Heading> {
+    match kind {
+        SourceKind::Rst => rst_headings(source),
+        SourceKind::Markdown => markdown_headings(source),
+    }
+}
+
+/// ソースファイルを読み込んで見出しを取り出す
+pub fn read_outline(path: &Path) -> Result<Vec<Heading>, String> {
+    let kind = SourceKind::from_path(path)
+        .ok_or_else(|| format!("見出しを解析できないファイルです: {}", path.display()))?;
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("ソースファイルの読み込みに失敗: {}", e))?;
+    Ok(extract_headings(&source, kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading(level: usize, title: &str, anchor: &str) -> Heading {
+        Heading {
+            level,
+            title: title.to_string(),
+            anchor: anchor.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rst_headings() {
+        let source = "\
+==========
+User Guide
+==========
+
+Intro text.
+
+Installation
+============
+
+Via pip
+-------
+
+.. code-block:: sh
+
+   pip install khafre
+
+Configuration
+=============
+
+Not a heading
+--
+";
+        assert_eq!(
+            extract_headings(source, SourceKind::Rst),
+            vec![
+                heading(1, "User Guide", "user-guide"),
+                heading(2, "Installation", "installation"),
+                heading(3, "Via pip", "via-pip"),
+                heading(2, "Configuration", "configuration"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_headings() {
+        let source = "\
+# Getting Started
+
+Some text with a #hashtag.
+
+## Install & Run ##
+
+```sh
+# not a heading
+```
+
+### Step 1: Configure
+####### too deep
+";
+        assert_eq!(
+            extract_headings(source, SourceKind::Markdown),
+            vec![
+                heading(1, "Getting Started", "getting-started"),
+                heading(2, "Install & Run", "install-run"),
+                heading(3, "Step 1: Configure", "step-1-configure"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_id() {
+        assert_eq!(make_id("User Guide"), "user-guide");
+        assert_eq!(make_id("  Install & Run!  "), "install-run");
+        // 先頭の数字と記号は除く
+        assert_eq!(make_id("1. Introduction"), "introduction");
+        assert_eq!(make_id("2024"), "");
+        // アクセント付きの文字はASCIIに寄せる
+        assert_eq!(make_id("Café Crème"), "cafe-creme");
+        assert_eq!(make_id("Straße und Øl"), "strasze-und-ol");
+        assert_eq!(make_id("Tiếng Việt"), "tieng-viet");
+        // ASCIIにならない文字は区切りにならずに消える
+        assert_eq!(make_id("Sphinxの使い方"), "sphinx");
+        assert_eq!(make_id("API（概要）"), "api");
+        assert_eq!(make_id("ａｂｃ　１２３"), "abc-123");
+        assert_eq!(make_id("はじめに"), "");
+    }
+
+    #[test]
+    fn test_section_ids_for_non_ascii_and_duplicate_titles() {
+        let source = "\
+はじめに
+========
+
+Usage
+-----
+
+インストール
+============
+
+Usage
+-----
+";
+        assert_eq!(
+            extract_headings(source, SourceKind::Rst),
+            vec![
+                heading(1, "はじめに", "id1"),
+                heading(2, "Usage", "usage"),
+                heading(1, "インストール", "id2"),
+                heading(2, "Usage", "id3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_kind_from_path() {
+        assert_eq!(
+            SourceKind::from_path(Path::new("docs/index.rst")),
+            Some(SourceKind::Rst)
+        );
+        assert_eq!(
+            SourceKind::from_path(Path::new("docs/guide.md")),
+            Some(SourceKind::Markdown)
+        );
+        assert_eq!(SourceKind::from_path(Path::new("docs/conf.py")), None);
+    }
+}
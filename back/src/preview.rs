@@ -0,0 +1,115 @@
+//! プレビューURLとドキュメントソースの対応付け
+
+use std::path::{Path, PathBuf};
+
+/// ソースファイルとして探す拡張子（優先順）
+const SOURCE_EXTENSIONS: [&str; 3] = ["rst", "md", "txt"];
+
+/// プレビューURLからページのパス（拡張子なし）を取り出す
+/// 例: http://127.0.0.1:8000/guide/install.html#usage -> guide/install
+fn url_to_page(url: &str, base: &str) -> Option<String> {
+    let rest = url.strip_prefix(base.trim_end_matches('/'))?;
+    if !(rest.is_empty() || rest.starts_with(['/', '?', '#'])) {
+        return None;
+    }
+    // クエリとフラグメントを除去
+    let path = rest.split(['?', '#']).next().unwrap_or("");
+    let path = path.trim_start_matches('/');
+
+    let page = if path.is_empty() || path.ends_with('/') {
+        format!("{}index", path)
+    } else {
+        path.strip_suffix(".html")?.to_string()
+    };
+
+    // ソースディレクトリの外を指すパスは扱わない
+    if page.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    Some(page)
+}
+
+/// プレビューURLに対応するソースファイルを探す
+/// index.html -> index.rst のように、ビルド結果と同じ相対パスのソースを拡張子違いで探索する
+pub fn html_url_to_source(url: &str, base: &str, source_dir: &Path) -> Option<PathBuf> {
+    let page = url_to_page(url, base)?;
+    SOURCE_EXTENSIONS
+        .iter()
+        .map(|ext| source_dir.join(format!("{}.{}", page, ext)))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixture;
+
+    const BASE: &str = "http://127.0.0.1:8000";
+
+    #[test]
+    fn test_url_to_page() {
+        assert_eq!(url_to_page(BASE, BASE), Some("index".to_string()));
+        assert_eq!(
+            url_to_page("http://127.0.0.1:8000/", BASE),
+            Some("index".to_string())
+        );
+        assert_eq!(
+            url_to_page("http://127.0.0.1:8000/guide/install.html#usage", BASE),
+            Some("guide/install".to_string())
+        );
+        assert_eq!(
+            url_to_page("http://127.0.0.1:8000/guide/?q=1", BASE),
+            Some("guide/index".to_string())
+        );
+    }
+
+    #[test]
+    fn test_url_to_page_rejects_other_urls() {
+        // 別のサーバー
+        assert_eq!(url_to_page("http://127.0.0.1:9000/index.html", BASE), None);
+        // ポート番号の前方一致
+        assert_eq!(url_to_page("http://127.0.0.1:80001/index.html", BASE), None);
+        // HTML以外
+        assert_eq!(
+            url_to_page("http://127.0.0.1:8000/_static/basic.css", BASE),
+            None
+        );
+        // ソースディレクトリ外
+        assert_eq!(
+            url_to_page("http://127.0.0.1:8000/../secret.html", BASE),
+            None
+        );
+    }
+
+    #[test]
+    fn test_html_url_to_source() {
+        let dir = fixture(
+            "url-to-source",
+            &[
+                ("index.rst", ""),
+                ("guide/install.md", ""),
+                ("guide/index.rst", ""),
+            ],
+        );
+
+        assert_eq!(
+            html_url_to_source("http://127.0.0.1:8000/index.html", BASE, &dir),
+            Some(dir.join("index.rst"))
+        );
+        assert_eq!(
+            html_url_to_source("http://127.0.0.1:8000/guide/install.html", BASE, &dir),
+            Some(dir.join("guide/install.md"))
+        );
+        assert_eq!(
+            html_url_to_source("http://127.0.0.1:8000/guide/", BASE, &dir),
+            Some(dir.join("guide/index.rst"))
+        );
+        // 対応するソースがない（genindexなどの自動生成ページ）
+        assert_eq!(
+            html_url_to_source("http://127.0.0.1:8000/genindex.html", BASE, &dir),
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
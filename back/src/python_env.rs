@@ -0,0 +1,233 @@
+//! pyenv / asdfによるPythonインタプリタの解決
+//!
+//! プロジェクトに `.python-version`（pyenv）や `.tool-versions`（asdf）がある場合、
+//! `pyenv which python` などで指定のバージョンのインタプリタのパスを取得する。
+
+use crate::config::PythonRunner;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Pythonのバージョン管理ツール
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionManager {
+    Pyenv,
+    Asdf,
+}
+
+impl VersionManager {
+    /// 実行するコマンド名
+    fn program(self) -> &'static str {
+        match self {
+            Self::Pyenv => "pyenv",
+            Self::Asdf => "asdf",
+        }
+    }
+}
+
+/// .tool-versionsにpythonのバージョン指定があるか
+fn has_python_tool_version(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| line.split_whitespace().next() == Some("python"))
+}
+
+/// プロジェクト（と親ディレクトリ）のバージョンファイルから使うツールを判定
+/// pyenv / asdfと同じく、プロジェクトに近いディレクトリのファイルを優先する
+pub fn detect_version_manager(project_path: &Path) -> Option<VersionManager> {
+    project_path.ancestors().find_map(|dir| {
+        if dir.join(".python-version").is_file() {
+            return Some(VersionManager::Pyenv);
+        }
+        std::fs::read_to_string(dir.join(".tool-versions"))
+            .ok()
+            .filter(|content| has_python_tool_version(content))
+            .map(|_| VersionManager::Asdf)
+    })
+}
+
+/// interpreterがパスではなくコマンド名（"python"、"python3"など）か
+fn is_command_name(interpreter: &str) -> bool {
+    !interpreter.is_empty() && !interpreter.contains(['/', '\\', '~', '$'])
+}
+
+/// runnerとプロジェクトのファイルから、インタプリタの解決に使うツールを決める
+/// autoではinterpreterにパスを指定している場合（仮想環境など）はそちらを優先する
+pub fn select_version_manager(
+    runner: PythonRunner,
+    interpreter: &str,
+    project_path: &Path,
+) -> Option<VersionManager> {
+    match runner {
+        PythonRunner::Auto if is_command_name(interpreter) => detect_version_manager(project_path),
+        PythonRunner::Auto | PythonRunner::Direct => None,
+        PythonRunner::Pyenv => Some(VersionManager::Pyenv),
+        PythonRunner::Asdf => Some(VersionManager::Asdf),
+    }
+}
+
+/// インタプリタのパスを問い合わせるコマンド（例: `pyenv which python`）
+pub fn which_command(manager: VersionManager, interpreter: &str, project_path: &Path) -> Command {
+    // パスを指定している場合はファイル名をコマンド名として問い合わせる
+    let name = Path::new(interpreter)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| is_command_name(name))
+        .unwrap_or("python");
+    let mut command = Command::new(manager.program());
+    command.args(["which", name]).current_dir(project_path);
+    command
+}
+
+/// whichコマンドの出力からパスを取り出す
+fn parse_which_output(stdout: &[u8]) -> Option<PathBuf> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+}
+
+/// pyenv / asdfで解決したインタプリタ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedInterpreter {
+    pub interpreter: String,
+    /// バージョン管理ツールで解決できず、設定をそのまま使う場合の理由（UIのログに出す）
+    pub warning: Option<String>,
+}
+
+/// pyenv / asdfでインタプリタを解決（使わない場合や解決できない場合はinterpreterをそのまま返す）
+pub fn resolve_interpreter(
+    project_path: &Path,
+    interpreter: &str,
+    runner: PythonRunner,
+) -> ResolvedInterpreter {
+    let unresolved = |warning| ResolvedInterpreter {
+        interpreter: interpreter.to_string(),
+        warning,
+    };
+    let Some(manager) = select_version_manager(runner, interpreter, project_path) else {
+        return unresolved(None);
+    };
+    let resolved = which_command(manager, interpreter, project_path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_which_output(&output.stdout));
+    match resolved {
+        Some(path) => ResolvedInterpreter {
+            interpreter: path.to_string_lossy().to_string(),
+            warning: None,
+        },
+        None => unresolved(Some(format!(
+            "{}でPythonを解決できませんでした。{}を使います",
+            manager.program(),
+            interpreter
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_dir;
+
+    #[test]
+    fn test_detect_version_manager() {
+        let dir = test_dir("python-env");
+        let project = dir.join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        assert_eq!(detect_version_manager(&project), None);
+
+        // pythonを指定していない.tool-versionsは対象外
+        std::fs::write(project.join(".tool-versions"), "nodejs 20.11.0\n").unwrap();
+        assert_eq!(detect_version_manager(&project), None);
+
+        // 親ディレクトリのファイルも見る
+        std::fs::write(
+            dir.join(".tool-versions"),
+            "nodejs 20.11.0\npython 3.12.1\n",
+        )
+        .unwrap();
+        assert_eq!(detect_version_manager(&project), Some(VersionManager::Asdf));
+
+        // プロジェクトに近いディレクトリを優先する
+        std::fs::write(project.join(".python-version"), "3.11.7\n").unwrap();
+        assert_eq!(
+            detect_version_manager(&project),
+            Some(VersionManager::Pyenv)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_select_version_manager() {
+        let dir = test_dir("python-runner");
+        std::fs::write(dir.join(".python-version"), "3.12.1\n").unwrap();
+
+        let select = |runner, interpreter| select_version_manager(runner, interpreter, &dir);
+        assert_eq!(
+            select(PythonRunner::Auto, "python"),
+            Some(VersionManager::Pyenv)
+        );
+        // 仮想環境などのパスを指定していれば、そのまま使う
+        assert_eq!(select(PythonRunner::Auto, ".venv/bin/python"), None);
+        assert_eq!(select(PythonRunner::Direct, "python"), None);
+        assert_eq!(
+            select(PythonRunner::Asdf, "python"),
+            Some(VersionManager::Asdf)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_which_command() {
+        let project = Path::new("/project");
+        let command = which_command(VersionManager::Pyenv, "python3", project);
+        assert_eq!(command.get_program(), "pyenv");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["which", "python3"]
+        );
+        assert_eq!(command.get_current_dir(), Some(project));
+
+        let command = which_command(VersionManager::Asdf, "/usr/bin/python3.12", project);
+        assert_eq!(command.get_program(), "asdf");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["which", "python3.12"]
+        );
+    }
+
+    #[test]
+    fn test_parse_which_output() {
+        assert_eq!(
+            parse_which_output(b"/home/me/.pyenv/versions/3.12.1/bin/python\n"),
+            Some(PathBuf::from("/home/me/.pyenv/versions/3.12.1/bin/python"))
+        );
+        assert_eq!(parse_which_output(b"\n"), None);
+    }
+
+    #[test]
+    fn test_resolve_interpreter_falls_back() {
+        let dir = test_dir("python-fallback");
+        std::fs::write(dir.join(".python-version"), "3.12.1\n").unwrap();
+
+        let unchanged = |interpreter: &str| ResolvedInterpreter {
+            interpreter: interpreter.to_string(),
+            warning: None,
+        };
+        assert_eq!(
+            resolve_interpreter(&dir, ".venv/bin/python", PythonRunner::Auto),
+            unchanged(".venv/bin/python")
+        );
+        assert_eq!(
+            resolve_interpreter(&dir, "python", PythonRunner::Direct),
+            unchanged("python")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
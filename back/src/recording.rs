@@ -0,0 +1,91 @@
+//! ターミナル出力の録画（asciicast v2形式）
+//!
+//! 1行目にヘッダー、以降は `[経過秒, "o", 出力]` のイベントを1行ずつ書き出す。
+//! <https://docs.asciinema.org/manual/asciicast/v2/>
+
+use serde_json::json;
+use std::io::Write;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// PTYの出力をasciicast v2形式で書き出す
+pub struct Recorder<W: Write> {
+    writer: W,
+    started: Instant,
+}
+
+impl<W: Write> Recorder<W> {
+    /// ヘッダーを書き込んで録画を開始
+    pub fn start(mut writer: W, cols: u16, rows: u16) -> Result<Self, String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(writer, "{}", header).map_err(|e| format!("録画の開始に失敗: {}", e))?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+        })
+    }
+
+    /// 出力を1イベントとして記録
+    pub fn output(&mut self, data: &str) -> Result<(), String> {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        // 秒はマイクロ秒単位に丸める（asciinemaと同じ精度）
+        let time = (elapsed * 1_000_000.0).round() / 1_000_000.0;
+        writeln!(self.writer, "{}", json!([time, "o", data]))
+            .map_err(|e| format!("録画の書き込みに失敗: {}", e))
+    }
+
+    /// 書き込みを確定して録画を終了
+    pub fn finish(mut self) -> Result<W, String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("録画の保存に失敗: {}", e))?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_recording_is_asciicast_v2() {
+        let mut recorder = Recorder::start(Vec::new(), 80, 24).unwrap();
+        recorder.output("$ echo hi\r\n").unwrap();
+        recorder.output("hi\r\n\x1b[32m$\x1b[0m ").unwrap();
+        let bytes = recorder.finish().unwrap();
+
+        let text = String::from_utf8(bytes).unwrap();
+        let lines: Vec<Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+
+        let header = &lines[0];
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 80);
+        assert_eq!(header["height"], 24);
+        assert!(header["timestamp"].as_u64().unwrap() > 0);
+
+        let first = lines[1].as_array().unwrap();
+        assert_eq!(first.len(), 3);
+        assert!(first[0].as_f64().unwrap() >= 0.0);
+        assert_eq!(first[1], "o");
+        assert_eq!(first[2], "$ echo hi\r\n");
+
+        // 制御文字はJSONの文字列としてエスケープされ、元に戻せる
+        let second = lines[2].as_array().unwrap();
+        assert!(second[0].as_f64().unwrap() >= first[0].as_f64().unwrap());
+        assert_eq!(second[2], "hi\r\n\x1b[32m$\x1b[0m ");
+    }
+}
@@ -1,39 +1,542 @@
-use std::collections::HashMap;
+use crate::config::{expand_path, resolve_path};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ffi::OsString;
 use std::io::{BufRead, BufReader};
 use std::net::TcpListener;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
+/// 起動検出のポーリング間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Sphinxのメッセージを英語に固定する環境変数
+/// 出力の判定（"build succeeded" など）が翻訳されたメッセージで外れないようにする。
+/// PythonのgettextはLANGUAGEを最優先で見るため、LC_ALLと併せて上書きする
+const SPHINX_LOCALE_ENV: [(&str, &str); 2] = [("LC_ALL", "C"), ("LANGUAGE", "C")];
+
+/// ビルド完了と判定する出力（sphinx.success_patternsのデフォルト）
+pub const DEFAULT_SUCCESS_PATTERNS: [&str; 2] = ["build succeeded", "waiting for changes"];
+
+/// エラーと判定する出力（sphinx.error_patternsのデフォルト）
+pub const DEFAULT_ERROR_PATTERNS: [&str; 2] = ["ERROR", "error:"];
+
+/// セッションごとに保持する出力の行数
+pub const LOG_CAPACITY: usize = 1000;
+
+/// 出力元のストリーム
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// 直近の出力を保持するリングバッファ（容量を超えたら古い行から捨てる）
+pub struct LogBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// 古い順の全行
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+type SharedLog = Arc<Mutex<LogBuffer>>;
+
+/// ビルドの開始を示す出力（ビルドのたびにSphinxが出力する）
+const BUILD_START_MARKER: &str = "Running Sphinx";
+
+/// 診断の重要度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Sphinxの警告・エラー（`path:line: WARNING: message` の形式の出力）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SphinxDiagnostic {
+    pub file: String,
+    /// 行番号が分からない警告（toctreeに含まれていない文書など）はNone
+    pub line: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// ビルドの開始を示す行か
+pub fn is_build_start(line: &str) -> bool {
+    line.contains(BUILD_START_MARKER)
+}
+
+/// Sphinxの警告・エラーの行を解析
+/// 例: "/docs/index.rst:12: WARNING: Title underline too short."
+///     "/docs/orphan.rst: WARNING: document isn't included in any toctree"
+pub fn parse_diagnostic(line: &str) -> Option<SphinxDiagnostic> {
+    let (location, severity, message) = [
+        (": WARNING: ", Severity::Warning),
+        (": ERROR: ", Severity::Error),
+    ]
+    .into_iter()
+    .find_map(|(marker, severity)| {
+        let (location, message) = line.split_once(marker)?;
+        Some((location, severity, message))
+    })?;
+    // 行番号が空の場合（"path:: WARNING:"）もある
+    let location = location.strip_suffix(':').unwrap_or(location);
+    let (file, line) = match location.rsplit_once(':') {
+        Some((file, number))
+            if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            (file, number.parse().ok())
+        }
+        _ => (location, None),
+    };
+    if file.is_empty() {
+        return None;
+    }
+    Some(SphinxDiagnostic {
+        file: file.to_string(),
+        line,
+        severity,
+        message: message.to_string(),
+    })
+}
+
+type SharedDiagnostics = Arc<Mutex<Vec<SphinxDiagnostic>>>;
+
+/// stderrの行をビルド完了・エラーに分類する
+/// パターンは部分一致（大文字小文字を区別する）
+#[derive(Debug, Clone)]
+pub struct LineClassifier {
+    success: Vec<String>,
+    error: Vec<String>,
+}
+
+impl LineClassifier {
+    pub fn new(success: Vec<String>, error: Vec<String>) -> Self {
+        Self { success, error }
+    }
+
+    /// ビルド完了を示す行か
+    pub fn is_success(&self, line: &str) -> bool {
+        self.success
+            .iter()
+            .any(|pattern| line.contains(pattern.as_str()))
+    }
+
+    /// エラーを示す行か
+    pub fn is_error(&self, line: &str) -> bool {
+        self.error
+            .iter()
+            .any(|pattern| line.contains(pattern.as_str()))
+    }
+}
+
+impl Default for LineClassifier {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_SUCCESS_PATTERNS.map(String::from).to_vec(),
+            DEFAULT_ERROR_PATTERNS.map(String::from).to_vec(),
+        )
+    }
+}
+
+/// ロケールを固定したSphinx実行用のコマンド
+fn sphinx_command(python: &Path) -> Command {
+    let mut command = Command::new(python);
+    command.envs(SPHINX_LOCALE_ENV);
+    command
+}
+
+/// sphinx-autobuildから通知するイベント
+#[derive(Debug, Clone, PartialEq)]
+pub enum SphinxEvent {
+    /// サーバーへの接続を確認
+    Started { session_id: String, port: u16 },
+    /// ビルド完了
+    Built { session_id: String },
+    /// エラー出力を検出
+    Error { session_id: String, message: String },
+    /// ビルドを開始した（前回のビルドの診断は破棄する）
+    BuildStarted { session_id: String },
+    /// 警告・エラーを検出
+    Diagnostic {
+        session_id: String,
+        diagnostic: SphinxDiagnostic,
+    },
+    /// sphinx-autobuildが出力した1行
+    LogLine {
+        session_id: String,
+        line: String,
+        stream: LogStream,
+    },
+    /// 指定したポートが使用中のため、別のポートで起動した
+    PortFallback {
+        session_id: String,
+        requested: u16,
+        actual: u16,
+    },
+    /// 起動は続けるが、利用者に知らせたいこと（pyenv / asdfで解決できなかったなど）
+    Warning { session_id: String, message: String },
+    /// 設定したインタプリタが見つからないため、自動検出したインタプリタで起動した
+    InterpreterFallback {
+        session_id: String,
+        configured: String,
+        actual: String,
+    },
+    /// 停止操作をしていないのにプロセスが終了した
+    /// port_in_use: ポートが既に使われていてサーバーを起動できなかった
+    Exited {
+        session_id: String,
+        port_in_use: bool,
+    },
+}
+
+/// イベントの通知先
+pub type EventSink = Arc<dyn Fn(SphinxEvent) + Send + Sync>;
+
+/// SphinxEventをTauriイベントとしてフロントエンドへ送るシンクを作成
+pub fn app_event_sink(app_handle: AppHandle) -> EventSink {
+    Arc::new(move |event| {
+        let _ = match event {
+            SphinxEvent::Started { session_id, port } => {
+                app_handle.emit("sphinx_started", (session_id, port))
+            }
+            SphinxEvent::Built { session_id } => app_handle.emit("sphinx_built", session_id),
+            SphinxEvent::Error {
+                session_id,
+                message,
+            } => app_handle.emit("sphinx_error", (session_id, message)),
+            SphinxEvent::BuildStarted { session_id } => {
+                app_handle.emit("sphinx_build_started", session_id)
+            }
+            SphinxEvent::Diagnostic {
+                session_id,
+                diagnostic,
+            } => app_handle.emit("sphinx_diagnostic", (session_id, diagnostic)),
+            SphinxEvent::LogLine {
+                session_id,
+                line,
+                stream,
+            } => app_handle.emit("sphinx_log", (session_id, line, stream)),
+            SphinxEvent::PortFallback {
+                session_id,
+                requested,
+                actual,
+            } => app_handle.emit("sphinx_port_fallback", (session_id, requested, actual)),
+            SphinxEvent::Warning {
+                session_id,
+                message,
+            } => app_handle.emit("sphinx_warning", (session_id, message)),
+            SphinxEvent::InterpreterFallback {
+                session_id,
+                configured,
+                actual,
+            } => app_handle.emit(
+                "sphinx_interpreter_fallback",
+                (session_id, configured, actual),
+            ),
+            SphinxEvent::Exited {
+                session_id,
+                port_in_use,
+            } => app_handle.emit("sphinx_exited", (session_id, port_in_use)),
+        };
+    })
+}
+
+/// ビルド出力ディレクトリのパスを解決（project_pathを基準）
+pub fn resolve_build_path(project_path: impl AsRef<Path>, build_dir: &str) -> PathBuf {
+    project_path.as_ref().join(build_dir)
+}
+
+/// sphinx-autobuildに渡す引数（Python実行ファイル以降）を構築
+/// パスはUTF-8でなくてもそのまま渡せるようOsStringで扱う
+pub fn build_argv(
+    project_path: impl AsRef<Path>,
+    source_dir: &str,
+    build_dir: &str,
+    port: u16,
+    extra_args: Vec<String>,
+) -> Vec<OsString> {
+    let source_path = project_path.as_ref().join(source_dir);
+    let build_path = resolve_build_path(&project_path, build_dir);
+
+    let mut args: Vec<OsString> = vec![
+        "-m".into(),
+        "sphinx_autobuild".into(),
+        source_path.into_os_string(),
+        build_path.into_os_string(),
+        "--port".into(),
+        port.to_string().into(),
+        "--host".into(),
+        "127.0.0.1".into(),
+    ];
+    // 追加引数をマージ
+    args.extend(extra_args.into_iter().map(OsString::from));
+    args
+}
+
+/// python_pathの `~` と環境変数を展開し、相対パスの場合はproject_pathを基準に解決
+pub fn resolve_python_path(
+    project_path: impl AsRef<Path>,
+    python_path: &str,
+) -> Result<PathBuf, String> {
+    let expanded = expand_path(python_path);
+    if !expanded.is_relative() {
+        return Ok(expanded);
+    }
+    let project_path = project_path.as_ref();
+    let full_path = resolve_path(project_path, python_path);
+    if !full_path.exists() {
+        return Err(format!(
+            "Pythonインタプリタが見つかりません: {} (プロジェクト: {})",
+            full_path.display(),
+            project_path.display()
+        ));
+    }
+    Ok(full_path)
+}
+
+/// インタプリタにsphinx-autobuildがインストールされているか確認
+/// 起動してから分かるモジュールの不足を、分かりやすいエラーにする
+/// sphinx.envでPYTHONPATHなどを指定している場合があるので、起動時と同じ環境変数で確認する
+pub fn check_sphinx_autobuild(python: &Path, env: &SphinxEnv) -> Result<(), String> {
+    let mut command = sphinx_command(python);
+    env.apply(&mut command);
+    let output = command
+        .args(["-c", "import sphinx_autobuild"])
+        .output()
+        .map_err(|e| format!("Pythonの起動に失敗: {} (Python: {})", e, python.display()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "sphinx-autobuildがインストールされていません (Python: {0})。`{0} -m pip install sphinx-autobuild` でインストールしてください",
+            python.display()
+        ));
+    }
+    Ok(())
+}
+
+/// sphinx-autobuildに渡す環境変数（sphinx.env / sphinx.inherit_env）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SphinxEnv {
+    pub vars: BTreeMap<String, String>,
+    /// Khafreの環境変数を引き継ぐか
+    pub inherit: bool,
+}
+
+impl Default for SphinxEnv {
+    fn default() -> Self {
+        Self {
+            vars: BTreeMap::new(),
+            inherit: true,
+        }
+    }
+}
+
+impl SphinxEnv {
+    /// コマンドに環境変数を設定
+    /// 引き継がない場合も、コマンドの検索に使うPATHとロケールの固定（SPHINX_LOCALE_ENV）は残す
+    fn apply(&self, command: &mut Command) {
+        if !self.inherit {
+            command.env_clear().envs(SPHINX_LOCALE_ENV);
+            if let Some(path) = std::env::var_os("PATH") {
+                command.env("PATH", path);
+            }
+        }
+        command.envs(&self.vars);
+    }
+}
+
+/// sphinx-autobuildがあると確認したインタプリタと環境変数の組
+/// （見つからなかった場合はインストール後にやり直せるよう覚えない）
+#[derive(Default)]
+pub struct AutobuildChecks(Mutex<HashSet<(PathBuf, SphinxEnv)>>);
+
+impl AutobuildChecks {
+    /// 未確認ならsphinx-autobuildがあるか確認する（Pythonの起動中はロックを持たない）
+    pub fn ensure(&self, python: &Path, env: &SphinxEnv) -> Result<(), String> {
+        let key = (python.to_path_buf(), env.clone());
+        if self.0.lock().map_err(|e| e.to_string())?.contains(&key) {
+            return Ok(());
+        }
+        check_sphinx_autobuild(python, env)?;
+        self.0.lock().map_err(|e| e.to_string())?.insert(key);
+        Ok(())
+    }
+}
+
+/// sphinx-buildでHTMLを一度だけビルド（out_dirに出力）
+pub fn build_once(
+    project_path: impl AsRef<Path>,
+    source_dir: &str,
+    out_dir: &Path,
+    python_path: &str,
+    env: &SphinxEnv,
+) -> Result<(), String> {
+    let project_path = project_path.as_ref();
+    let python = resolve_python_path(project_path, python_path)?;
+    let source_path = project_path.join(source_dir);
+
+    let mut command = sphinx_command(&python);
+    env.apply(&mut command);
+    let output = command
+        .args(["-m", "sphinx", "-b", "html", "-q"])
+        .arg(&source_path)
+        .arg(out_dir)
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| {
+            format!(
+                "sphinx-buildの起動に失敗: {} (Python: {})",
+                e,
+                python.display()
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "sphinx-buildが失敗しました: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// sphinx-autobuildの出力から配信中のURLを取り出す
+/// 例: "[sphinx-autobuild] Serving on http://127.0.0.1:8000"
+pub fn parse_serving_url(line: &str) -> Option<&str> {
+    const MARKER: &str = "Serving on ";
+    let start = line.find(MARKER)? + MARKER.len();
+    line[start..]
+        .split_whitespace()
+        .next()
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+}
+
+/// URLのポート番号を取り出す
+fn url_port(url: &str) -> Option<u16> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split('/').next()?;
+    authority.rsplit_once(':')?.1.parse().ok()
+}
+
+/// ポートのbindに失敗した時のエラー出力か
+/// (Linux: Errno 98, macOS: Errno 48, Windows: WinError 10048)
+pub fn is_address_in_use(line: &str) -> bool {
+    line.contains("Address already in use")
+        || line.contains("address already in use")
+        || line.contains("WinError 10048")
+}
+
+/// 起動時の設定（再起動で同じ設定のまま起動し直すために保持する）
+#[derive(Clone)]
+struct StartParams {
+    project_path: String,
+    source_dir: String,
+    build_dir: String,
+    python_path: String,
+    requested_port: u16,
+    extra_args: Vec<String>,
+    env: SphinxEnv,
+    classifier: LineClassifier,
+    sink: EventSink,
+}
+
 /// sphinx-autobuildプロセス情報
 pub struct SphinxProcess {
     child: Child,
-    port: u16,
-    /// 停止フラグ（ポーリングスレッド終了用）
+    params: StartParams,
+    /// 配信中のポート（出力から実際のポートが分かれば更新）
+    port: Arc<AtomicU16>,
+    /// 停止フラグ（監視スレッド終了用）
     stopped: Arc<AtomicBool>,
+    /// 起動検出スレッド（停止時にjoinする）
+    poller: Option<JoinHandle<()>>,
+}
+
+impl SphinxProcess {
+    /// 監視スレッドに停止を通知し、プロセスを終了させる
+    fn shutdown(&mut self) -> std::io::Result<()> {
+        self.stopped.store(true, Ordering::Relaxed);
+        let result = match self.child.kill() {
+            // 既に終了している場合はエラーを無視
+            Err(e) if e.kind() != std::io::ErrorKind::InvalidInput => Err(e),
+            _ => Ok(()),
+        };
+        // 確実に終了を待機（ゾンビプロセス防止）
+        let _ = self.child.wait();
+        if let Some(poller) = self.poller.take() {
+            let _ = poller.join();
+        }
+        result
+    }
 }
 
 /// Sphinxプロセスマネージャ
 pub struct SphinxManager {
     processes: HashMap<String, SphinxProcess>,
+    /// セッションごとの出力（異常終了や再起動の後も原因を確認できるよう、停止しても残す）
+    logs: HashMap<String, SharedLog>,
+    /// セッションごとの直近のビルドの警告・エラー
+    diagnostics: HashMap<String, SharedDiagnostics>,
+    /// sphinx-autobuildがあると確認したインタプリタ
+    autobuild_checks: Arc<AutobuildChecks>,
 }
 
 impl SphinxManager {
     pub fn new() -> Self {
         Self {
             processes: HashMap::new(),
+            logs: HashMap::new(),
+            diagnostics: HashMap::new(),
+            autobuild_checks: Arc::default(),
         }
     }
 
-    /// 利用可能なポートを検索
-    fn find_available_port() -> Result<u16, String> {
-        TcpListener::bind("127.0.0.1:0")
-            .map_err(|e| format!("ポートの検索に失敗: {}", e))?
+    /// 空いているポートを確保（listenerを閉じるまで他のプロセスには割り当てられない）
+    fn reserve_port() -> Result<(u16, TcpListener), String> {
+        let listener =
+            TcpListener::bind("127.0.0.1:0").map_err(|e| format!("ポートの検索に失敗: {}", e))?;
+        let port = listener
             .local_addr()
-            .map_err(|e| format!("アドレスの取得に失敗: {}", e))
-            .map(|addr| addr.port())
+            .map_err(|e| format!("アドレスの取得に失敗: {}", e))?
+            .port();
+        Ok((port, listener))
+    }
+
+    /// sphinx-autobuildの確認結果（マネージャのロックを取らずに確認するため）
+    pub fn autobuild_checks(&self) -> Arc<AutobuildChecks> {
+        Arc::clone(&self.autobuild_checks)
+    }
+
+    /// 利用可能なポートを検索（起動時はreserve_portで確保したまま渡す）
+    #[cfg(test)]
+    fn find_available_port() -> Result<u16, String> {
+        Self::reserve_port().map(|(port, _)| port)
     }
 
     /// sphinx-autobuildを起動
@@ -47,53 +550,82 @@ impl SphinxManager {
         python_path: String,
         requested_port: u16,
         extra_args: Vec<String>,
-        app_handle: AppHandle,
+        env: SphinxEnv,
+        classifier: LineClassifier,
+        sink: EventSink,
     ) -> Result<u16, String> {
         // 既存セッションがあれば停止
         if self.processes.contains_key(&session_id) {
             self.stop(&session_id)?;
         }
 
-        let port = if requested_port == 0 {
-            Self::find_available_port()?
-        } else {
-            requested_port
+        let params = StartParams {
+            project_path,
+            source_dir,
+            build_dir,
+            python_path,
+            requested_port,
+            extra_args,
+            env,
+            classifier,
+            sink,
         };
+        self.launch(session_id, params)
+    }
 
-        // python_pathが相対パスの場合、project_pathを基準に解決
-        let resolved_python_path = if std::path::Path::new(&python_path).is_relative() {
-            let full_path = std::path::Path::new(&project_path).join(&python_path);
-            if !full_path.exists() {
-                return Err(format!(
-                    "Pythonインタプリタが見つかりません: {} (プロジェクト: {})",
-                    full_path.display(),
-                    project_path
-                ));
-            }
-            full_path.to_string_lossy().to_string()
-        } else {
-            python_path.clone()
+    /// 同じ設定のままsphinx-autobuildを起動し直す（sphinx-autobuildが拾わないconf.pyの変更の反映など）
+    pub fn restart(&mut self, session_id: &str) -> Result<u16, String> {
+        let params = self
+            .processes
+            .get(session_id)
+            .map(|process| process.params.clone())
+            .ok_or_else(|| format!("sphinx-autobuildが起動していません: {}", session_id))?;
+        self.stop(session_id)?;
+        self.launch(session_id.to_string(), params)
+    }
+
+    /// プロセスを起動して監視スレッドを立てる
+    fn launch(&mut self, session_id: String, params: StartParams) -> Result<u16, String> {
+        let StartParams {
+            project_path,
+            source_dir,
+            build_dir,
+            python_path,
+            requested_port,
+            extra_args,
+            env,
+            classifier,
+            sink,
+        } = params.clone();
+
+        let resolved_python_path = resolve_python_path(&project_path, &python_path)?;
+        self.autobuild_checks.ensure(&resolved_python_path, &env)?;
+
+        // ポートは起動直前まで確保しておき、他のプロセスに取られる隙を減らす
+        // 指定したポートが使用中（異常終了したプロセスが残っているなど）なら空いているポートで起動する
+        let (port, reserved) = match requested_port {
+            0 => Self::reserve_port()?,
+            requested => match TcpListener::bind(("127.0.0.1", requested)) {
+                Ok(listener) => (requested, listener),
+                Err(_) => {
+                    let (actual, listener) = Self::reserve_port()?;
+                    sink(SphinxEvent::PortFallback {
+                        session_id: session_id.clone(),
+                        requested,
+                        actual,
+                    });
+                    (actual, listener)
+                }
+            },
         };
 
-        let source_path = std::path::Path::new(&project_path).join(&source_dir);
-        let build_path = std::path::Path::new(&project_path).join(&build_dir);
-
-        // 基本引数を構築
-        let mut args = vec![
-            "-m".to_string(),
-            "sphinx_autobuild".to_string(),
-            source_path.to_str().unwrap().to_string(),
-            build_path.to_str().unwrap().to_string(),
-            "--port".to_string(),
-            port.to_string(),
-            "--host".to_string(),
-            "127.0.0.1".to_string(),
-        ];
-        // 追加引数をマージ
-        args.extend(extra_args);
+        let args = build_argv(&project_path, &source_dir, &build_dir, port, extra_args);
 
         // sphinx-autobuildを起動
-        let mut child = Command::new(&resolved_python_path)
+        drop(reserved);
+        let mut command = sphinx_command(&resolved_python_path);
+        env.apply(&mut command);
+        let mut child = command
             .args(&args)
             .current_dir(&project_path)
             .stdout(Stdio::piped())
@@ -102,62 +634,159 @@ impl SphinxManager {
             .map_err(|e| {
                 format!(
                     "sphinx-autobuildの起動に失敗: {} (Python: {}, 作業ディレクトリ: {})",
-                    e, resolved_python_path, project_path
+                    e,
+                    resolved_python_path.display(),
+                    project_path
                 )
             })?;
 
+        // 停止フラグを作成
+        let stopped = Arc::new(AtomicBool::new(false));
+        // 止まったセッションの出力は、別のセッションを起動するまでだけ残す
+        // （プロジェクトを開き直すたびにセッションIDが変わるので、残し続けると溜まっていく）
+        let processes = &self.processes;
+        let keep = |id: &String| *id == session_id || processes.contains_key(id);
+        self.logs.retain(|id, _| keep(id));
+        self.diagnostics.retain(|id, _| keep(id));
+        let log = Arc::clone(
+            self.logs
+                .entry(session_id.clone())
+                .or_insert_with(|| Arc::new(Mutex::new(LogBuffer::new(LOG_CAPACITY)))),
+        );
+        let diagnostics = Arc::clone(self.diagnostics.entry(session_id.clone()).or_default());
+        // 出力を記録し、ビルドの開始と警告・エラーを集計して通知する（停止後の出力は通知しない）
+        // Sphinxは進捗をstdout、警告をstderrに出すため、両方の行をここで扱う
+        let record_line = {
+            let sink = Arc::clone(&sink);
+            let stopped = Arc::clone(&stopped);
+            let session_id = session_id.clone();
+            move |line: &str, stream: LogStream| {
+                if let Ok(mut log) = log.lock() {
+                    log.push(line.to_string());
+                }
+                let mut events = vec![SphinxEvent::LogLine {
+                    session_id: session_id.clone(),
+                    line: line.to_string(),
+                    stream,
+                }];
+                if is_build_start(line) {
+                    if let Ok(mut diagnostics) = diagnostics.lock() {
+                        diagnostics.clear();
+                    }
+                    events.push(SphinxEvent::BuildStarted {
+                        session_id: session_id.clone(),
+                    });
+                } else if let Some(diagnostic) = parse_diagnostic(line) {
+                    if let Ok(mut diagnostics) = diagnostics.lock() {
+                        diagnostics.push(diagnostic.clone());
+                    }
+                    events.push(SphinxEvent::Diagnostic {
+                        session_id: session_id.clone(),
+                        diagnostic,
+                    });
+                }
+                if !stopped.load(Ordering::Relaxed) {
+                    events.into_iter().for_each(|event| sink(event));
+                }
+            }
+        };
+        let record_stderr = record_line.clone();
+        // 出力から読み取った実際のポート（0 = 未確認）
+        let served_port = Arc::new(AtomicU16::new(0));
+
+        // stdoutはログへの記録と配信URLの検出のみ（読み続けないとパイプが詰まる）
+        if let Some(stdout) = child.stdout.take() {
+            let served_port = Arc::clone(&served_port);
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    record_line(&line, LogStream::Stdout);
+                    if let Some(port) = parse_serving_url(&line).and_then(url_port) {
+                        served_port.store(port, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+
         // stderrを監視してビルドイベントを通知
         let stderr = child.stderr.take();
         let sid = session_id.clone();
-        let handle = app_handle.clone();
+        let stderr_sink = Arc::clone(&sink);
+        let stopped_stderr = Arc::clone(&stopped);
+        let served_port_stderr = Arc::clone(&served_port);
 
         if let Some(stderr) = stderr {
             thread::spawn(move || {
                 let reader = BufReader::new(stderr);
+                let mut port_in_use = false;
                 for line in reader.lines().map_while(Result::ok) {
+                    // 停止後の出力は通知しない
+                    if stopped_stderr.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    record_stderr(&line, LogStream::Stderr);
+                    if let Some(port) = parse_serving_url(&line).and_then(url_port) {
+                        served_port_stderr.store(port, Ordering::Relaxed);
+                    }
+                    port_in_use |= is_address_in_use(&line);
                     // ビルド完了を検出
-                    if line.contains("build succeeded") || line.contains("waiting for changes") {
-                        let _ = handle.emit("sphinx_built", &sid);
+                    if classifier.is_success(&line) {
+                        stderr_sink(SphinxEvent::Built {
+                            session_id: sid.clone(),
+                        });
                     }
                     // エラーを検出
-                    if line.contains("ERROR") || line.contains("error:") {
-                        let _ = handle.emit("sphinx_error", (&sid, &line));
+                    if classifier.is_error(&line) {
+                        stderr_sink(SphinxEvent::Error {
+                            session_id: sid.clone(),
+                            message: line,
+                        });
                     }
                 }
+                // stderrが閉じた = プロセスが終了した（停止操作によるものは通知しない）
+                if !stopped_stderr.load(Ordering::Relaxed) {
+                    stderr_sink(SphinxEvent::Exited {
+                        session_id: sid,
+                        port_in_use,
+                    });
+                }
             });
         }
 
-        // 停止フラグを作成
-        let stopped = Arc::new(AtomicBool::new(false));
-        let stopped_poll = Arc::clone(&stopped);
-
         // サーバー起動をポーリングで検出（ポートへの接続を試みる）
+        // 出力から実際のポートが分かればそちらを正とする（指定したポートのbindに失敗した場合など）
+        let stopped_poll = Arc::clone(&stopped);
         let sid_poll = session_id.clone();
-        let handle_poll = app_handle.clone();
-        let poll_port = port;
-        thread::spawn(move || {
+        let confirmed_port = Arc::new(AtomicU16::new(port));
+        let confirmed_poll = Arc::clone(&confirmed_port);
+        let poller = thread::spawn(move || {
             use std::net::TcpStream;
-            use std::time::Duration;
 
-            let addr = format!("127.0.0.1:{}", poll_port);
-            // 停止されるまで1秒ごとにポーリング
-            loop {
-                // 停止フラグをチェック
-                if stopped_poll.load(Ordering::Relaxed) {
-                    return;
-                }
-                thread::sleep(Duration::from_secs(1));
-                if TcpStream::connect(&addr).is_ok() {
-                    let _ = handle_poll.emit("sphinx_started", (&sid_poll, poll_port));
+            // 停止されるまでポーリング
+            while !stopped_poll.load(Ordering::Relaxed) {
+                let poll_port = match served_port.load(Ordering::Relaxed) {
+                    0 => port,
+                    served => served,
+                };
+                if TcpStream::connect(("127.0.0.1", poll_port)).is_ok() {
+                    confirmed_poll.store(poll_port, Ordering::Relaxed);
+                    if !stopped_poll.load(Ordering::Relaxed) {
+                        sink(SphinxEvent::Started {
+                            session_id: sid_poll,
+                            port: poll_port,
+                        });
+                    }
                     return;
                 }
+                thread::sleep(POLL_INTERVAL);
             }
         });
 
         let process = SphinxProcess {
             child,
-            port,
+            params,
+            port: confirmed_port,
             stopped,
+            poller: Some(poller),
         };
         self.processes.insert(session_id.clone(), process);
 
@@ -167,24 +796,35 @@ impl SphinxManager {
     /// sphinx-autobuildを停止
     pub fn stop(&mut self, session_id: &str) -> Result<(), String> {
         if let Some(mut process) = self.processes.remove(session_id) {
-            // ポーリングスレッドに停止を通知
-            process.stopped.store(true, Ordering::Relaxed);
-            // プロセスをkill
-            if let Err(e) = process.child.kill() {
-                // 既に終了している場合はエラーを無視
-                if e.kind() != std::io::ErrorKind::InvalidInput {
-                    return Err(format!("プロセスの停止に失敗: {}", e));
-                }
-            }
-            // 確実に終了を待機（ゾンビプロセス防止）
-            let _ = process.child.wait();
+            // 起動中（ポーリング中）でもプロセスとスレッドを確実に止める
+            process
+                .shutdown()
+                .map_err(|e| format!("プロセスの停止に失敗: {}", e))?;
         }
         Ok(())
     }
 
+    /// セッションの直近の出力（古い順、最大LOG_CAPACITY行）
+    pub fn get_log(&self, session_id: &str) -> Vec<String> {
+        self.logs
+            .get(session_id)
+            .and_then(|log| log.lock().ok().map(|log| log.lines()))
+            .unwrap_or_default()
+    }
+
+    /// セッションの直近のビルドの警告・エラー（出力順）
+    pub fn get_diagnostics(&self, session_id: &str) -> Vec<SphinxDiagnostic> {
+        self.diagnostics
+            .get(session_id)
+            .and_then(|diagnostics| diagnostics.lock().ok().map(|d| d.clone()))
+            .unwrap_or_default()
+    }
+
     /// ポートを取得
     pub fn get_port(&self, session_id: &str) -> Option<u16> {
-        self.processes.get(session_id).map(|p| p.port)
+        self.processes
+            .get(session_id)
+            .map(|p| p.port.load(Ordering::Relaxed))
     }
 
     /// 実行中かどうか
@@ -198,9 +838,7 @@ impl Drop for SphinxManager {
     fn drop(&mut self) {
         // 全プロセスを停止
         for (_, mut process) in self.processes.drain() {
-            process.stopped.store(true, Ordering::Relaxed);
-            let _ = process.child.kill();
-            let _ = process.child.wait();
+            let _ = process.shutdown();
         }
     }
 }
@@ -214,6 +852,9 @@ pub fn create_sphinx_manager() -> SharedSphinxManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::test_dir;
+    #[cfg(unix)]
+    use crate::test_util::{fake_python, write_script};
 
     #[test]
     fn test_sphinx_manager_creation() {
@@ -233,4 +874,839 @@ mod tests {
         // 存在しないセッションの停止は成功する
         assert!(manager.stop("nonexistent").is_ok());
     }
+
+    #[test]
+    fn test_log_buffer_keeps_latest_lines() {
+        let mut log = LogBuffer::new(3);
+        for i in 0..5 {
+            log.push(format!("line {}", i));
+        }
+        assert_eq!(log.lines(), vec!["line 2", "line 3", "line 4"]);
+    }
+
+    #[test]
+    fn test_get_log_nonexistent_session() {
+        let manager = SphinxManager::new();
+        assert!(manager.get_log("nonexistent").is_empty());
+    }
+
+    #[test]
+    fn test_parse_diagnostic() {
+        assert_eq!(
+            parse_diagnostic("/docs/index.rst:12: WARNING: Title underline too short."),
+            Some(SphinxDiagnostic {
+                file: "/docs/index.rst".to_string(),
+                line: Some(12),
+                severity: Severity::Warning,
+                message: "Title underline too short.".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_diagnostic("docs/api.rst:3: ERROR: Unknown directive type \"foo\"."),
+            Some(SphinxDiagnostic {
+                file: "docs/api.rst".to_string(),
+                line: Some(3),
+                severity: Severity::Error,
+                message: "Unknown directive type \"foo\".".to_string(),
+            })
+        );
+        // 行番号のない警告
+        let orphan = parse_diagnostic(
+            "/docs/orphan.rst: WARNING: document isn't included in any toctree [toc.not_included]",
+        )
+        .unwrap();
+        assert_eq!(orphan.file, "/docs/orphan.rst");
+        assert_eq!(orphan.line, None);
+        assert_eq!(
+            orphan.message,
+            "document isn't included in any toctree [toc.not_included]"
+        );
+        assert_eq!(
+            parse_diagnostic("/docs/index.rst:: WARNING: duplicate label").map(|d| d.line),
+            Some(None)
+        );
+        // docstring内の警告は行番号をdocstringの中の位置として扱う
+        let docstring = parse_diagnostic(
+            "/src/pkg/mod.py:docstring of pkg.mod.func:5: WARNING: Unexpected indentation.",
+        )
+        .unwrap();
+        assert_eq!(docstring.file, "/src/pkg/mod.py:docstring of pkg.mod.func");
+        assert_eq!(docstring.line, Some(5));
+
+        // 場所のない警告や通常の出力は対象外
+        assert_eq!(
+            parse_diagnostic("WARNING: html_static_path entry '_static' does not exist"),
+            None
+        );
+        assert_eq!(parse_diagnostic("build succeeded, 2 warnings."), None);
+    }
+
+    #[test]
+    fn test_is_build_start() {
+        assert!(is_build_start("Running Sphinx v8.1.3"));
+        assert!(!is_build_start(
+            "[sphinx-autobuild] Serving on http://127.0.0.1:8000"
+        ));
+    }
+
+    #[test]
+    fn test_get_diagnostics_nonexistent_session() {
+        let manager = SphinxManager::new();
+        assert!(manager.get_diagnostics("nonexistent").is_empty());
+    }
+
+    #[test]
+    fn test_classify_default_patterns() {
+        let classifier = LineClassifier::default();
+        assert!(classifier.is_success("build succeeded, 2 warnings."));
+        assert!(!classifier.is_success("[sphinx-autobuild] Starting initial build"));
+        assert!(classifier.is_success("waiting for changes..."));
+        assert!(classifier.is_error("index.rst:3: ERROR: Unknown directive type."));
+        assert!(classifier.is_error("error: unrecognized arguments"));
+        assert!(!classifier.is_error("reading sources... [100%] index"));
+    }
+
+    #[test]
+    fn test_classify_custom_patterns() {
+        let classifier = LineClassifier::new(
+            vec!["[custom] done".to_string()],
+            vec!["FAILED".to_string()],
+        );
+        assert!(classifier.is_success("[custom] done in 1.2s"));
+        // デフォルトのパターンは使わない
+        assert!(!classifier.is_success("build succeeded."));
+        assert!(classifier.is_error("step 2 FAILED"));
+        assert!(!classifier.is_error("index.rst:3: ERROR: Unknown directive type."));
+        // 大文字小文字は区別する
+        assert!(!classifier.is_error("step 2 failed"));
+    }
+
+    #[test]
+    fn test_resolve_build_path() {
+        assert_eq!(
+            resolve_build_path("/path/to/project", "_build/html"),
+            PathBuf::from("/path/to/project/_build/html")
+        );
+        // 絶対パスはそのまま使う
+        assert_eq!(
+            resolve_build_path("/path/to/project", "/tmp/html"),
+            PathBuf::from("/tmp/html")
+        );
+    }
+
+    #[test]
+    fn test_build_argv() {
+        let args = build_argv(
+            "/project",
+            "docs",
+            "_build/html",
+            8000,
+            vec!["--open-browser".to_string()],
+        );
+        assert_eq!(
+            args,
+            vec![
+                "-m",
+                "sphinx_autobuild",
+                "/project/docs",
+                "/project/_build/html",
+                "--port",
+                "8000",
+                "--host",
+                "127.0.0.1",
+                "--open-browser",
+            ]
+        );
+    }
+
+    /// 受け取ったイベントを記録するシンク
+    fn recording_sink() -> (EventSink, Arc<Mutex<Vec<SphinxEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let sink: EventSink = Arc::new(move |event| recorded.lock().unwrap().push(event));
+        (sink, events)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stop_immediately_after_start() {
+        let dir = test_dir("cancel-start");
+        let python = fake_python(&dir, "exec sleep 30");
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                0,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+        assert!(manager.is_running("test"));
+        let pid = manager.processes["test"].child.id();
+
+        // 起動検出前に停止
+        manager.stop("test").unwrap();
+        assert!(!manager.is_running("test"));
+
+        // プロセスが残っていない（wait済みなのでシグナルを送れない）
+        let alive = Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success();
+        assert!(!alive);
+
+        // ポーリングスレッドはjoin済みで、停止後にイベントは届かない
+        std::thread::sleep(POLL_INTERVAL * 2);
+        assert!(events.lock().unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_once() {
+        let dir = test_dir("build-once");
+        // 引数: -m sphinx -b html -q <source> <out>
+        let python = fake_python(&dir, "mkdir -p \"$7\" && echo \"$6\" > \"$7/index.html\"");
+        let out_dir = dir.join("out");
+
+        build_once(&dir, "docs", &out_dir, &python, &SphinxEnv::default()).unwrap();
+        let index = std::fs::read_to_string(out_dir.join("index.html")).unwrap();
+        assert_eq!(index.trim(), dir.join("docs").to_string_lossy());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sphinx_command_forces_locale() {
+        let command = sphinx_command(Path::new("python3"));
+        let envs: HashMap<_, _> = command.get_envs().collect();
+        assert_eq!(envs[std::ffi::OsStr::new("LC_ALL")], Some("C".as_ref()));
+        assert_eq!(envs[std::ffi::OsStr::new("LANGUAGE")], Some("C".as_ref()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sphinx_env_apply() {
+        let run = |inherit: bool| {
+            let env = SphinxEnv {
+                vars: BTreeMap::from([("SPHINXOPTS".to_string(), "-W".to_string())]),
+                inherit,
+            };
+            let mut command = sphinx_command(Path::new("sh"));
+            env.apply(&mut command);
+            let output = command
+                .args(["-c", "echo \"$SPHINXOPTS|$LC_ALL|${HOME:-none}\""])
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let home = std::env::var("HOME").unwrap_or_else(|_| "none".to_string());
+        assert_eq!(run(true), format!("-W|C|{}", home));
+        // 引き継がない場合もPATHでコマンドを探せて、ロケールは固定される
+        assert_eq!(run(false), "-W|C|none");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_once_runs_with_c_locale() {
+        let dir = test_dir("build-once-locale");
+        let python = fake_python(
+            &dir,
+            "mkdir -p \"$7\" && echo \"$LC_ALL $LANGUAGE\" > \"$7/index.html\"",
+        );
+        let out_dir = dir.join("out");
+
+        build_once(&dir, "docs", &out_dir, &python, &SphinxEnv::default()).unwrap();
+        let index = std::fs::read_to_string(out_dir.join("index.html")).unwrap();
+        assert_eq!(index.trim(), "C C");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_once_reports_failure() {
+        let dir = test_dir("build-once-failure");
+        let python = fake_python(&dir, "echo 'source directory not found' >&2; exit 2");
+
+        let err = build_once(
+            &dir,
+            "docs",
+            &dir.join("out"),
+            &python,
+            &SphinxEnv::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("source directory not found"), "{}", err);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_serving_url() {
+        assert_eq!(
+            parse_serving_url("[sphinx-autobuild] Serving on http://127.0.0.1:8000"),
+            Some("http://127.0.0.1:8000")
+        );
+        assert_eq!(
+            parse_serving_url("Serving on http://127.0.0.1:5500/ (Press CTRL+C to quit)"),
+            Some("http://127.0.0.1:5500/")
+        );
+        assert_eq!(parse_serving_url("build succeeded."), None);
+        assert_eq!(parse_serving_url("Serving on port 8000"), None);
+    }
+
+    #[test]
+    fn test_url_port() {
+        assert_eq!(url_port("http://127.0.0.1:8000"), Some(8000));
+        assert_eq!(url_port("http://localhost:5500/index.html"), Some(5500));
+        assert_eq!(url_port("http://localhost/"), None);
+    }
+
+    #[test]
+    fn test_restart_nonexistent_session() {
+        let mut manager = SphinxManager::new();
+        assert!(manager.restart("nonexistent").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restart_reuses_start_params() {
+        let dir = test_dir("restart");
+        let log = dir.join("argv.log");
+        let python = fake_python(
+            &dir,
+            &format!("echo \"$PWD $*\" >> '{}'; exec sleep 30", log.display()),
+        );
+        let (sink, _events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        let port = manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                SphinxManager::find_available_port().unwrap(),
+                vec!["--watch".to_string(), "../src".to_string()],
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+        let pid = manager.processes["test"].child.id();
+
+        // 1回目の起動がスクリプトを実行し終えてから再起動する
+        let read_log = || std::fs::read_to_string(&log).unwrap_or_default();
+        (0..40)
+            .take_while(|_| read_log().lines().count() < 1)
+            .for_each(|_| std::thread::sleep(POLL_INTERVAL));
+        assert_eq!(manager.restart("test").unwrap(), port);
+        assert!(manager.is_running("test"));
+        assert_ne!(manager.processes["test"].child.id(), pid);
+
+        // 2回とも同じ作業ディレクトリと引数で起動している
+        (0..40)
+            .take_while(|_| read_log().lines().count() < 2)
+            .for_each(|_| std::thread::sleep(POLL_INTERVAL));
+        let log = read_log();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], lines[1]);
+        assert!(lines[0].contains("--watch ../src"));
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_started_uses_port_from_output() {
+        let dir = test_dir("served-port");
+        // 実際に配信しているポート（指定したポートとは異なる）
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let served = listener.local_addr().unwrap().port();
+        let python = fake_python(
+            &dir,
+            &format!(
+                "echo 'Serving on http://127.0.0.1:{}' >&2; exec sleep 30",
+                served
+            ),
+        );
+        let requested = SphinxManager::find_available_port().unwrap();
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                requested,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+
+        let started = (0..40).find_map(|_| {
+            std::thread::sleep(POLL_INTERVAL);
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|event| matches!(event, SphinxEvent::Started { .. }))
+                .cloned()
+        });
+        assert_eq!(
+            started,
+            Some(SphinxEvent::Started {
+                session_id: "test".to_string(),
+                port: served,
+            })
+        );
+        assert_eq!(manager.get_port("test"), Some(served));
+
+        // 出力はイベントで通知し、停止した後も取得できる
+        let serving = format!("Serving on http://127.0.0.1:{}", served);
+        assert!(events.lock().unwrap().contains(&SphinxEvent::LogLine {
+            session_id: "test".to_string(),
+            line: serving.clone(),
+            stream: LogStream::Stderr,
+        }));
+        manager.stop("test").unwrap();
+        assert_eq!(manager.get_log("test"), vec![serving]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exited_when_process_crashes() {
+        let dir = test_dir("crash");
+        let python = fake_python(
+            &dir,
+            "echo 'Traceback (most recent call last):' >&2; exit 1",
+        );
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                0,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+
+        // stderrのLogLine / Errorと前後しても、Exitedを待つ（最大10秒）
+        let exited = (0..40).find_map(|_| {
+            std::thread::sleep(POLL_INTERVAL);
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|event| matches!(event, SphinxEvent::Exited { .. }))
+                .cloned()
+        });
+        assert_eq!(
+            exited,
+            Some(SphinxEvent::Exited {
+                session_id: "test".to_string(),
+                port_in_use: false,
+            })
+        );
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_diagnostics_are_cleared_on_rebuild() {
+        let dir = test_dir("diagnostics");
+        let python = fake_python(
+            &dir,
+            "echo 'Running Sphinx v8.1.3'; sleep 0.2; \
+             echo '/docs/a.rst:1: WARNING: first build' >&2; sleep 0.2; \
+             echo 'Running Sphinx v8.1.3'; sleep 0.2; \
+             echo '/docs/b.rst:2: ERROR: second build' >&2; exec sleep 30",
+        );
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                0,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+
+        let count_diagnostics = || {
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|event| matches!(event, SphinxEvent::Diagnostic { .. }))
+                .count()
+        };
+        (0..40)
+            .take_while(|_| count_diagnostics() < 2)
+            .for_each(|_| std::thread::sleep(POLL_INTERVAL));
+
+        // 2回目のビルドの開始で1回目の警告は破棄される
+        assert_eq!(count_diagnostics(), 2);
+        assert_eq!(
+            manager.get_diagnostics("test"),
+            vec![SphinxDiagnostic {
+                file: "/docs/b.rst".to_string(),
+                line: Some(2),
+                severity: Severity::Error,
+                message: "second build".to_string(),
+            }]
+        );
+        let build_starts = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| matches!(event, SphinxEvent::BuildStarted { .. }))
+            .count();
+        assert_eq!(build_starts, 2);
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_is_address_in_use() {
+        assert!(is_address_in_use(
+            "OSError: [Errno 98] Address already in use"
+        ));
+        assert!(is_address_in_use(
+            "OSError: [Errno 48] Address already in use"
+        ));
+        assert!(is_address_in_use("OSError: [WinError 10048] Only one usage of each socket address (protocol/network address/port) is normally permitted"));
+        assert!(!is_address_in_use("build succeeded."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_start_fails_without_sphinx_autobuild() {
+        let dir = test_dir("no-autobuild");
+        let python = dir.join("python");
+        write_script(
+            &python,
+            "#!/bin/sh\necho \"ModuleNotFoundError: No module named 'sphinx_autobuild'\" >&2\nexit 1\n",
+        );
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        let error = manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python.to_string_lossy().to_string(),
+                0,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap_err();
+
+        assert!(error.contains("pip install sphinx-autobuild"));
+        assert!(error.contains(&python.display().to_string()));
+        assert!(!manager.is_running("test"));
+        assert!(events.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sphinx_autobuild_check_is_cached() {
+        let dir = test_dir("autobuild-check");
+        let log = dir.join("checks.log");
+        let python = fake_python(&dir, "exec sleep 30");
+        // 確認の呼び出しを記録する（fake_pythonの確認より前に差し込む）
+        let script = std::fs::read_to_string(&python).unwrap().replacen(
+            "#!/bin/sh\n",
+            &format!(
+                "#!/bin/sh\n[ \"$1\" = \"-c\" ] && echo check >> '{}'\n",
+                log.display()
+            ),
+            1,
+        );
+        std::fs::write(&python, script).unwrap();
+        let (sink, _events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        let start = |manager: &mut SphinxManager| {
+            manager
+                .start(
+                    "test".to_string(),
+                    dir.to_string_lossy().to_string(),
+                    "docs".to_string(),
+                    "_build/html".to_string(),
+                    python.clone(),
+                    0,
+                    Vec::new(),
+                    SphinxEnv::default(),
+                    LineClassifier::default(),
+                    Arc::clone(&sink),
+                )
+                .unwrap()
+        };
+        start(&mut manager);
+        manager.stop("test").unwrap();
+        start(&mut manager);
+        manager.restart("test").unwrap();
+
+        let checks = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(checks.lines().count(), 1);
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_drops_output_of_replaced_sessions() {
+        let dir = test_dir("replaced-sessions");
+        let python = fake_python(&dir, "echo started\nexec sleep 30");
+        let (sink, _events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        let start = |manager: &mut SphinxManager, session_id: &str| {
+            manager
+                .start(
+                    session_id.to_string(),
+                    dir.to_string_lossy().to_string(),
+                    "docs".to_string(),
+                    "_build/html".to_string(),
+                    python.clone(),
+                    0,
+                    Vec::new(),
+                    SphinxEnv::default(),
+                    LineClassifier::default(),
+                    Arc::clone(&sink),
+                )
+                .unwrap()
+        };
+        start(&mut manager, "old");
+        start(&mut manager, "running");
+        manager.stop("old").unwrap();
+        // 止めた直後は原因を確認できるよう残す
+        assert!(manager.logs.contains_key("old"));
+
+        start(&mut manager, "new");
+        let mut sessions: Vec<_> = manager.logs.keys().cloned().collect();
+        sessions.sort();
+        assert_eq!(sessions, ["new", "running"]);
+        let mut sessions: Vec<_> = manager.diagnostics.keys().cloned().collect();
+        sessions.sort();
+        assert_eq!(sessions, ["new", "running"]);
+
+        manager.stop("running").unwrap();
+        manager.stop("new").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sphinx_autobuild_check_uses_env() {
+        let dir = test_dir("autobuild-check-env");
+        // sphinx.envのPYTHONPATHで見つかる場合を想定し、環境変数があるときだけ確認が通る
+        let python = dir.join("python").to_string_lossy().to_string();
+        write_script(
+            Path::new(&python),
+            "#!/bin/sh\n[ \"$1\" = \"-c\" ] && { [ \"$KHAFRE_AUTOBUILD\" = 1 ]; exit $?; }\nexec sleep 30\n",
+        );
+        let (sink, _events) = recording_sink();
+        let with_env = SphinxEnv {
+            vars: BTreeMap::from([("KHAFRE_AUTOBUILD".to_string(), "1".to_string())]),
+            inherit: true,
+        };
+
+        let mut manager = SphinxManager::new();
+        let mut start = |env: SphinxEnv| {
+            manager.start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python.clone(),
+                0,
+                Vec::new(),
+                env,
+                LineClassifier::default(),
+                Arc::clone(&sink),
+            )
+        };
+        assert!(start(with_env).is_ok());
+        // 確認済みなのは環境変数との組なので、環境変数が違えば確認し直す
+        let error = start(SphinxEnv::default()).unwrap_err();
+        assert!(error.contains("pip install sphinx-autobuild"));
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_falls_back_when_requested_port_is_in_use() {
+        let dir = test_dir("port-fallback");
+        // 指定したポートを別のプロセスが使っている
+        let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+        let requested = taken.local_addr().unwrap().port();
+        let python = fake_python(&dir, "exec sleep 30");
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        let actual = manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                requested,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+
+        assert_ne!(actual, requested);
+        assert_eq!(
+            events.lock().unwrap().first(),
+            Some(&SphinxEvent::PortFallback {
+                session_id: "test".to_string(),
+                requested,
+                actual,
+            })
+        );
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exited_reports_port_in_use() {
+        let dir = test_dir("port-in-use");
+        // 確保したポートを起動までの間に別のプロセスに取られた
+        let python = fake_python(
+            &dir,
+            "echo 'OSError: [Errno 98] Address already in use' >&2; exit 1",
+        );
+        let (sink, events) = recording_sink();
+
+        let mut manager = SphinxManager::new();
+        manager
+            .start(
+                "test".to_string(),
+                dir.to_string_lossy().to_string(),
+                "docs".to_string(),
+                "_build/html".to_string(),
+                python,
+                0,
+                Vec::new(),
+                SphinxEnv::default(),
+                LineClassifier::default(),
+                sink,
+            )
+            .unwrap();
+
+        let exited = (0..40).find_map(|_| {
+            std::thread::sleep(POLL_INTERVAL);
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|event| matches!(event, SphinxEvent::Exited { .. }))
+                .cloned()
+        });
+        assert_eq!(
+            exited,
+            Some(SphinxEvent::Exited {
+                session_id: "test".to_string(),
+                port_in_use: true,
+            })
+        );
+
+        manager.stop("test").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reserved_port_is_not_reassigned() {
+        let (reserved, _listener) = SphinxManager::reserve_port().unwrap();
+        // 確保中のポートは次の検索で選ばれない
+        for _ in 0..20 {
+            assert_ne!(SphinxManager::find_available_port().unwrap(), reserved);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_argv_non_utf8_project_path() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let project = Path::new(std::ffi::OsStr::from_bytes(b"/docs-\xff"));
+        let args = build_argv(project, "docs", "_build/html", 8000, Vec::new());
+        assert_eq!(args[2], project.join("docs").into_os_string());
+        assert_eq!(args[3], project.join("_build/html").into_os_string());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_once_non_utf8_project_path() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut bytes = b"khafre-".to_vec();
+        bytes.push(0xff);
+        bytes.extend(format!("-{}", std::process::id()).bytes());
+        let dir = std::env::temp_dir().join(std::ffi::OsStr::from_bytes(&bytes));
+        std::fs::create_dir_all(&dir).unwrap();
+        fake_python(&dir, "mkdir -p \"$7\" && touch \"$7/index.html\"");
+        let out_dir = dir.join("out");
+
+        // 相対パスのPythonもUTF-8でないプロジェクトを基準に解決できる
+        build_once(&dir, "docs", &out_dir, "python", &SphinxEnv::default()).unwrap();
+        assert!(out_dir.join("index.html").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,6 +1,9 @@
+use crate::recording::Recorder;
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
 use std::collections::HashMap;
-use std::io::{Read, Write};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -23,13 +26,91 @@ fn detect_shell(config_shell: Option<&str>) -> String {
     "/bin/sh".to_string()
 }
 
+/// PTYで起動するシェルコマンドを構築
+/// 24bitカラー対応をCOLORTERMで通知する（SGR 38;2/48;2の解釈はxterm.js側）
+fn build_shell_command(shell_path: &str, cwd: Option<&str>) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new(shell_path);
+    cmd.arg("-l");
+
+    if let Some(dir) = cwd {
+        cmd.cwd(dir);
+    }
+
+    cmd.env("TERM", "xterm-256color");
+    cmd.env("COLORTERM", "truecolor");
+    cmd.env("SHELL", shell_path);
+    cmd
+}
+
+/// 末尾の不完全なUTF-8シーケンスのバイト数を返す
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    // 末尾から最大3バイト遡って先頭バイトを探す
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0b1100_0000 == 0b1000_0000 {
+            // 継続バイト
+            continue;
+        }
+        let needed = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if needed > back { back } else { 0 };
+    }
+    0
+}
+
+/// PTY出力をUTF-8文字列に変換
+/// 読み取り境界で分断されたマルチバイト文字（ZWJ絵文字など）は次回の読み取りまで保持する
+fn decode_utf8_chunk(pending: &mut Vec<u8>, input: &[u8]) -> String {
+    pending.extend_from_slice(input);
+    let complete = pending.len() - incomplete_utf8_tail(pending);
+    let rest = pending.split_off(complete);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    text
+}
+
+/// PTYセッション生成時のエラー
+#[derive(Debug)]
+pub enum SpawnError {
+    /// 擬似端末を確保できない（リソース枯渇やサンドボックス環境）
+    PtyUnavailable(String),
+    /// シェルの起動に失敗
+    Command(String),
+    /// PTYの入出力ハンドル取得に失敗
+    Io(String),
+}
+
+impl std::fmt::Display for SpawnError {
+    /// UIにそのまま表示できるメッセージ
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpawnError::PtyUnavailable(detail) => write!(
+                f,
+                "No pseudo-terminals available. Close other terminal sessions or check sandbox restrictions. ({})",
+                detail
+            ),
+            SpawnError::Command(detail) => write!(f, "Failed to spawn command: {}", detail),
+            SpawnError::Io(detail) => write!(f, "Failed to set up terminal I/O: {}", detail),
+        }
+    }
+}
+
+/// 録画中の出力先（読み取りスレッドと共有する）
+type SharedRecorder = Arc<Mutex<Option<Recorder<BufWriter<File>>>>>;
+
+/// シェルのプロセス（終了コードを取得するため読み取りスレッドと共有する）
+type SharedChild = Arc<Mutex<Box<dyn Child + Send + Sync>>>;
+
 /// PTYセッションを管理する構造体
 pub struct PtySession {
     writer: Box<dyn Write + Send>,
     size: PtySize,
-    #[allow(dead_code)]
-    child: Box<dyn Child + Send + Sync>,
-    #[allow(dead_code)]
+    recorder: SharedRecorder,
+    child: SharedChild,
     master: Box<dyn MasterPty + Send>,
 }
 
@@ -60,7 +141,7 @@ impl TerminalManager {
         cols: u16,
         rows: u16,
         app_handle: AppHandle,
-    ) -> Result<(), String> {
+    ) -> Result<(), SpawnError> {
         // 既に同じセッションが存在する場合はスキップ（React StrictMode対策）
         if self.sessions.contains_key(&session_id) {
             return Ok(());
@@ -77,25 +158,16 @@ impl TerminalManager {
 
         let pair = pty_system
             .openpty(size)
-            .map_err(|e| format!("Failed to open pty: {}", e))?;
+            .map_err(|e| SpawnError::PtyUnavailable(e.to_string()))?;
 
         // シェルを検出してログインシェルとして起動
         let shell_path = detect_shell(shell.as_deref());
-        let mut cmd = CommandBuilder::new(&shell_path);
-        cmd.arg("-l");
-
-        if let Some(ref dir) = cwd {
-            cmd.cwd(dir);
-        }
-
-        cmd.env("TERM", "xterm-256color");
-        cmd.env("COLORTERM", "truecolor");
-        cmd.env("SHELL", &shell_path);
+        let cmd = build_shell_command(&shell_path, cwd.as_deref());
 
         let child = pair
             .slave
             .spawn_command(cmd)
-            .map_err(|e| format!("Failed to spawn command: {}", e))?;
+            .map_err(|e| SpawnError::Command(e.to_string()))?;
 
         // macOS: spawn後の短いスリープでレースコンディション回避
         thread::sleep(Duration::from_millis(50));
@@ -106,17 +178,20 @@ impl TerminalManager {
         let mut reader = pair
             .master
             .try_clone_reader()
-            .map_err(|e| format!("Failed to clone reader: {}", e))?;
+            .map_err(|e| SpawnError::Io(format!("clone reader: {}", e)))?;
 
         let writer = pair
             .master
             .take_writer()
-            .map_err(|e| format!("Failed to take writer: {}", e))?;
+            .map_err(|e| SpawnError::Io(format!("take writer: {}", e)))?;
 
+        let recorder = SharedRecorder::default();
+        let child: SharedChild = Arc::new(Mutex::new(child));
         let session = PtySession {
             writer,
             size,
-            child,
+            recorder: recorder.clone(),
+            child: child.clone(),
             master: pair.master,
         };
         self.sessions.insert(session_id.clone(), session);
@@ -126,17 +201,26 @@ impl TerminalManager {
 
         thread::spawn(move || {
             let mut buffer = [0u8; 4096];
+            let mut pending = Vec::new();
 
             loop {
                 match reader.read(&mut buffer) {
                     Ok(0) => {
-                        let _ = app_handle.emit("pty_exit", (&sid, 0));
+                        // 途中で終わったシーケンスは置換文字として送る
+                        if !pending.is_empty() {
+                            let data = String::from_utf8_lossy(&pending).to_string();
+                            let _ = app_handle.emit("pty_data", (&sid, data));
+                        }
+                        let _ = app_handle.emit("pty_exit", (&sid, wait_exit_code(&child)));
                         break;
                     }
                     Ok(n) => {
                         // 読み取ったデータを即座に送信
-                        let data = String::from_utf8_lossy(&buffer[..n]).to_string();
-                        let _ = app_handle.emit("pty_data", (&sid, data));
+                        let data = decode_utf8_chunk(&mut pending, &buffer[..n]);
+                        if !data.is_empty() {
+                            record_output(&recorder, &data);
+                            let _ = app_handle.emit("pty_data", (&sid, data));
+                        }
                     }
                     Err(_) => {
                         let _ = app_handle.emit("pty_exit", (&sid, 1));
@@ -189,6 +273,45 @@ impl TerminalManager {
         Ok(())
     }
 
+    /// 出力の録画を開始（asciicast v2形式でpathに書き出す）
+    pub fn start_recording(&mut self, session_id: &str, path: &Path) -> Result<(), String> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        let file = File::create(path).map_err(|e| format!("Failed to create recording: {}", e))?;
+        let recorder = Recorder::start(BufWriter::new(file), session.size.cols, session.size.rows)?;
+        *session.recorder.lock().map_err(|e| e.to_string())? = Some(recorder);
+        Ok(())
+    }
+
+    /// 録画を終了してファイルを保存
+    pub fn stop_recording(&mut self, session_id: &str) -> Result<(), String> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        let recorder = session.recorder.lock().map_err(|e| e.to_string())?.take();
+        match recorder {
+            Some(recorder) => recorder.finish().map(|_| ()),
+            None => Err("Not recording".to_string()),
+        }
+    }
+
+    /// シェルからコマンド（エディタやビルドなど）をフォアグラウンドで実行中か
+    pub fn has_foreground_job(&self, session_id: &str) -> Result<bool, String> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        Ok(foreground_job_running(
+            session.master.as_ref(),
+            &session.child,
+        ))
+    }
+
     /// セッションを終了
     pub fn kill(&mut self, session_id: &str) -> Result<(), String> {
         self.sessions
@@ -198,6 +321,44 @@ impl TerminalManager {
     }
 }
 
+/// 端末のフォアグラウンドのプロセスグループがシェル自身でなければ、コマンドを実行中とみなす
+#[cfg(unix)]
+fn foreground_job_running(master: &dyn MasterPty, child: &SharedChild) -> bool {
+    let shell_pid = child.lock().ok().and_then(|child| child.process_id());
+    match (master.process_group_leader(), shell_pid) {
+        (Some(leader), Some(shell_pid)) => leader as u32 != shell_pid,
+        _ => false,
+    }
+}
+
+/// プロセスグループを取得できない環境では常に実行中でないものとする
+#[cfg(not(unix))]
+fn foreground_job_running(_master: &dyn MasterPty, _child: &SharedChild) -> bool {
+    false
+}
+
+/// シェルの終了を待って終了コードを返す（取得できなければ1）
+fn wait_exit_code(child: &SharedChild) -> u32 {
+    child
+        .lock()
+        .ok()
+        .and_then(|mut child| child.wait().ok())
+        .map(|status| status.exit_code())
+        .unwrap_or(1)
+}
+
+/// 録画中なら出力を記録（書き込みに失敗したら録画をやめる）
+fn record_output(recorder: &SharedRecorder, data: &str) {
+    let Ok(mut recorder) = recorder.lock() else {
+        return;
+    };
+    if let Some(active) = recorder.as_mut() {
+        if active.output(data).is_err() {
+            *recorder = None;
+        }
+    }
+}
+
 /// グローバルなTerminalManagerへのアクセス用
 pub type SharedTerminalManager = Arc<Mutex<TerminalManager>>;
 
@@ -230,6 +391,59 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_recording_nonexistent_session() {
+        let mut manager = TerminalManager::new();
+        let path = std::env::temp_dir().join("khafre-no-session.cast");
+        assert!(manager.start_recording("nonexistent", &path).is_err());
+        assert!(manager.stop_recording("nonexistent").is_err());
+    }
+
+    #[test]
+    fn test_wait_exit_code() {
+        let pair = native_pty_system().openpty(PtySize::default()).unwrap();
+        let mut cmd = CommandBuilder::new("/bin/sh");
+        cmd.args(["-c", "exit 3"]);
+        let child: SharedChild = Arc::new(Mutex::new(pair.slave.spawn_command(cmd).unwrap()));
+        assert_eq!(wait_exit_code(&child), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_foreground_job_running() {
+        let pair = native_pty_system().openpty(PtySize::default()).unwrap();
+        let mut cmd = CommandBuilder::new("/bin/sh");
+        cmd.arg("-i");
+        cmd.env("PS1", "$ ");
+        let child: SharedChild = Arc::new(Mutex::new(pair.slave.spawn_command(cmd).unwrap()));
+        // 出力で端末のバッファが詰まらないよう読み捨てる
+        let mut reader = pair.master.try_clone_reader().unwrap();
+        std::thread::spawn(move || std::io::copy(&mut reader, &mut std::io::sink()));
+        let mut writer = pair.master.take_writer().unwrap();
+
+        let wait_until = |expected: bool| {
+            let deadline = std::time::Instant::now() + Duration::from_secs(5);
+            while foreground_job_running(pair.master.as_ref(), &child) != expected {
+                assert!(
+                    std::time::Instant::now() < deadline,
+                    "expected {}",
+                    expected
+                );
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        };
+        // プロンプトを表示している間は実行中でない
+        wait_until(false);
+        writer.write_all(b"sleep 10\n").unwrap();
+        wait_until(true);
+
+        // Ctrl+Cでコマンドを終了するとシェルに戻る
+        writer.write_all(b"\x03").unwrap();
+        wait_until(false);
+        writer.write_all(b"exit\n").unwrap();
+        let _ = child.lock().unwrap().wait();
+    }
+
     #[test]
     fn test_kill_nonexistent_session() {
         let mut manager = TerminalManager::new();
@@ -237,6 +451,74 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_pty_unavailable_message() {
+        let error = SpawnError::PtyUnavailable("out of pty devices".to_string());
+        let message = error.to_string();
+        assert!(message.starts_with("No pseudo-terminals available"));
+        assert!(message.contains("out of pty devices"));
+    }
+
+    #[test]
+    fn test_spawn_error_messages_by_kind() {
+        let command = SpawnError::Command("No such file".to_string());
+        assert_eq!(command.to_string(), "Failed to spawn command: No such file");
+        let io = SpawnError::Io("take writer: closed".to_string());
+        assert_eq!(
+            io.to_string(),
+            "Failed to set up terminal I/O: take writer: closed"
+        );
+    }
+
+    #[test]
+    fn test_shell_command_advertises_truecolor() {
+        let cmd = build_shell_command("/bin/zsh", Some("/tmp"));
+        assert_eq!(
+            cmd.get_env("COLORTERM").and_then(|v| v.to_str()),
+            Some("truecolor")
+        );
+        assert_eq!(
+            cmd.get_env("TERM").and_then(|v| v.to_str()),
+            Some("xterm-256color")
+        );
+        assert_eq!(
+            cmd.get_env("SHELL").and_then(|v| v.to_str()),
+            Some("/bin/zsh")
+        );
+    }
+
+    #[test]
+    fn test_decode_zwj_sequence_split_across_reads() {
+        // 家族の絵文字（ZWJ結合）と肌の色修飾子
+        let text = "a👨\u{200d}👩\u{200d}👧b👍🏽";
+        let bytes = text.as_bytes();
+
+        // あらゆる位置で分割しても元の文字列に戻る
+        for split in 0..=bytes.len() {
+            let mut pending = Vec::new();
+            let mut decoded = decode_utf8_chunk(&mut pending, &bytes[..split]);
+            decoded.push_str(&decode_utf8_chunk(&mut pending, &bytes[split..]));
+            assert_eq!(decoded, text, "split at {}", split);
+            assert!(pending.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_decode_holds_incomplete_tail() {
+        let mut pending = Vec::new();
+        let emoji = "👍".as_bytes();
+        assert_eq!(decode_utf8_chunk(&mut pending, &emoji[..2]), "");
+        assert_eq!(pending.len(), 2);
+        assert_eq!(decode_utf8_chunk(&mut pending, &emoji[2..]), "👍");
+    }
+
+    #[test]
+    fn test_decode_replaces_invalid_bytes() {
+        let mut pending = Vec::new();
+        assert_eq!(decode_utf8_chunk(&mut pending, b"a\xffb"), "a\u{fffd}b");
+        assert!(pending.is_empty());
+    }
+
     #[test]
     fn test_detect_shell_with_config() {
         // 設定値が優先される
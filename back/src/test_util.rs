@@ -0,0 +1,47 @@
+//! テスト用の共通ヘルパー
+
+use std::path::{Path, PathBuf};
+
+/// テスト用の一時ディレクトリを作成（前回のテストで残ったものは消す）
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("khafre-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// ファイルを書き込む（親ディレクトリが無ければ作る）
+pub fn write_file(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// ファイル名と内容の組からフィクスチャのディレクトリを作成
+pub fn fixture(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = test_dir(name);
+    for (file, content) in files {
+        write_file(&dir.join(file), content);
+    }
+    dir
+}
+
+/// 実行可能なシェルスクリプトを作成
+#[cfg(unix)]
+pub fn write_script(path: &Path, content: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::write(path, content).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Pythonの代わりに起動する実行可能スクリプトを作成
+/// 起動前のsphinx-autobuildの確認（-c "import sphinx_autobuild"）は成功させる
+#[cfg(unix)]
+pub fn fake_python(dir: &Path, body: &str) -> String {
+    let path = dir.join("python");
+    write_script(
+        &path,
+        &format!("#!/bin/sh\n[ \"$1\" = \"-c\" ] && exit 0\n{}\n", body),
+    );
+    path.to_string_lossy().to_string()
+}